@@ -1,7 +1,7 @@
 use log::info;
 use serde::Deserialize;
-use squawk_linter::{versions::Version, violations::RuleViolationKind};
-use std::{env, io, path::Path, path::PathBuf};
+use squawk_linter::{config::RuleOptions, versions::Version, violations::RuleViolationKind};
+use std::{collections::HashMap, env, io, path::Path, path::PathBuf};
 
 const FILE_NAME: &str = ".squawk.toml";
 
@@ -54,6 +54,9 @@ pub struct Config {
     pub assume_in_transaction: Option<bool>,
     #[serde(default)]
     pub upload_to_github: UploadToGitHubConfig,
+    /// Options for specific rules, e.g. `[rules.prefer-text-field]`.
+    #[serde(default)]
+    pub rules: HashMap<RuleViolationKind, RuleOptions>,
 }
 
 impl Config {
@@ -166,4 +169,22 @@ fail_on_violations = true
         fs::write(&squawk_toml, file).expect("Unable to write file");
         assert_debug_snapshot!(Config::parse(Some(squawk_toml.path().to_path_buf())));
     }
+    #[test]
+    fn test_load_rule_options() {
+        let squawk_toml = NamedTempFile::new().expect("generate tempFile");
+        let file = r#"
+[rules.prefer-text-field]
+max_allowed_length = 32
+        "#;
+        fs::write(&squawk_toml, file).expect("Unable to write file");
+        let config = Config::parse(Some(squawk_toml.path().to_path_buf()))
+            .unwrap()
+            .unwrap();
+        let mut options = RuleOptions::new();
+        options.insert("max_allowed_length".into(), 32.into());
+        assert_eq!(
+            config.rules,
+            HashMap::from([(RuleViolationKind::PreferTextField, options)])
+        );
+    }
 }
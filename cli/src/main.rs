@@ -18,8 +18,11 @@ use atty::Stream;
 use config::Config;
 use log::info;
 use simplelog::CombinedLogger;
+use squawk_linter::config::CheckSqlConfig;
+use squawk_linter::diff::AddedLines;
 use squawk_linter::versions::Version;
 use squawk_linter::violations::RuleViolationKind;
+use std::fs;
 use std::io;
 use std::path::PathBuf;
 use std::process;
@@ -81,8 +84,12 @@ struct Opt {
     #[structopt(long, possible_values = &Reporter::variants(), case_insensitive = true)]
     reporter: Option<Reporter>,
     #[structopt(long, value_name = "filepath")]
-    /// Path to use in reporting for stdin
+    /// Path to use in reporting for stdin, defaults to `<stdin>`
     stdin_filepath: Option<String>,
+    /// Only report violations for statements on lines added by the unified diff
+    /// at this path, e.g. the output of `git diff`
+    #[structopt(long, value_name = "filepath")]
+    diff: Option<PathBuf>,
     #[structopt(subcommand)]
     cmd: Option<Command>,
     /// Enable debug logging output
@@ -199,13 +206,26 @@ fn main() {
                 "Failed to dump AST",
             );
         } else {
+            let added_lines = opts.diff.map(|diff_path| {
+                let diff = fs::read_to_string(&diff_path).unwrap_or_else(|e| {
+                    eprintln!("Failed to read diff {}: {e}", diff_path.display());
+                    process::exit(1);
+                });
+                AddedLines::parse(&diff)
+            });
+            let config = CheckSqlConfig {
+                excluded_rules,
+                pg_version,
+                assume_in_transaction,
+                rules: conf.rules,
+                ..CheckSqlConfig::default()
+            };
             match check_files(
                 &found_paths,
                 read_stdin,
                 opts.stdin_filepath,
-                &excluded_rules,
-                pg_version,
-                assume_in_transaction,
+                &config,
+                added_lines.as_ref(),
             ) {
                 Ok(file_reports) => {
                     let reporter = opts.reporter.unwrap_or(Reporter::Tty);
@@ -2,17 +2,21 @@ use console::strip_ansi_codes;
 use console::style;
 use log::info;
 use serde::Serialize;
+use serde_json::{json, Value};
+use squawk_linter::config::CheckSqlConfig;
+use squawk_linter::diff::AddedLines;
 use squawk_linter::errors::CheckSqlError;
-use squawk_linter::versions::Version;
-use squawk_linter::violations::{RuleViolation, RuleViolationKind, Span, ViolationMessage};
-use squawk_linter::{check_sql, SquawkRule, RULES};
+use squawk_linter::violations::{
+    LockLevel, RuleViolation, RuleViolationKind, Severity, Span, ViolationMessage,
+};
+use squawk_linter::{check_sql, rule_by_kind, SquawkRule, RULES};
 use squawk_parser::error::PgQueryError;
 use squawk_parser::parse::{parse_sql_query, parse_sql_query_json};
 use std::convert::TryFrom;
 use std::fs::File;
 use std::io;
 use std::io::prelude::*;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use structopt::clap::arg_enum;
 use structopt::StructOpt;
 
@@ -133,12 +137,17 @@ impl std::convert::From<CheckSqlError> for CheckFilesError {
 fn process_violations(
     sql: &str,
     path: &str,
-    excluded_rules: &[RuleViolationKind],
-    pg_version: Option<Version>,
-    assume_in_transaction: bool,
+    config: &CheckSqlConfig,
+    added_lines: Option<&AddedLines>,
 ) -> ViolationContent {
-    match check_sql(sql, excluded_rules, pg_version, assume_in_transaction) {
-        Ok(violations) => pretty_violations(violations, sql, path),
+    match check_sql(sql, config) {
+        Ok(violations) => {
+            let violations = match added_lines {
+                Some(added_lines) => added_lines.filter(Path::new(path), sql, violations),
+                None => violations,
+            };
+            pretty_violations(violations, sql, path)
+        }
         Err(err) => ViolationContent {
             filename: path.into(),
             sql: sql.into(),
@@ -147,6 +156,8 @@ fn process_violations(
                 file: path.into(),
                 level: ViolationLevel::Error,
                 line: 0,
+                location: (1, 1),
+                lock_level: None,
                 messages: vec![
                     ViolationMessage::Note(err.to_string()),
                     ViolationMessage::Help(
@@ -160,13 +171,16 @@ fn process_violations(
     }
 }
 
+/// Name used in the output for SQL read from stdin, unless overridden with
+/// `--stdin-filepath`.
+pub const STDIN_FILENAME: &str = "<stdin>";
+
 pub fn check_files(
     path_patterns: &[PathBuf],
     read_stdin: bool,
     stdin_path: Option<String>,
-    excluded_rules: &[RuleViolationKind],
-    pg_version: Option<Version>,
-    assume_in_transaction: bool,
+    config: &CheckSqlConfig,
+    added_lines: Option<&AddedLines>,
 ) -> Result<Vec<ViolationContent>, CheckFilesError> {
     let mut output_violations = vec![];
 
@@ -177,14 +191,8 @@ pub fn check_files(
         if sql.trim().is_empty() {
             info!("ignoring empty stdin");
         } else {
-            let path = stdin_path.unwrap_or_else(|| "stdin".into());
-            output_violations.push(process_violations(
-                &sql,
-                &path,
-                excluded_rules,
-                pg_version,
-                assume_in_transaction,
-            ));
+            let path = stdin_path.unwrap_or_else(|| STDIN_FILENAME.into());
+            output_violations.push(process_violations(&sql, &path, config, added_lines));
         }
     }
 
@@ -194,9 +202,8 @@ pub fn check_files(
         output_violations.push(process_violations(
             &sql,
             path.to_str().unwrap(),
-            excluded_rules,
-            pg_version,
-            assume_in_transaction,
+            config,
+            added_lines,
         ));
     }
     Ok(output_violations)
@@ -216,18 +223,52 @@ arg_enum! {
         Tty,
         Gcc,
         Json,
+        Sarif,
+        Github,
     }
 }
 
 #[derive(Debug, Serialize)]
 pub enum ViolationLevel {
+    Info,
     Warning,
     Error,
 }
 
+impl ViolationLevel {
+    /// The SARIF `level` of results with this level.
+    const fn sarif_level(&self) -> &'static str {
+        match self {
+            Self::Info => "note",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+
+    /// The GitHub Actions workflow command for annotations with this level.
+    const fn github_command(&self) -> &'static str {
+        match self {
+            Self::Info => "notice",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl std::convert::From<Severity> for ViolationLevel {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Info => Self::Info,
+            Severity::Warning => Self::Warning,
+            Severity::Error => Self::Error,
+        }
+    }
+}
+
 impl std::fmt::Display for ViolationLevel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let val = match self {
+            Self::Info => "info",
             Self::Warning => "warning",
             Self::Error => "error",
         };
@@ -240,7 +281,13 @@ pub struct ReportViolation {
     pub file: String,
     pub line: usize,
     pub column: usize,
+    /// 1-indexed line and column of the statement, from
+    /// `RuleViolation::location`.
+    #[serde(skip_serializing)]
+    pub location: (usize, usize),
     pub level: ViolationLevel,
+    /// The table lock the statement takes, when the rule knows it.
+    pub lock_level: Option<LockLevel>,
     pub messages: Vec<ViolationMessage>,
     pub rule_name: RuleViolationKind,
     // don't output in JSON format
@@ -259,7 +306,9 @@ fn fmt_gcc<W: io::Write>(
                 .iter()
                 .map(|v| {
                     match v {
-                        ViolationMessage::Note(s) | ViolationMessage::Help(s) => s,
+                        ViolationMessage::Note(s)
+                        | ViolationMessage::Help(s)
+                        | ViolationMessage::Url(s) => s,
                     }
                     .to_string()
                 })
@@ -285,6 +334,7 @@ pub fn fmt_tty_violation<W: io::Write>(
     violation: &ReportViolation,
 ) -> Result<(), std::io::Error> {
     let violation_level = match violation.level {
+        ViolationLevel::Info => style(format!("{}", violation.level)).cyan(),
         ViolationLevel::Warning => style(format!("{}", violation.level)).yellow(),
         ViolationLevel::Error => style(format!("{}", violation.level)).red(),
     };
@@ -308,6 +358,9 @@ pub fn fmt_tty_violation<W: io::Write>(
             ViolationMessage::Help(help) => {
                 writeln!(f, "  {}: {}", style("help").bold(), help)?;
             }
+            ViolationMessage::Url(url) => {
+                writeln!(f, "  {}: {}", style("docs").bold(), url)?;
+            }
         }
     }
     writeln!(f)
@@ -350,6 +403,228 @@ fn fmt_json<W: io::Write>(
     writeln!(f, "{json_str}")
 }
 
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+fn join_messages(messages: &[ViolationMessage], note: bool) -> String {
+    messages
+        .iter()
+        .filter_map(|msg| match msg {
+            ViolationMessage::Note(s) if note => Some(s.as_str()),
+            ViolationMessage::Help(s) | ViolationMessage::Url(s) if !note => Some(s.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn sarif_rule(rule: &SquawkRule) -> Value {
+    let name = rule.name.to_string();
+    json!({
+        "id": name,
+        "shortDescription": { "text": name },
+        "fullDescription": { "text": join_messages(&rule.messages, true) },
+        "help": { "text": join_messages(&rule.messages, false) },
+        "helpUri": rule.name.doc_url(),
+        "defaultConfiguration": { "level": ViolationLevel::from(rule.severity).sarif_level() },
+    })
+}
+
+/// A SARIF result for a violation at the 1-indexed `location` of `file`.
+fn sarif_result(
+    rule_name: &RuleViolationKind,
+    level: &ViolationLevel,
+    messages: &[ViolationMessage],
+    file: &str,
+    location: (usize, usize),
+) -> Value {
+    let message = messages
+        .iter()
+        .map(|v| match v {
+            ViolationMessage::Note(s) | ViolationMessage::Help(s) | ViolationMessage::Url(s) => {
+                s.as_str()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    json!({
+        "ruleId": rule_name.to_string(),
+        "level": level.sarif_level(),
+        "message": { "text": message },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": { "uri": file },
+                // SARIF lines and columns are 1-indexed.
+                "region": {
+                    "startLine": location.0,
+                    "startColumn": location.1,
+                },
+            },
+        }],
+    })
+}
+
+/// Output a SARIF 2.1.0 log with every rule and the `results`.
+///
+/// <https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html>
+fn fmt_sarif_log<W: io::Write>(
+    f: &mut W,
+    results: Vec<Value>,
+) -> std::result::Result<(), std::io::Error> {
+    let sarif = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "squawk",
+                    "informationUri": "https://squawkhq.com",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": RULES.iter().map(sarif_rule).collect::<Vec<_>>(),
+                },
+            },
+            "results": results,
+        }],
+    });
+    let json_str = serde_json::to_string(&sarif)?;
+    writeln!(f, "{json_str}")
+}
+
+fn fmt_sarif<W: io::Write>(
+    f: &mut W,
+    files: &[ViolationContent],
+) -> std::result::Result<(), std::io::Error> {
+    let results = files
+        .iter()
+        .flat_map(|file| &file.violations)
+        .map(|violation| {
+            sarif_result(
+                &violation.rule_name,
+                &violation.level,
+                &violation.messages,
+                &violation.file,
+                violation.location,
+            )
+        })
+        .collect();
+    fmt_sarif_log(f, results)
+}
+
+/// Escape data for a GitHub Actions workflow command.
+///
+/// <https://github.com/actions/toolkit/blob/main/packages/core/src/command.ts>
+fn escape_github_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_github_property(s: &str) -> String {
+    escape_github_data(s)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+fn fmt_github_annotation<W: io::Write>(
+    f: &mut W,
+    level: &ViolationLevel,
+    file: &str,
+    line: usize,
+    column: usize,
+    rule_name: &RuleViolationKind,
+    messages: &[ViolationMessage],
+) -> std::result::Result<(), std::io::Error> {
+    let message = messages
+        .iter()
+        .map(|v| match v {
+            ViolationMessage::Note(s) | ViolationMessage::Help(s) | ViolationMessage::Url(s) => {
+                s.as_str()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    writeln!(
+        f,
+        "::{} file={},line={},col={}::{}",
+        level.github_command(),
+        escape_github_property(file),
+        line,
+        column,
+        escape_github_data(&format!("{rule_name}: {message}"))
+    )
+}
+
+fn fmt_github<W: io::Write>(
+    f: &mut W,
+    files: &[ViolationContent],
+) -> std::result::Result<(), std::io::Error> {
+    for file in files {
+        for violation in &file.violations {
+            let (line, column) = violation.location;
+            fmt_github_annotation(
+                f,
+                &violation.level,
+                &violation.file,
+                line,
+                column,
+                &violation.rule_name,
+                &violation.messages,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Output violations as GitHub Actions workflow commands, which show up as
+/// annotations on the pull request diff.
+///
+/// `sql` is the content of `path` and is used to find the line and column of
+/// each violation.
+pub fn fmt_github_annotations<W: io::Write>(
+    f: &mut W,
+    violations: &[RuleViolation],
+    path: &str,
+    sql: &str,
+) -> std::result::Result<(), std::io::Error> {
+    for violation in violations {
+        let (line, column) = violation.location(sql);
+        fmt_github_annotation(
+            f,
+            &violation.severity.into(),
+            path,
+            line,
+            column,
+            &violation.kind,
+            &violation.messages,
+        )?;
+    }
+    Ok(())
+}
+
+/// Output violations in SARIF 2.1.0 for GitHub code scanning.
+///
+/// `sql` is the content of `path` and is used to find the line and column of
+/// each violation.
+pub fn fmt_sarif_violations<W: io::Write>(
+    f: &mut W,
+    violations: &[RuleViolation],
+    path: &str,
+    sql: &str,
+) -> std::result::Result<(), std::io::Error> {
+    let results = violations
+        .iter()
+        .map(|violation| {
+            sarif_result(
+                &violation.kind,
+                &violation.severity.into(),
+                &violation.messages,
+                path,
+                violation.location(sql),
+            )
+        })
+        .collect();
+    fmt_sarif_log(f, results)
+}
+
 #[derive(Debug)]
 pub struct ViolationContent {
     pub filename: String,
@@ -391,11 +666,15 @@ pub fn pretty_violations(
                 // slice off the beginning new lines
                 let problem_sql = &content[col..];
 
+                let location = violation.location(sql);
+
                 ReportViolation {
                     file: filename.into(),
                     line: lineno,
                     column: col,
-                    level: ViolationLevel::Warning,
+                    location,
+                    level: violation.severity.into(),
+                    lock_level: violation.lock_level,
                     messages: violation.messages,
                     rule_name: violation.kind,
                     sql: problem_sql.into(),
@@ -413,6 +692,8 @@ pub fn print_violations<W: io::Write>(
     match reporter {
         Reporter::Gcc => fmt_gcc(writer, &file_reports),
         Reporter::Json => fmt_json(writer, file_reports),
+        Reporter::Sarif => fmt_sarif(writer, &file_reports),
+        Reporter::Github => fmt_github(writer, &file_reports),
         Reporter::Tty => fmt_tty(writer, &file_reports),
     }
 }
@@ -430,6 +711,7 @@ fn output_rule_info<W: io::Write>(writer: &mut W, rule: &SquawkRule) -> Result<(
         let msg_content = match msg {
             ViolationMessage::Note(s) => format!("note: {s}"),
             ViolationMessage::Help(s) => format!("help: {s}"),
+            ViolationMessage::Url(s) => format!("docs: {s}"),
         };
         writeln!(writer, "    {msg_content}")?;
     }
@@ -438,8 +720,11 @@ fn output_rule_info<W: io::Write>(writer: &mut W, rule: &SquawkRule) -> Result<(
 
 pub fn explain_rule<W: io::Write>(writer: &mut W, name: &str) -> Result<(), std::io::Error> {
     if let Ok(name) = RuleViolationKind::try_from(name) {
-        if let Some(r) = RULES.iter().find(|r| r.name == name) {
+        if let Some(r) = rule_by_kind(&name) {
             output_rule_info(writer, r)?;
+            if let Some(explanation) = r.explanation {
+                writeln!(writer, "\n{explanation}")?;
+            }
         }
     }
     Ok(())
@@ -551,7 +836,9 @@ SELECT 1;
                 file: "alpha.sql".into(),
                 line: 1,
                 column: 0,
+                location: (2, 1),
                 level: ViolationLevel::Warning,
+                lock_level: Some(LockLevel::AccessExclusive),
                 messages: vec![
                     ViolationMessage::Note(
                         "Adding a NOT NULL field requires exclusive locks and table rewrites."
@@ -621,9 +908,10 @@ mod test_check_files {
     use insta::assert_yaml_snapshot;
     use serde_json::Value;
 
-    use crate::reporter::fmt_json;
+    use crate::reporter::{fmt_github, fmt_json};
+    use squawk_linter::config::CheckSqlConfig;
 
-    use super::process_violations;
+    use super::{process_violations, STDIN_FILENAME};
 
     #[test]
     fn test_check_files_invalid_syntax() {
@@ -631,24 +919,55 @@ mod test_check_files {
 select \;
         "#;
         let mut buff = Vec::new();
-        let res = process_violations(sql, "test.sql", &[], None, false);
+        let res = process_violations(sql, "test.sql", &CheckSqlConfig::default(), None);
         fmt_json(&mut buff, vec![res]).unwrap();
 
         let val: Value = serde_json::from_slice(&buff).unwrap();
         assert_yaml_snapshot!(val);
     }
+
+    /// SQL from stdin is reported with the virtual filename in every format.
+    #[test]
+    fn test_stdin_filename() {
+        let sql = r#"
+SELECT 1;
+ALTER TABLE "core_recipe" ADD COLUMN "foo" integer NOT NULL;
+        "#;
+        let res = process_violations(sql, STDIN_FILENAME, &CheckSqlConfig::default(), None);
+
+        let mut buff = Vec::new();
+        fmt_github(&mut buff, &[res]).unwrap();
+        let annotations = String::from_utf8(buff).unwrap();
+        assert_ne!(annotations, "");
+        // every rule reports the ALTER TABLE, which starts on line 3.
+        assert!(annotations
+            .lines()
+            .all(|line| line.contains(" file=<stdin>,line=3,col=1::")));
+
+        let res = process_violations(sql, STDIN_FILENAME, &CheckSqlConfig::default(), None);
+        let mut buff = Vec::new();
+        fmt_json(&mut buff, vec![res]).unwrap();
+        let val: Value = serde_json::from_slice(&buff).unwrap();
+        assert_eq!(val[0]["file"], "<stdin>");
+        assert_eq!(val[0]["line"], 3);
+    }
 }
 
 #[cfg(test)]
 mod test_reporter {
-    use crate::reporter::{pretty_violations, print_violations, Reporter};
+    use crate::reporter::{
+        explain_rule, fmt_github_annotations, fmt_sarif_violations, pretty_violations,
+        print_violations, Reporter,
+    };
 
     use console::strip_ansi_codes;
     use insta::{assert_debug_snapshot, assert_display_snapshot};
 
+    use serde_json::{json, Value};
     use squawk_linter::{
         check_sql_with_rule,
         violations::{RuleViolation, RuleViolationKind},
+        RULES,
     };
 
     fn lint_sql(sql: &str) -> Vec<RuleViolation> {
@@ -736,10 +1055,156 @@ SELECT 1;
 
         assert!(res.is_ok());
         assert_display_snapshot!(String::from_utf8_lossy(&buff), @r###"
-        [{"file":"main.sql","line":1,"column":0,"level":"Warning","messages":[{"Note":"Adding a NOT NULL field without a DEFAULT will fail for a populated table."},{"Help":"Make the field nullable or add a non-VOLATILE DEFAULT (Postgres 11+)."}],"rule_name":"adding-required-field"},{"file":"main.sql","line":3,"column":1,"level":"Warning","messages":[{"Note":"Adding a NOT NULL field without a DEFAULT will fail for a populated table."},{"Help":"Make the field nullable or add a non-VOLATILE DEFAULT (Postgres 11+)."}],"rule_name":"adding-required-field"}]
+        [{"file":"main.sql","line":1,"column":0,"level":"Warning","lock_level":null,"messages":[{"Note":"Adding a NOT NULL field without a DEFAULT will fail for a populated table."},{"Help":"Make the field nullable or add a non-VOLATILE DEFAULT (Postgres 11+)."}],"rule_name":"adding-required-field"},{"file":"main.sql","line":3,"column":1,"level":"Warning","lock_level":null,"messages":[{"Note":"Adding a NOT NULL field without a DEFAULT will fail for a populated table."},{"Help":"Make the field nullable or add a non-VOLATILE DEFAULT (Postgres 11+)."}],"rule_name":"adding-required-field"}]
         "###);
     }
 
+    #[test]
+    fn test_display_violations_sarif() {
+        let sql = r#" 
+   ALTER TABLE "core_recipe" ADD COLUMN "foo" integer NOT NULL;
+ALTER TABLE "core_foo" ADD COLUMN "bar" integer NOT NULL;
+SELECT 1;
+"#;
+        let violations = lint_sql(sql);
+        let filename = "main.sql";
+        let mut buff = Vec::new();
+
+        let res = print_violations(
+            &mut buff,
+            vec![pretty_violations(violations, sql, filename)],
+            &Reporter::Sarif,
+        );
+
+        assert!(res.is_ok());
+        let sarif: Value = serde_json::from_slice(&buff).unwrap();
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), RULES.len());
+        let rule = rules
+            .iter()
+            .find(|rule| rule["id"] == "adding-field-with-default")
+            .unwrap();
+        assert_eq!(
+            *rule,
+            json!({
+                "id": "adding-field-with-default",
+                "shortDescription": { "text": "adding-field-with-default" },
+                "fullDescription": { "text": "Adding a field with a VOLATILE DEFAULT requires a table rewrite with an ACCESS EXCLUSIVE lock. In Postgres versions 11+, non-VOLATILE DEFAULTs can be added without a rewrite." },
+                "help": { "text": "Add the field as nullable, then set a default, backfill, and remove nullabilty. https://www.postgresql.org/docs/current/sql-altertable.html" },
+                "helpUri": "https://squawkhq.com/docs/adding-field-with-default",
+                "defaultConfiguration": { "level": "warning" },
+            })
+        );
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["region"],
+            json!({ "startLine": 2, "startColumn": 4 })
+        );
+        assert_eq!(
+            results[1],
+            json!({
+                "ruleId": "adding-required-field",
+                "level": "warning",
+                "message": { "text": "Adding a NOT NULL field without a DEFAULT will fail for a populated table. Make the field nullable or add a non-VOLATILE DEFAULT (Postgres 11+)." },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "main.sql" },
+                        "region": { "startLine": 3, "startColumn": 1 },
+                    },
+                }],
+            })
+        );
+    }
+
+    /// The CLI reporter outputs the same SARIF as `fmt_sarif_violations`.
+    #[test]
+    fn test_sarif_violations() {
+        let sql = r#" 
+   ALTER TABLE "core_recipe" ADD COLUMN "foo" integer NOT NULL;
+ALTER TABLE "core_foo" ADD COLUMN "bar" integer NOT NULL;
+SELECT 1;
+"#;
+        let violations = lint_sql(sql);
+        let mut buff = Vec::new();
+
+        let res = fmt_sarif_violations(&mut buff, &violations, "main.sql", sql);
+
+        assert!(res.is_ok());
+        let sarif: Value = serde_json::from_slice(&buff).unwrap();
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| &result["locations"][0]["physicalLocation"]["region"])
+                .collect::<Vec<_>>(),
+            vec![
+                &json!({ "startLine": 2, "startColumn": 4 }),
+                &json!({ "startLine": 3, "startColumn": 1 }),
+            ]
+        );
+
+        let mut cli_buff = Vec::new();
+        print_violations(
+            &mut cli_buff,
+            vec![pretty_violations(violations, sql, "main.sql")],
+            &Reporter::Sarif,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&buff),
+            String::from_utf8_lossy(&cli_buff)
+        );
+    }
+
+    #[test]
+    fn test_explain_rule() {
+        let mut buff = Vec::new();
+        explain_rule(&mut buff, "require-concurrent-index-creation").unwrap();
+        let output = String::from_utf8_lossy(&buff);
+        assert!(output.starts_with("require-concurrent-index-creation\n    note: "));
+        assert!(output.contains("Use `CREATE INDEX CONCURRENTLY`"));
+
+        let mut buff = Vec::new();
+        explain_rule(&mut buff, "prefer-timestamptz").unwrap();
+        assert!(!String::from_utf8_lossy(&buff).contains("\n\n"));
+    }
+
+    #[test]
+    fn test_display_violations_github() {
+        let sql = r#" 
+   ALTER TABLE "core_recipe" ADD COLUMN "foo" integer NOT NULL;
+ALTER TABLE "core_foo" ADD COLUMN "bar" integer NOT NULL;
+SELECT 1;
+"#;
+        let violations = lint_sql(sql);
+        let mut buff = Vec::new();
+
+        let res = fmt_github_annotations(&mut buff, &violations, "migrations/main,1.sql", sql);
+
+        assert!(res.is_ok());
+        assert_eq!(
+            String::from_utf8_lossy(&buff),
+            "::warning file=migrations/main%2C1.sql,line=2,col=4::adding-required-field: Adding a NOT NULL field without a DEFAULT will fail for a populated table. Make the field nullable or add a non-VOLATILE DEFAULT (Postgres 11+).\n\
+             ::warning file=migrations/main%2C1.sql,line=3,col=1::adding-required-field: Adding a NOT NULL field without a DEFAULT will fail for a populated table. Make the field nullable or add a non-VOLATILE DEFAULT (Postgres 11+).\n"
+        );
+
+        let mut buff = Vec::new();
+        let res = print_violations(
+            &mut buff,
+            vec![pretty_violations(violations, sql, "main.sql")],
+            &Reporter::Github,
+        );
+        assert!(res.is_ok());
+        assert_eq!(
+            String::from_utf8_lossy(&buff),
+            "::warning file=main.sql,line=2,col=4::adding-required-field: Adding a NOT NULL field without a DEFAULT will fail for a populated table. Make the field nullable or add a non-VOLATILE DEFAULT (Postgres 11+).\n\
+             ::warning file=main.sql,line=3,col=1::adding-required-field: Adding a NOT NULL field without a DEFAULT will fail for a populated table. Make the field nullable or add a non-VOLATILE DEFAULT (Postgres 11+).\n"
+        );
+    }
+
     #[test]
     fn test_span_offsets() {
         let sql = r#"
@@ -765,12 +1230,15 @@ SELECT 1;
         [
             RuleViolation {
                 kind: AddingRequiredField,
+                severity: Warning,
+                lock_level: None,
                 span: Span {
                     start: 0,
                     len: Some(
                         59,
                     ),
                 },
+                statement_kind: AlterTable,
                 messages: [
                     Note(
                         "Adding a NOT NULL field without a DEFAULT will fail for a populated table.",
@@ -779,6 +1247,7 @@ SELECT 1;
                         "Make the field nullable or add a non-VOLATILE DEFAULT (Postgres 11+).",
                     ),
                 ],
+                snippet: None,
             },
         ]
         "###);
@@ -793,7 +1262,12 @@ SELECT 1;
                     file: "main.sql",
                     line: 1,
                     column: 0,
+                    location: (
+                        1,
+                        1,
+                    ),
                     level: Warning,
+                    lock_level: None,
                     messages: [
                         Note(
                             "Adding a NOT NULL field without a DEFAULT will fail for a populated table.",
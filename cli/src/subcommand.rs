@@ -6,7 +6,7 @@ use crate::{
 };
 use log::info;
 use squawk_github::{actions, app, comment_on_pr, GitHubApi, GithubError};
-use squawk_linter::{versions::Version, violations::RuleViolationKind};
+use squawk_linter::{config::CheckSqlConfig, versions::Version, violations::RuleViolationKind};
 use structopt::StructOpt;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -195,14 +195,14 @@ pub fn check_and_comment_on_pr(
     let found_paths = find_paths(&paths, exclude_paths)?;
 
     info!("checking files");
-    let file_results = check_files(
-        &found_paths,
-        is_stdin,
-        stdin_path,
-        exclude,
+    let config = CheckSqlConfig {
+        excluded_rules: exclude.to_vec(),
         pg_version,
         assume_in_transaction,
-    )?;
+        rules: cfg.rules.clone(),
+        ..CheckSqlConfig::default()
+    };
+    let file_results = check_files(&found_paths, is_stdin, stdin_path, &config, None)?;
 
     // We should only leave a comment when there are files checked.
     if paths.is_empty() {
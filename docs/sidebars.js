@@ -3,32 +3,67 @@ module.exports = {
     General: ["quick_start", "safe_migrations", "cli", "github_app", "web-frameworks", "postgres-locks", "troubleshooting"],
     Rules: [
       "rules",
+      "adding-column-with-domain-type",
+      "adding-enum-value-in-transaction",
       "adding-field-with-default",
       "adding-foreign-key-constraint",
+      "adding-identity-column",
       "adding-not-nullable-field",
       "adding-required-field",
+      "adding-serial-column",
       "adding-serial-primary-key-field",
+      "adding-stored-generated-column",
+      "ban-alter-owner",
       "ban-char-field",
+      "ban-cluster",
       "ban-concurrent-index-creation-in-transaction",
+      "ban-create-table-as",
+      "ban-deprecated-type",
+      "ban-disabling-statement-timeout",
       "ban-drop-column",
       "ban-drop-database",
       "ban-drop-not-null",
       "ban-drop-table",
+      "ban-grant-in-migration",
+      "ban-money-type",
+      "ban-set-unlogged",
+      "ban-truncate",
+      "ban-vacuum-full",
       "changing-column-type",
+      "changing-object-schema",
+      "changing-storage-parameter",
       "constraint-missing-not-valid",
+      "disallowed-exclusion-constraint",
       "disallowed-unique-constraint",
+      "duplicate-index",
+      "mixing-ddl-and-dml",
+      "naming-convention",
+      "non-immutable-check-constraint",
+      "partition-lock-hazard",
       "prefer-big-int",
       "prefer-bigint-over-int",
       "prefer-bigint-over-smallint",
       "prefer-identity",
+      "prefer-jsonb",
+      "prefer-primary-key",
       "prefer-robust-stmts",
       "prefer-text-field",
       "prefer-timestamptz",
       "renaming-column",
+      "renaming-constraint",
       "renaming-table",
       "require-concurrent-index-creation",
       "require-concurrent-index-deletion",
+      "require-concurrent-reindex",
+      "require-if-not-exists",
+      "require-lock-timeout",
+      "require-replica-identity",
+      "require-validate-constraint",
+      "setting-not-null-on-existing-column",
+      "setting-search-path",
+      "too-many-locking-statements",
       "transaction-nesting",
+      "unbounded-data-modification",
       // generator::new-rule-above
     ],
   },
@@ -183,6 +183,201 @@ const rules = [
     tags: ["schema"],
     description: "Prevent forbidden use of transactions during concurrent index creation.",
   },
+  {
+    name: "adding-serial-column",
+    tags: ["locking"],
+    description:
+      "Prevent blocking reads/writes to table while table is rewritten.",
+  },
+  {
+    name: "require-lock-timeout",
+    tags: ["locking"],
+    description:
+      "Prevent statements waiting on a lock from blocking all other queries on the table.",
+  },
+  {
+    name: "prefer-jsonb",
+    tags: ["schema"],
+    description: "Use jsonb instead of json for efficient storage and indexing.",
+  },
+  {
+    name: "setting-not-null-on-existing-column",
+    tags: ["locking"],
+    description:
+      "Prevent blocking reads and writes while checking an existing column for nulls.",
+  },
+  {
+    name: "ban-vacuum-full",
+    tags: ["locking"],
+    description:
+      "Prevent rewriting a table and blocking all access with VACUUM FULL.",
+  },
+  {
+    name: "ban-cluster",
+    tags: ["locking"],
+    description:
+      "Prevent rewriting a table and blocking all access with CLUSTER.",
+  },
+  {
+    name: "adding-enum-value-in-transaction",
+    tags: ["schema"],
+    description:
+      "Prevent errors from adding and using an enum value in a transaction.",
+  },
+  {
+    name: "require-if-not-exists",
+    tags: ["schema"],
+    description:
+      "Ensure migrations can be rerun after failing part way through.",
+  },
+  {
+    name: "renaming-constraint",
+    tags: ["backwards compatibility"],
+    description: "Prevent breaking existing clients that depend on constraint.",
+  },
+  {
+    name: "too-many-locking-statements",
+    tags: ["locking"],
+    description:
+      "Warn about migrations with many statements that take strong locks.",
+  },
+  {
+    name: "ban-truncate",
+    tags: ["backwards compatibility", "locking"],
+    description: "Prevent deleting all of a table's data with TRUNCATE.",
+  },
+  {
+    name: "ban-disabling-statement-timeout",
+    tags: ["locking"],
+    description:
+      "Prevent statements from running and blocking queries indefinitely.",
+  },
+  {
+    name: "prefer-primary-key",
+    tags: ["schema"],
+    description: "Prevent creating tables without a primary key.",
+  },
+  {
+    name: "ban-money-type",
+    tags: ["schema"],
+    description: "Prevent locale-dependent money columns.",
+  },
+  {
+    name: "adding-stored-generated-column",
+    tags: ["locking"],
+    description:
+      "Prevent table rewrites from adding a stored generated column.",
+  },
+  {
+    name: "require-validate-constraint",
+    tags: ["schema"],
+    description:
+      "Remind to validate NOT VALID constraints in a later migration.",
+  },
+  {
+    name: "ban-set-unlogged",
+    tags: ["locking"],
+    description:
+      "Prevent table rewrites from ALTER TABLE ... SET LOGGED / SET UNLOGGED.",
+  },
+  {
+    name: "mixing-ddl-and-dml",
+    tags: ["locking"],
+    description:
+      "Keep locks short by separating backfills from schema changes.",
+  },
+  {
+    name: "require-concurrent-reindex",
+    tags: ["locking"],
+    description: "Rebuild indexes without blocking writes.",
+  },
+  {
+    name: "changing-object-schema",
+    tags: ["backwards compatibility"],
+    description:
+      "Prevent breaking clients that use the old schema-qualified name.",
+  },
+  {
+    name: "duplicate-index",
+    tags: ["locking"],
+    description: "Avoid building the same index twice.",
+  },
+  {
+    name: "ban-create-table-as",
+    tags: ["schema"],
+    description: "Keep schema and data changes separate.",
+  },
+  {
+    name: "partition-lock-hazard",
+    tags: ["locking"],
+    description:
+      "Prevent blocking reads/writes while attaching or detaching partitions.",
+  },
+  {
+    name: "ban-grant-in-migration",
+    tags: ["schema"],
+    description: "Keep permission changes out of schema migrations.",
+  },
+  {
+    name: "naming-convention",
+    tags: ["schema"],
+    description: "Enforce naming conventions for indexes and constraints.",
+  },
+  {
+    name: "adding-column-with-domain-type",
+    tags: ["locking"],
+    description:
+      "Custom column types may be domains with constraints that rewrite the table.",
+  },
+  {
+    name: "non-immutable-check-constraint",
+    tags: ["schema"],
+    description: "CHECK constraints should only call immutable functions.",
+  },
+  {
+    name: "ban-deprecated-type",
+    tags: ["schema"],
+    description: "Don't use deprecated types like abstime or oid for columns.",
+  },
+  {
+    name: "require-replica-identity",
+    tags: ["schema"],
+    description:
+      "Keep a replica identity for tables used with logical replication.",
+  },
+  {
+    name: "unbounded-data-modification",
+    tags: ["locking"],
+    description: "Modify rows in batches instead of the whole table at once.",
+  },
+  {
+    name: "ban-alter-owner",
+    tags: ["schema"],
+    description: "Manage object ownership separately from schema migrations.",
+  },
+  {
+    name: "setting-search-path",
+    tags: ["schema"],
+    description:
+      "Qualify names instead of changing search_path partway through a migration.",
+  },
+  {
+    name: "disallowed-exclusion-constraint",
+    tags: ["locking"],
+    description:
+      "Exclusion constraints block reads and writes while their index is built.",
+  },
+  {
+    name: "adding-identity-column",
+    tags: ["locking"],
+    description: "Prevent table rewrites from adding an identity column.",
+  },
+  {
+    name: "changing-storage-parameter",
+    tags: ["locking"],
+    description:
+      "Note storage parameters that need a table rewrite to take effect.",
+  },
   // generator::new-rule-above
 ]
 
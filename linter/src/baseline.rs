@@ -0,0 +1,493 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::CheckSqlConfig;
+use crate::errors::CheckFileError;
+use crate::violations::{RuleViolation, RuleViolationKind};
+
+/// A violation recorded in a baseline.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    /// The file the violation was found in. Entries without one apply to
+    /// every file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    pub kind: RuleViolationKind,
+    /// `RuleViolation::fingerprint` of the violation.
+    pub fingerprint: String,
+}
+
+/// Existing violations to ignore, so squawk can be adopted on a project
+/// without fixing every historical migration first.
+///
+/// Violations are identified by `RuleViolation::fingerprint` rather than
+/// byte offsets, so reformatting a migration or adding comments doesn't
+/// invalidate the baseline. Entries from `from_files` only apply to the file
+/// they were found in, so the same statement in a new migration is still
+/// reported by `check_files`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Baseline {
+    entries: HashSet<BaselineEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BaselineFile {
+    violations: Vec<BaselineEntry>,
+}
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` is stable across Rust
+/// versions.
+pub(crate) fn fnv1a(data: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in data.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+pub(crate) fn statement_text<'a>(sql: &'a str, violation: &RuleViolation) -> &'a str {
+    #[allow(clippy::cast_sign_loss)]
+    let start = (violation.span.start.max(0) as usize).min(sql.len());
+    #[allow(clippy::cast_sign_loss)]
+    let end = violation
+        .span
+        .len
+        .map_or(sql.len(), |len| start + len.max(0) as usize)
+        .min(sql.len());
+    sql.get(start..end).unwrap_or_default()
+}
+
+/// Skip the whitespace and comments before a statement, which Postgres
+/// includes in the span.
+pub(crate) fn strip_leading_comments(mut text: &str) -> &str {
+    loop {
+        text = text.trim_start();
+        if let Some(rest) = text.strip_prefix("--") {
+            text = rest.split_once('\n').map_or("", |(_, rest)| rest);
+        } else if let Some(rest) = text.strip_prefix("/*") {
+            text = rest.split_once("*/").map_or("", |(_, rest)| rest);
+        } else {
+            return text;
+        }
+    }
+}
+
+/// Byte offset of the statement itself in `sql`, past the whitespace and
+/// comments at the start of its span.
+pub(crate) fn statement_start(sql: &str, violation: &RuleViolation) -> usize {
+    let text = statement_text(sql, violation);
+    #[allow(clippy::cast_sign_loss)]
+    let start = (violation.span.start.max(0) as usize).min(sql.len());
+    start + text.len() - strip_leading_comments(text).len()
+}
+
+/// The statement without comments, with whitespace collapsed and keywords
+/// and unquoted identifiers lowercased, so formatting doesn't change it.
+/// Quoted identifiers Postgres would fold to the same name, e.g. `"foo"` and
+/// `foo`, are unquoted. String literals are kept as they are.
+pub(crate) fn normalize_statement(text: &str) -> String {
+    let is_word = |c: char| c.is_alphanumeric() || matches!(c, '_' | '$' | '"' | '\'');
+    let mut out = String::with_capacity(text.len());
+    let mut space = false;
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let token = if c.is_whitespace() {
+            space = true;
+            rest = &rest[c.len_utf8()..];
+            continue;
+        } else if let Some(comment) = rest.strip_prefix("--") {
+            rest = comment.split_once('\n').map_or("", |(_, rest)| rest);
+            space = true;
+            continue;
+        } else if rest.starts_with("/*") {
+            rest = skip_block_comment(rest);
+            space = true;
+            continue;
+        } else if c == '\'' || c == '"' {
+            // doubling the quote escapes it, so the token ends at the first
+            // quote that isn't followed by another.
+            let mut end = 1;
+            loop {
+                match rest[end..].find(c) {
+                    Some(idx) if rest[end + idx + 1..].starts_with(c) => end += idx + 2,
+                    Some(idx) => break end + idx + 1,
+                    None => break rest.len(),
+                }
+            }
+        } else if let Some(tag) =
+            dollar_quote_tag(rest).filter(|_| space || !out.ends_with(is_word))
+        {
+            rest[tag.len()..]
+                .find(tag)
+                .map_or(rest.len(), |idx| 2 * tag.len() + idx)
+        } else {
+            c.len_utf8()
+        };
+        let (token, tail) = rest.split_at(token);
+        rest = tail;
+        if space && out.ends_with(is_word) && token.starts_with(is_word) {
+            out.push(' ');
+        }
+        space = false;
+        match token.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+            Some(name)
+                if name.starts_with(|c: char| c.is_ascii_lowercase() || c == '_')
+                    && name.chars().all(|c| {
+                        c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '$')
+                    }) =>
+            {
+                out.push_str(name);
+            }
+            _ if token.starts_with(['\'', '"', '$']) && token.len() > 1 => out.push_str(token),
+            _ => out.push_str(&token.to_ascii_lowercase()),
+        }
+    }
+    out
+}
+
+/// The text after a block comment at the start of `text`, which can be
+/// nested in Postgres.
+fn skip_block_comment(text: &str) -> &str {
+    let mut depth = 0;
+    let mut rest = text;
+    loop {
+        if let Some(tail) = rest.strip_prefix("/*") {
+            depth += 1;
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix("*/") {
+            depth -= 1;
+            rest = tail;
+            if depth == 0 {
+                return rest;
+            }
+        } else if let Some(c) = rest.chars().next() {
+            rest = &rest[c.len_utf8()..];
+        } else {
+            return rest;
+        }
+    }
+}
+
+/// The `$tag$` opening a dollar-quoted string at the start of `text`.
+fn dollar_quote_tag(text: &str) -> Option<&str> {
+    let tag = text.strip_prefix('$')?;
+    let end = tag.find('$')?;
+    let name = &tag[..end];
+    let valid = !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    valid.then_some(&text[..end + 2])
+}
+
+fn entry(path: Option<&Path>, sql: &str, violation: &RuleViolation) -> BaselineEntry {
+    BaselineEntry {
+        path: path.map(Path::to_path_buf),
+        kind: violation.kind.clone(),
+        fingerprint: violation.fingerprint(sql),
+    }
+}
+
+impl Baseline {
+    /// Record the violations found in `sql`, e.g. from `check_sql`, for
+    /// every file.
+    pub fn add_violations(&mut self, sql: &str, violations: &[RuleViolation]) {
+        self.entries
+            .extend(violations.iter().map(|v| entry(None, sql, v)));
+    }
+
+    /// Record the violations found in `sql`, the content of `path`.
+    pub fn add_file_violations(&mut self, path: &Path, sql: &str, violations: &[RuleViolation]) {
+        self.entries
+            .extend(violations.iter().map(|v| entry(Some(path), sql, v)));
+    }
+
+    /// Create a baseline of the current violations in each file.
+    pub fn from_files(paths: &[PathBuf], config: &CheckSqlConfig) -> Result<Self, CheckFileError> {
+        // don't filter out the violations we're recording.
+        let config = CheckSqlConfig {
+            baseline: None,
+            ..config.clone()
+        };
+        let mut baseline = Self::default();
+        for path in paths {
+            let sql = std::fs::read_to_string(path)?;
+            let violations = crate::check_sql(&sql, &config)?;
+            baseline.add_file_violations(path, &sql, &violations);
+        }
+        Ok(baseline)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove violations that are in the baseline for every file.
+    #[must_use]
+    pub fn filter(&self, sql: &str, violations: Vec<RuleViolation>) -> Vec<RuleViolation> {
+        self.filter_file(None, sql, violations)
+    }
+
+    /// Remove violations that are in the baseline for `path`, or for every
+    /// file. Paths are compared as given, so use the same paths as
+    /// `from_files`.
+    #[must_use]
+    pub fn filter_file(
+        &self,
+        path: Option<&Path>,
+        sql: &str,
+        violations: Vec<RuleViolation>,
+    ) -> Vec<RuleViolation> {
+        if self.is_empty() {
+            return violations;
+        }
+        violations
+            .into_iter()
+            .filter(|v| {
+                !self.entries.contains(&entry(None, sql, v))
+                    && (path.is_none() || !self.entries.contains(&entry(path, sql, v)))
+            })
+            .collect()
+    }
+
+    /// Write the baseline as JSON, sorted so the output is stable.
+    pub fn write<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        let mut violations: Vec<BaselineEntry> = self.entries.iter().cloned().collect();
+        violations.sort_by(|a, b| {
+            (&a.path, a.kind.to_string(), &a.fingerprint).cmp(&(
+                &b.path,
+                b.kind.to_string(),
+                &b.fingerprint,
+            ))
+        });
+        serde_json::to_writer_pretty(writer, &BaselineFile { violations })?;
+        Ok(())
+    }
+
+    pub fn read<R: io::Read>(reader: R) -> io::Result<Self> {
+        let file: BaselineFile = serde_json::from_reader(reader)?;
+        Ok(Self {
+            entries: file.violations.into_iter().collect(),
+        })
+    }
+}
+
+/// The violations in `head` that aren't in `base`, e.g. the violations a pull
+/// request adds compared to its target branch.
+///
+/// Violations are matched by `RuleViolation::fingerprint` like a `Baseline`,
+/// so edits that move or reformat a statement don't make its violations new.
+/// Each violation in `base` matches at most one in `head`, so copying a
+/// statement reports the copy's violations.
+#[must_use]
+pub fn new_violations(
+    base: &[RuleViolation],
+    head: &[RuleViolation],
+    base_sql: &str,
+    head_sql: &str,
+) -> Vec<RuleViolation> {
+    let mut unmatched: HashMap<BaselineEntry, usize> = HashMap::new();
+    for violation in base {
+        *unmatched
+            .entry(entry(None, base_sql, violation))
+            .or_default() += 1;
+    }
+    head.iter()
+        .filter(
+            |violation| match unmatched.get_mut(&entry(None, head_sql, violation)) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            },
+        )
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod test_baseline {
+    use super::{new_violations, normalize_statement, Baseline};
+    use crate::{check_files, check_sql, config::CheckSqlConfig, violations::RuleViolationKind};
+
+    fn config() -> CheckSqlConfig {
+        CheckSqlConfig {
+            excluded_rules: vec![RuleViolationKind::PreferRobustStmts],
+            ..CheckSqlConfig::default()
+        }
+    }
+
+    fn baseline_for(sql: &str) -> Baseline {
+        let violations = check_sql(sql, &config()).unwrap();
+        let mut baseline = Baseline::default();
+        baseline.add_violations(sql, &violations);
+        baseline
+    }
+
+    #[test]
+    fn test_baseline_ignores_existing_violations() {
+        let sql = r#"
+ALTER TABLE "foo" DROP COLUMN "bar";
+"#;
+        let baseline = baseline_for(sql);
+        assert_eq!(baseline.len(), 1);
+
+        // reformatting and comments don't change the fingerprint.
+        let sql = r#"
+-- drop the old column
+alter table foo
+    drop column bar;
+ALTER TABLE "foo" DROP COLUMN "baz";
+"#;
+        let config = CheckSqlConfig {
+            baseline: Some(baseline),
+            ..config()
+        };
+        let res = check_sql(sql, &config).unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].kind, RuleViolationKind::BanDropColumn);
+        assert_eq!(res[0].span.start, 60);
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let sql = r#"
+ALTER TABLE "foo" DROP COLUMN "bar";
+DROP TABLE "foo";
+"#;
+        let baseline = baseline_for(sql);
+        let mut buff = Vec::new();
+        baseline.write(&mut buff).unwrap();
+        let read = Baseline::read(buff.as_slice()).unwrap();
+        assert_eq!(read, baseline);
+        assert_eq!(read.len(), 2);
+
+        let violations = check_sql(sql, &config()).unwrap();
+        assert_eq!(read.filter(sql, violations), vec![]);
+    }
+
+    /// Parts of the statement squawk doesn't look at still make it a
+    /// different statement.
+    #[test]
+    fn test_baseline_compares_whole_statement() {
+        let config = CheckSqlConfig::builder()
+            .only(RuleViolationKind::RequireConcurrentIndexCreation)
+            .build();
+        let sql = r#"CREATE INDEX "foo_idx" ON "foo" ("bar");"#;
+        let mut baseline = Baseline::default();
+        baseline.add_violations(sql, &check_sql(sql, &config).unwrap());
+        assert_eq!(baseline.len(), 1);
+
+        let sql = r#"
+create index foo_idx on foo (bar);
+CREATE INDEX "foo_idx" ON "foo" ("bar") INCLUDE ("baz");
+CREATE INDEX "Foo_idx" ON "foo" ("bar");
+"#;
+        let violations = check_sql(sql, &config).unwrap();
+        assert_eq!(violations.len(), 3);
+        let res = baseline.filter(sql, violations);
+        assert_eq!(
+            res.iter().map(|v| v.span.start).collect::<Vec<_>>(),
+            vec![35, 92]
+        );
+    }
+
+    /// Entries from `from_files` only apply to their file, and the baseline
+    /// parses the files the same way `check_sql` does.
+    #[test]
+    fn test_baseline_from_files() {
+        let dir = std::env::temp_dir().join(format!("squawk-baseline-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let old = dir.join("0001_old.sql");
+        std::fs::write(
+            &old,
+            "\\set ON_ERROR_STOP on\nVACUUM FULL;\nSELECT * FROM;\nDROP TABLE \"foo\";\n",
+        )
+        .unwrap();
+        let new = dir.join("0002_new.sql");
+        std::fs::write(&new, "VACUUM FULL;\n").unwrap();
+
+        let config = CheckSqlConfig::builder()
+            .only(RuleViolationKind::BanVacuumFull)
+            .only(RuleViolationKind::BanDropTable)
+            .recover_from_parse_errors(true)
+            .ignore_psql_meta_commands(true)
+            .build();
+        let baseline = Baseline::from_files(&[old.clone()], &config).unwrap();
+        assert_eq!(baseline.len(), 3);
+
+        let config = CheckSqlConfig {
+            baseline: Some(baseline),
+            ..config
+        };
+        let res = check_files(&[old.clone(), new.clone()], &config);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(res[&old].as_ref().unwrap(), &vec![]);
+        let kinds: Vec<RuleViolationKind> = res[&new]
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|v| v.kind.clone())
+            .collect();
+        assert_eq!(kinds, vec![RuleViolationKind::BanVacuumFull]);
+    }
+
+    #[test]
+    fn test_normalize_statement() {
+        assert_eq!(
+            normalize_statement(
+                "ALTER TABLE \"Foo\"  -- rename\n  RENAME TO\t\"foo\" /* not /* nested */ done */;"
+            ),
+            "alter table \"Foo\" rename to foo;"
+        );
+        assert_eq!(
+            normalize_statement("SET search_path = 'App', \"a\"\"b\" ;"),
+            "set search_path='App',\"a\"\"b\";"
+        );
+        assert_eq!(
+            normalize_statement("CREATE FUNCTION f() AS $body$ SELECT  'A' $body$ LANGUAGE SQL"),
+            "create function f()as $body$ SELECT  'A' $body$ language sql"
+        );
+    }
+
+    #[test]
+    fn test_new_violations() {
+        let base_sql = r#"
+ALTER TABLE "foo" DROP COLUMN "bar";
+"#;
+        // the existing statement is reformatted and moved.
+        let head_sql = r#"
+DROP TABLE "baz";
+-- drop the old column
+alter table foo
+    drop column bar;
+"#;
+        let base = check_sql(base_sql, &config()).unwrap();
+        let head = check_sql(head_sql, &config()).unwrap();
+        let res = new_violations(&base, &head, base_sql, head_sql);
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].kind, RuleViolationKind::BanDropTable);
+        assert_eq!(res[0].span.start, 0);
+
+        // a copy of an existing statement is new.
+        let head_sql = r#"
+ALTER TABLE "foo" DROP COLUMN "bar";
+ALTER TABLE "foo" DROP COLUMN "bar";
+"#;
+        let head = check_sql(head_sql, &config()).unwrap();
+        let res = new_violations(&base, &head, base_sql, head_sql);
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].kind, RuleViolationKind::BanDropColumn);
+        assert_eq!(res[0].span.start, 37);
+    }
+}
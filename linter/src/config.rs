@@ -0,0 +1,332 @@
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::num::NonZeroUsize;
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::{
+    baseline::Baseline,
+    rule_by_kind,
+    versions::Version,
+    violations::{RuleViolationKind, Severity, UnknownRuleName},
+};
+
+/// Options for a single rule, e.g. `max_allowed_length` for `prefer-text-field`.
+pub type RuleOptions = Map<String, Value>;
+
+/// How `check_sql` orders the violations it returns.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// In the order the statements appear in the file.
+    #[default]
+    Position,
+    /// Errors before warnings, then by position.
+    Severity,
+    /// Grouped by rule, then by position.
+    Rule,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CheckSqlConfig {
+    pub excluded_rules: Vec<RuleViolationKind>,
+    pub pg_version: Option<Version>,
+    pub assume_in_transaction: bool,
+    /// Options for specific rules, rules without an entry use their defaults.
+    pub rules: HashMap<RuleViolationKind, RuleOptions>,
+    /// Lint the statements that parse and report the rest as
+    /// `invalid-statement` violations, instead of failing the entire file.
+    pub recover_from_parse_errors: bool,
+    /// Pre-existing violations to ignore.
+    pub baseline: Option<Baseline>,
+    /// Number of threads `check_files` uses to lint files in parallel. Files
+    /// are linted one at a time on the calling thread by default.
+    pub threads: Option<NonZeroUsize>,
+    pub sort_order: SortOrder,
+    /// Treat warnings as errors in `RuleViolation::is_error`, e.g. so CI
+    /// fails on any violation.
+    pub warnings_as_errors: bool,
+    /// Severities that replace the default severity of a rule's violations,
+    /// e.g. to make `prefer-text-field` an error.
+    pub severity_overrides: HashMap<RuleViolationKind, Severity>,
+    /// Only run these rules, including opt-in rules. Takes precedence over
+    /// `excluded_rules`, which is ignored when this is set.
+    pub only: Option<HashSet<RuleViolationKind>>,
+    /// Set `RuleViolation::snippet` to the text of each violation's
+    /// statement. Off by default to avoid copying the SQL.
+    pub include_snippets: bool,
+    /// Ignore lines starting with a psql meta-command, e.g. `\set` or `\i`,
+    /// which Postgres can't parse. Spans still point into the original SQL.
+    pub ignore_psql_meta_commands: bool,
+}
+
+impl CheckSqlConfig {
+    /// Whether the rule should run at all. Opt-in rules only run when they
+    /// have an entry in `rules`, and when `only` is set, just the rules it
+    /// lists run.
+    #[must_use]
+    pub fn is_enabled(&self, rule: &RuleViolationKind) -> bool {
+        if let Some(only) = &self.only {
+            return only.contains(rule);
+        }
+        !self.excluded_rules.contains(rule)
+            && rule_by_kind(rule).map_or(true, |r| !r.opt_in || self.rules.contains_key(rule))
+    }
+
+    /// Build a config by chaining the settings that differ from the defaults.
+    ///
+    /// ```
+    /// use squawk_linter::config::{CheckSqlConfig, RuleOptions, SortOrder};
+    /// use squawk_linter::versions::Version;
+    /// use squawk_linter::violations::RuleViolationKind;
+    ///
+    /// let mut options = RuleOptions::new();
+    /// options.insert("max_allowed_length".into(), 255.into());
+    ///
+    /// let config = CheckSqlConfig::builder()
+    ///     .exclude(RuleViolationKind::RequireLockTimeout)
+    ///     .pg_version(Version::new(13, None, None))
+    ///     .assume_in_transaction(true)
+    ///     .rule_option(RuleViolationKind::PreferTextField, options)
+    ///     .sort_order(SortOrder::Severity)
+    ///     .build();
+    ///
+    /// assert!(!config.is_enabled(&RuleViolationKind::RequireLockTimeout));
+    /// assert_eq!(config.pg_version, Some(Version::new(13, None, None)));
+    /// assert!(config.rules.contains_key(&RuleViolationKind::PreferTextField));
+    /// ```
+    pub fn builder() -> CheckSqlConfigBuilder {
+        CheckSqlConfigBuilder::default()
+    }
+}
+
+/// Chainable setters for a `CheckSqlConfig`, from `CheckSqlConfig::builder`.
+#[derive(Debug, Default, Clone)]
+#[must_use]
+pub struct CheckSqlConfigBuilder {
+    config: CheckSqlConfig,
+}
+
+impl CheckSqlConfigBuilder {
+    /// Don't run `rule`. Can be called multiple times.
+    pub fn exclude(mut self, rule: RuleViolationKind) -> Self {
+        if !self.config.excluded_rules.contains(&rule) {
+            self.config.excluded_rules.push(rule);
+        }
+        self
+    }
+
+    pub fn pg_version(mut self, pg_version: Version) -> Self {
+        self.config.pg_version = Some(pg_version);
+        self
+    }
+
+    pub fn assume_in_transaction(mut self, assume_in_transaction: bool) -> Self {
+        self.config.assume_in_transaction = assume_in_transaction;
+        self
+    }
+
+    /// Set the options for `rule`, which also enables it if it's opt-in.
+    pub fn rule_option(mut self, rule: RuleViolationKind, options: RuleOptions) -> Self {
+        self.config.rules.insert(rule, options);
+        self
+    }
+
+    pub fn recover_from_parse_errors(mut self, recover_from_parse_errors: bool) -> Self {
+        self.config.recover_from_parse_errors = recover_from_parse_errors;
+        self
+    }
+
+    pub fn baseline(mut self, baseline: Baseline) -> Self {
+        self.config.baseline = Some(baseline);
+        self
+    }
+
+    pub fn threads(mut self, threads: NonZeroUsize) -> Self {
+        self.config.threads = Some(threads);
+        self
+    }
+
+    pub fn sort_order(mut self, sort_order: SortOrder) -> Self {
+        self.config.sort_order = sort_order;
+        self
+    }
+
+    pub fn warnings_as_errors(mut self, warnings_as_errors: bool) -> Self {
+        self.config.warnings_as_errors = warnings_as_errors;
+        self
+    }
+
+    /// Report violations of `rule` with `severity` instead of the rule's
+    /// default.
+    pub fn severity(mut self, rule: RuleViolationKind, severity: Severity) -> Self {
+        self.config.severity_overrides.insert(rule, severity);
+        self
+    }
+
+    pub fn include_snippets(mut self, include_snippets: bool) -> Self {
+        self.config.include_snippets = include_snippets;
+        self
+    }
+
+    pub fn ignore_psql_meta_commands(mut self, ignore_psql_meta_commands: bool) -> Self {
+        self.config.ignore_psql_meta_commands = ignore_psql_meta_commands;
+        self
+    }
+
+    /// Only run `rule`, ignoring the excluded rules. Can be called multiple
+    /// times to run several rules.
+    pub fn only(mut self, rule: RuleViolationKind) -> Self {
+        self.config
+            .only
+            .get_or_insert_with(HashSet::new)
+            .insert(rule);
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> CheckSqlConfig {
+        self.config
+    }
+}
+
+/// Check that each name is a rule, e.g. the excluded rules from a config file,
+/// returning the names that aren't.
+///
+/// # Errors
+///
+/// Returns the unknown names in the order they were passed.
+pub fn validate_rule_names(names: &[String]) -> Result<(), Vec<String>> {
+    let unknown: Vec<String> = names
+        .iter()
+        .filter(|name| RuleViolationKind::try_from(name.as_str()).is_err())
+        .cloned()
+        .collect();
+    if unknown.is_empty() {
+        Ok(())
+    } else {
+        Err(unknown)
+    }
+}
+
+/// Configuration with rule names as strings, e.g. from a config file that an
+/// embedder parses itself.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RawConfig {
+    pub excluded_rules: Vec<String>,
+    pub pg_version: Option<Version>,
+    pub assume_in_transaction: bool,
+    /// Options for specific rules, e.g. `[rules.prefer-text-field]`. An entry
+    /// enables an opt-in rule, and `enabled = false` disables the rule.
+    pub rules: HashMap<String, RuleOptions>,
+}
+
+/// A `CheckSqlConfig` built from a `RawConfig`, along with the rule names it
+/// didn't recognize so they can be reported, e.g. as typos.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ParsedConfig {
+    pub config: CheckSqlConfig,
+    /// Sorted by name, these rules are ignored.
+    pub unknown_rules: Vec<UnknownRuleName>,
+}
+
+impl From<RawConfig> for ParsedConfig {
+    fn from(raw: RawConfig) -> Self {
+        let mut unknown_rules = vec![];
+        let mut config = CheckSqlConfig {
+            pg_version: raw.pg_version,
+            assume_in_transaction: raw.assume_in_transaction,
+            ..CheckSqlConfig::default()
+        };
+        for name in &raw.excluded_rules {
+            match RuleViolationKind::try_from(name.as_str()) {
+                Ok(rule) => config.excluded_rules.push(rule),
+                Err(e) => unknown_rules.push(e),
+            }
+        }
+        for (name, mut options) in raw.rules {
+            let rule = match RuleViolationKind::try_from(name.as_str()) {
+                Ok(rule) => rule,
+                Err(e) => {
+                    unknown_rules.push(e);
+                    continue;
+                }
+            };
+            if options.remove("enabled") == Some(Value::Bool(false)) {
+                config.excluded_rules.push(rule);
+            } else {
+                config.rules.insert(rule, options);
+            }
+        }
+        unknown_rules.sort_by(|a, b| a.name().cmp(b.name()));
+        unknown_rules.dedup();
+        Self {
+            config,
+            unknown_rules,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_config {
+    use serde_json::json;
+
+    use super::{validate_rule_names, ParsedConfig, RawConfig};
+    use crate::violations::RuleViolationKind;
+
+    #[test]
+    fn test_from_raw_config() {
+        let raw: RawConfig = serde_json::from_value(json!({
+            "excluded_rules": ["ban-drop-column", "require-concurent-index-creation"],
+            "rules": {
+                "ban-grant-in-migration": {},
+                "prefer-text-field": { "max_allowed_length": 255 },
+                "require-lock-timeout": { "enabled": false },
+                "prefer-txt-field": {},
+            },
+        }))
+        .unwrap();
+        let parsed = ParsedConfig::from(raw);
+        let config = &parsed.config;
+        assert!(config.is_enabled(&RuleViolationKind::BanGrantInMigration));
+        assert!(!config.is_enabled(&RuleViolationKind::BanDropColumn));
+        assert!(!config.is_enabled(&RuleViolationKind::RequireLockTimeout));
+        assert_eq!(
+            config.rules[&RuleViolationKind::PreferTextField]["max_allowed_length"],
+            json!(255)
+        );
+        assert!(!config
+            .rules
+            .contains_key(&RuleViolationKind::RequireLockTimeout));
+        assert_eq!(
+            parsed
+                .unknown_rules
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "invalid rule name prefer-txt-field",
+                "invalid rule name require-concurent-index-creation",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_rule_names() {
+        assert_eq!(
+            validate_rule_names(&[
+                "ban-drop-column".into(),
+                "require-concurrent-index-creation".into()
+            ]),
+            Ok(())
+        );
+        assert_eq!(
+            validate_rule_names(&[
+                "ban-drop-column".into(),
+                "require-concurent-index-creation".into()
+            ]),
+            Err(vec!["require-concurent-index-creation".to_string()])
+        );
+    }
+}
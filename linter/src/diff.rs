@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::baseline::{statement_text, strip_leading_comments};
+use crate::violations::RuleViolation;
+
+/// The lines added to each file by a unified diff, e.g. the output of
+/// `git diff`, so only violations in new or changed SQL are reported.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AddedLines {
+    /// 1-indexed, inclusive line ranges for each file in the diff.
+    files: HashMap<PathBuf, Vec<(usize, usize)>>,
+}
+
+/// Parse a hunk header like `@@ -1,3 +1,4 @@` into the old line count, the
+/// first new line, and the new line count.
+fn parse_hunk_header(line: &str) -> Option<(usize, usize, usize)> {
+    let mut ranges = line.strip_prefix("@@ ")?.split(' ');
+    let old = ranges.next()?.strip_prefix('-')?;
+    let new = ranges.next()?.strip_prefix('+')?;
+    // the count is omitted when it's 1, e.g. `@@ -1 +1 @@`.
+    let parse_range = |range: &str| -> Option<(usize, usize)> {
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (_, old_count) = parse_range(old)?;
+    let (new_start, new_count) = parse_range(new)?;
+    Some((old_count, new_start, new_count))
+}
+
+/// The path from a `+++ b/path` header, `None` for deleted files.
+fn parse_new_path(header: &str) -> Option<PathBuf> {
+    // some tools add a timestamp after a tab.
+    let path = header.split('\t').next()?.trim_end();
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(PathBuf::from(path.strip_prefix("b/").unwrap_or(path)))
+}
+
+/// The first and last line of the statement, ignoring the comments before
+/// it, so changing a comment doesn't report the statement that follows.
+fn statement_lines(sql: &str, violation: &RuleViolation) -> (usize, usize) {
+    let stmt = strip_leading_comments(statement_text(sql, violation));
+    let (start, _) = violation.location(sql);
+    (start, start + stmt.trim_end().matches('\n').count())
+}
+
+impl AddedLines {
+    #[must_use]
+    pub fn parse(diff: &str) -> Self {
+        let mut files: HashMap<PathBuf, Vec<(usize, usize)>> = HashMap::new();
+        let mut path = None;
+        // lines left in the current hunk, so removed lines that look like
+        // headers, e.g. a `-- comment` line, aren't mistaken for one.
+        let mut old_remaining = 0;
+        let mut new_remaining = 0;
+        let mut line_number = 0;
+        for line in diff.lines() {
+            if old_remaining == 0 && new_remaining == 0 {
+                if let Some(header) = line.strip_prefix("+++ ") {
+                    path = parse_new_path(header);
+                } else if let Some((old_count, new_start, new_count)) = parse_hunk_header(line) {
+                    old_remaining = old_count;
+                    new_remaining = new_count;
+                    line_number = new_start;
+                }
+                continue;
+            }
+            if line.starts_with('+') {
+                if let Some(path) = &path {
+                    let ranges = files.entry(path.clone()).or_default();
+                    match ranges.last_mut() {
+                        Some((_, end)) if *end + 1 == line_number => *end = line_number,
+                        _ => ranges.push((line_number, line_number)),
+                    }
+                }
+                new_remaining = new_remaining.saturating_sub(1);
+                line_number += 1;
+            } else if line.starts_with('-') {
+                old_remaining = old_remaining.saturating_sub(1);
+            } else if !line.starts_with('\\') {
+                // `\ No newline at end of file` isn't part of either file.
+                old_remaining = old_remaining.saturating_sub(1);
+                new_remaining = new_remaining.saturating_sub(1);
+                line_number += 1;
+            }
+        }
+        Self { files }
+    }
+
+    /// The added lines for `path`, which matches a diff path it ends with,
+    /// since diff paths are relative to the repository root.
+    fn ranges(&self, path: &Path) -> Option<&[(usize, usize)]> {
+        self.files
+            .iter()
+            .find(|(diff_path, _)| path.ends_with(diff_path))
+            .map(|(_, ranges)| ranges.as_slice())
+    }
+
+    /// Remove violations for statements that don't touch an added line.
+    /// Files that aren't in the diff are unchanged, so all of their
+    /// violations are removed.
+    #[must_use]
+    pub fn filter(
+        &self,
+        path: &Path,
+        sql: &str,
+        violations: Vec<RuleViolation>,
+    ) -> Vec<RuleViolation> {
+        let Some(ranges) = self.ranges(path) else {
+            return vec![];
+        };
+        violations
+            .into_iter()
+            .filter(|v| {
+                let (start, end) = statement_lines(sql, v);
+                ranges
+                    .iter()
+                    .any(|(added_start, added_end)| *added_start <= end && start <= *added_end)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test_diff {
+    use std::path::Path;
+
+    use super::AddedLines;
+    use crate::{check_sql_with_rule, violations::RuleViolationKind};
+
+    const DIFF: &str = r#"diff --git a/migrations/0002_add_index.sql b/migrations/0002_add_index.sql
+index 3b18e51..a1b2c3d 100644
+--- a/migrations/0002_add_index.sql
++++ b/migrations/0002_add_index.sql
+@@ -1,3 +1,4 @@
+--- drop the column
++-- drop the column and add an index
+ ALTER TABLE "core_recipe" DROP COLUMN "bar";
++CREATE INDEX "core_recipe_foo_idx" ON "core_recipe" ("foo");
+ SELECT 1;
+diff --git a/migrations/0001_old.sql b/migrations/0001_old.sql
+deleted file mode 100644
+--- a/migrations/0001_old.sql
++++ /dev/null
+@@ -1 +0,0 @@
+-SELECT 1;
+"#;
+
+    #[test]
+    fn test_parse() {
+        let added = AddedLines::parse(DIFF);
+        assert_eq!(
+            added.ranges(Path::new("migrations/0002_add_index.sql")),
+            Some([(1, 1), (3, 3)].as_slice())
+        );
+        assert_eq!(
+            added.ranges(Path::new("./project/migrations/0002_add_index.sql")),
+            Some([(1, 1), (3, 3)].as_slice())
+        );
+        assert_eq!(added.ranges(Path::new("migrations/0001_old.sql")), None);
+    }
+
+    #[test]
+    fn test_filter_only_added_statement() {
+        let sql = r#"-- drop the column and add an index
+ALTER TABLE "core_recipe" DROP COLUMN "bar";
+CREATE INDEX "core_recipe_foo_idx" ON "core_recipe" ("foo");
+SELECT 1;
+"#;
+        let violations = check_sql_with_rule(
+            sql,
+            &RuleViolationKind::RequireConcurrentIndexCreation,
+            None,
+            false,
+        )
+        .unwrap();
+        assert_eq!(violations.len(), 1);
+        let added = AddedLines::parse(DIFF);
+        let path = Path::new("migrations/0002_add_index.sql");
+        assert_eq!(added.filter(path, sql, violations).len(), 1);
+
+        // the DROP COLUMN is on an unchanged line.
+        let violations =
+            check_sql_with_rule(sql, &RuleViolationKind::BanDropColumn, None, false).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(added.filter(path, sql, violations), vec![]);
+
+        // files that aren't in the diff are unchanged.
+        let violations =
+            check_sql_with_rule(sql, &RuleViolationKind::BanDropColumn, None, false).unwrap();
+        assert_eq!(
+            added.filter(Path::new("migrations/0003_other.sql"), sql, violations),
+            vec![]
+        );
+    }
+}
@@ -5,6 +5,17 @@ pub enum CheckSqlError {
     ParsingSql(PgQueryError),
 }
 
+impl CheckSqlError {
+    /// Byte offset into the SQL where parsing failed, if Postgres reported
+    /// one.
+    #[must_use]
+    pub fn offset(&self) -> Option<usize> {
+        match *self {
+            Self::ParsingSql(ref err) => err.offset(),
+        }
+    }
+}
+
 impl std::fmt::Display for CheckSqlError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match *self {
@@ -18,3 +29,43 @@ impl std::convert::From<PgQueryError> for CheckSqlError {
         Self::ParsingSql(err)
     }
 }
+
+#[derive(Debug)]
+pub enum CheckFileError {
+    CheckSql(CheckSqlError),
+    IoError(std::io::Error),
+    /// The SQL isn't valid UTF-8.
+    InvalidUtf8(std::string::FromUtf8Error),
+}
+
+impl std::fmt::Display for CheckFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Self::CheckSql(ref err) => err.fmt(f),
+            Self::IoError(ref err) => err.fmt(f),
+            Self::InvalidUtf8(ref err) => write!(
+                f,
+                "SQL isn't valid UTF-8, invalid byte at offset {}",
+                err.utf8_error().valid_up_to()
+            ),
+        }
+    }
+}
+
+impl std::convert::From<std::io::Error> for CheckFileError {
+    fn from(err: std::io::Error) -> Self {
+        Self::IoError(err)
+    }
+}
+
+impl std::convert::From<std::string::FromUtf8Error> for CheckFileError {
+    fn from(err: std::string::FromUtf8Error) -> Self {
+        Self::InvalidUtf8(err)
+    }
+}
+
+impl std::convert::From<CheckSqlError> for CheckFileError {
+    fn from(err: CheckSqlError) -> Self {
+        Self::CheckSql(err)
+    }
+}
@@ -0,0 +1,187 @@
+use squawk_parser::ast::{
+    AlterTableCmds, AlterTableDef, AlterTableType, ConstrType, ObjectType, RangeVar, Stmt,
+};
+use squawk_parser::parse::parse_sql_query;
+
+use crate::baseline::{statement_text, strip_leading_comments};
+use crate::violations::{RuleViolation, RuleViolationKind};
+
+/// The SQL of the statement a violation points at, without the comments
+/// and whitespace preceding it or the trailing `;`.
+fn statement_sql<'a>(sql: &'a str, violation: &RuleViolation) -> &'a str {
+    strip_leading_comments(statement_text(sql, violation))
+        .trim_end()
+        .trim_end_matches(';')
+        .trim_end()
+}
+
+fn quote_ident(name: &str) -> String {
+    format!(r#""{}""#, name.replace('"', r#""""#))
+}
+
+fn table_name(relation: &RangeVar) -> String {
+    match &relation.schemaname {
+        Some(schema) => format!("{}.{}", quote_ident(schema), quote_ident(&relation.relname)),
+        None => quote_ident(&relation.relname),
+    }
+}
+
+/// Add `CONCURRENTLY` after the `INDEX` keyword, e.g. `CREATE UNIQUE INDEX`
+/// or `DROP INDEX`, which comes before any identifiers in the statement.
+fn add_concurrently(text: &str) -> Option<String> {
+    let mut offset = 0;
+    for word in text.split_inclusive(char::is_whitespace) {
+        if word.trim_end().eq_ignore_ascii_case("index") {
+            let end = offset + "index".len();
+            return Some(format!("{} CONCURRENTLY{};", &text[..end], &text[end..]));
+        }
+        offset += word.len();
+    }
+    None
+}
+
+/// Split adding a constraint into adding it as `NOT VALID` and validating it
+/// separately. Only named `CHECK` and foreign key constraints can be added as
+/// `NOT VALID` and validated by name.
+fn add_not_valid(text: &str, stmt: &Stmt) -> Option<String> {
+    let Stmt::AlterTableStmt(stmt) = stmt else {
+        return None;
+    };
+    let [AlterTableCmds::AlterTableCmd(cmd)] = stmt.cmds.as_slice() else {
+        return None;
+    };
+    let Some(AlterTableDef::Constraint(constraint)) = &cmd.def else {
+        return None;
+    };
+    if cmd.subtype != AlterTableType::AddConstraint
+        || !constraint.initially_valid
+        || !matches!(constraint.contype, ConstrType::Check | ConstrType::Foreign)
+    {
+        return None;
+    }
+    let name = constraint.conname.as_ref()?;
+    Some(format!(
+        "{text} NOT VALID;\nALTER TABLE {} VALIDATE CONSTRAINT {};",
+        table_name(&stmt.relation),
+        quote_ident(name)
+    ))
+}
+
+/// Suggest SQL to replace the statement reported by `violation`, e.g. for an
+/// editor to offer as a quick-fix. The replacement can be multiple
+/// statements.
+///
+/// `sql` is the SQL that was checked. Returns `None` when the rule has no safe
+/// mechanical fix for the statement.
+#[must_use]
+pub fn suggest_fix(sql: &str, violation: &RuleViolation) -> Option<String> {
+    let text = statement_sql(sql, violation);
+    let tree = parse_sql_query(text).ok()?;
+    let [raw_stmt] = tree.as_slice() else {
+        return None;
+    };
+    match violation.kind {
+        RuleViolationKind::RequireConcurrentIndexCreation => match &raw_stmt.stmt {
+            Stmt::IndexStmt(stmt) if !stmt.concurrent => add_concurrently(text),
+            _ => None,
+        },
+        RuleViolationKind::RequireConcurrentIndexDeletion => match &raw_stmt.stmt {
+            Stmt::DropStmt(stmt) if !stmt.concurrent && stmt.remove_type == ObjectType::Index => {
+                add_concurrently(text)
+            }
+            _ => None,
+        },
+        RuleViolationKind::ConstraintMissingNotValid
+        | RuleViolationKind::AddingForeignKeyConstraint => add_not_valid(text, &raw_stmt.stmt),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test_fixes {
+    use super::suggest_fix;
+    use crate::{check_sql_with_rule, violations::RuleViolationKind};
+
+    fn fixes(sql: &str, kind: &RuleViolationKind) -> Vec<Option<String>> {
+        check_sql_with_rule(sql, kind, None, false)
+            .unwrap()
+            .iter()
+            .map(|v| suggest_fix(sql, v))
+            .collect()
+    }
+
+    #[test]
+    fn test_create_index_concurrently() {
+        let sql = r#"
+-- add an index
+CREATE INDEX "field_name_idx" ON "table_name" ("field_name");
+create unique index "other_idx" on "table_name" ("other");
+"#;
+        assert_eq!(
+            fixes(sql, &RuleViolationKind::RequireConcurrentIndexCreation),
+            vec![
+                Some(
+                    r#"CREATE INDEX CONCURRENTLY "field_name_idx" ON "table_name" ("field_name");"#
+                        .into()
+                ),
+                Some(
+                    r#"create unique index CONCURRENTLY "other_idx" on "table_name" ("other");"#
+                        .into()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_drop_index_concurrently() {
+        let sql = r#"DROP INDEX IF EXISTS "field_name_idx";"#;
+        assert_eq!(
+            fixes(sql, &RuleViolationKind::RequireConcurrentIndexDeletion),
+            vec![Some(
+                r#"DROP INDEX CONCURRENTLY IF EXISTS "field_name_idx";"#.into()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_constraint_not_valid() {
+        let sql = r#"
+ALTER TABLE "app"."accounts" ADD CONSTRAINT "positive_balance" CHECK ("balance" >= 0);
+"#;
+        assert_eq!(
+            fixes(sql, &RuleViolationKind::ConstraintMissingNotValid),
+            vec![Some(
+                r#"ALTER TABLE "app"."accounts" ADD CONSTRAINT "positive_balance" CHECK ("balance" >= 0) NOT VALID;
+ALTER TABLE "app"."accounts" VALIDATE CONSTRAINT "positive_balance";"#
+                    .into()
+            )]
+        );
+
+        let sql = r#"ALTER TABLE "email" ADD CONSTRAINT "fk_user" FOREIGN KEY ("user_id") REFERENCES "user" ("id");"#;
+        assert_eq!(
+            fixes(sql, &RuleViolationKind::AddingForeignKeyConstraint),
+            vec![Some(
+                r#"ALTER TABLE "email" ADD CONSTRAINT "fk_user" FOREIGN KEY ("user_id") REFERENCES "user" ("id") NOT VALID;
+ALTER TABLE "email" VALIDATE CONSTRAINT "fk_user";"#
+                    .into()
+            )]
+        );
+    }
+
+    /// Without a name there's no way to validate the constraint afterwards,
+    /// and we don't split up statements that make other changes.
+    #[test]
+    fn test_no_fix() {
+        let sql = r#"
+ALTER TABLE "accounts" ADD CHECK ("balance" >= 0);
+ALTER TABLE "accounts" ADD COLUMN "limit" integer, ADD CONSTRAINT "positive_limit" CHECK ("limit" >= 0);
+"#;
+        assert_eq!(
+            fixes(sql, &RuleViolationKind::ConstraintMissingNotValid),
+            vec![None, None]
+        );
+
+        let sql = r#"ALTER TABLE "foo" DROP COLUMN "bar";"#;
+        assert_eq!(fixes(sql, &RuleViolationKind::BanDropColumn), vec![None]);
+    }
+}
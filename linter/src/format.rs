@@ -0,0 +1,106 @@
+use std::fmt::Write;
+
+use crate::baseline::{statement_start, statement_text, strip_leading_comments};
+use crate::violations::{RuleViolation, ViolationMessage};
+
+/// Render a violation like a rustc diagnostic, with the first line of the
+/// statement underlined and the note, help, and doc link messages below it.
+///
+/// Statements spanning several lines are shortened to their first line
+/// followed by `...`. Comments before the statement are skipped so the caret
+/// points at the statement itself.
+///
+/// ```
+/// use squawk_linter::format::format_violation;
+/// use squawk_linter::{check_sql_with_rule, violations::RuleViolationKind};
+///
+/// let sql = r#"ALTER TABLE "core_recipe" DROP COLUMN "foo";"#;
+/// let violations =
+///     check_sql_with_rule(sql, &RuleViolationKind::BanDropColumn, None, false).unwrap();
+/// let formatted = format_violation(&violations[0], sql, "0001_drop_foo.sql");
+/// assert!(formatted.starts_with("error: ban-drop-column\n --> 0001_drop_foo.sql:1:1\n"));
+/// ```
+#[must_use]
+pub fn format_violation(violation: &RuleViolation, sql: &str, filename: &str) -> String {
+    let stmt = strip_leading_comments(statement_text(sql, violation));
+    let (line_number, column) = violation.location(sql);
+    let line_start = sql[..statement_start(sql, violation)]
+        .rfind('\n')
+        .map_or(0, |idx| idx + 1);
+    let line = sql[line_start..]
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim_end();
+
+    let stmt = stmt.trim_end();
+    let (first_line, is_multi_line) = match stmt.split_once('\n') {
+        Some((first_line, _)) => (first_line.trim_end(), true),
+        None => (stmt, false),
+    };
+
+    let gutter = " ".repeat(line_number.to_string().len());
+    let mut out = String::new();
+    // writing to a String can't fail.
+    let _ = writeln!(out, "{}: {}", violation.severity, violation.kind);
+    let _ = writeln!(out, "{gutter}--> {filename}:{line_number}:{column}");
+    let _ = writeln!(out, "{gutter} |");
+    let _ = writeln!(out, "{line_number} | {line}");
+    let _ = write!(
+        out,
+        "{gutter} | {}{}",
+        " ".repeat(column - 1),
+        "^".repeat(first_line.chars().count().max(1))
+    );
+    if is_multi_line {
+        let _ = write!(out, "\n...");
+    }
+    if !violation.messages.is_empty() {
+        let _ = write!(out, "\n{gutter} |");
+    }
+    for message in &violation.messages {
+        let _ = match message {
+            ViolationMessage::Note(text) => write!(out, "\n{gutter} = note: {text}"),
+            ViolationMessage::Help(text) => write!(out, "\n{gutter} = help: {text}"),
+            ViolationMessage::Url(url) => {
+                write!(out, "\n{gutter} = note: for more information, see {url}")
+            }
+        };
+    }
+    out
+}
+
+#[cfg(test)]
+mod test_format {
+    use super::format_violation;
+    use crate::{check_sql_with_rule, violations::RuleViolationKind};
+    use insta::assert_display_snapshot;
+
+    #[test]
+    fn test_single_line() {
+        let sql = r#"
+-- drop the unused column
+ALTER TABLE "core_recipe" DROP COLUMN "foo";
+"#;
+        let violations =
+            check_sql_with_rule(sql, &RuleViolationKind::BanDropColumn, None, false).unwrap();
+        let formatted = format_violation(&violations[0], sql, "migrations/0002_drop_foo.sql");
+        assert_display_snapshot!(formatted);
+    }
+
+    #[test]
+    fn test_multi_line() {
+        let sql = r#"
+BEGIN;
+  CREATE TABLE "core_bar" (
+    "id" serial NOT NULL PRIMARY KEY,
+    "alpha" varchar(100) NOT NULL
+  );
+COMMIT;
+"#;
+        let violations =
+            check_sql_with_rule(sql, &RuleViolationKind::PreferTextField, None, false).unwrap();
+        let formatted = format_violation(&violations[0], sql, "migrations/0001_init.sql");
+        assert_display_snapshot!(formatted);
+    }
+}
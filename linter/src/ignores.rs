@@ -0,0 +1,425 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use squawk_parser::ast::RawStmt;
+
+use crate::violations::{RuleViolation, RuleViolationKind};
+
+const IGNORE_DIRECTIVE: &str = "squawk-ignore";
+const NEXT_LINE_SUFFIX: &str = "-next-line";
+const FILE_SUFFIX: &str = "-file";
+
+/// What a directive applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    /// `-- squawk-ignore`, anywhere in the comments before a statement.
+    Statement,
+    /// `-- squawk-ignore-next-line`, on the line right before a statement.
+    NextLine,
+    /// `-- squawk-ignore-file`, every statement from the next one to the end
+    /// of the file.
+    File,
+}
+
+/// Rules suppressed for a statement via a `-- squawk-ignore` comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Ignored {
+    /// A bare `-- squawk-ignore` suppresses every rule.
+    All,
+    Rules(HashSet<RuleViolationKind>),
+}
+
+impl Ignored {
+    fn contains(&self, kind: &RuleViolationKind) -> bool {
+        match self {
+            Self::All => true,
+            Self::Rules(rules) => rules.contains(kind),
+        }
+    }
+
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Rules(mut rules), Self::Rules(other_rules)) => {
+                rules.extend(other_rules);
+                Self::Rules(rules)
+            }
+            _ => Self::All,
+        }
+    }
+}
+
+/// Parse the body of a `--` comment, e.g. ` squawk-ignore ban-drop-column`.
+///
+/// Rule names can be separated by commas or whitespace. Unknown rule names
+/// are skipped.
+fn parse_directive(comment: &str) -> Option<(Scope, Ignored)> {
+    let rest = comment.trim().strip_prefix(IGNORE_DIRECTIVE)?;
+    let (scope, rest) = if let Some(rest) = rest.strip_prefix(NEXT_LINE_SUFFIX) {
+        (Scope::NextLine, rest)
+    } else if let Some(rest) = rest.strip_prefix(FILE_SUFFIX) {
+        (Scope::File, rest)
+    } else {
+        (Scope::Statement, rest)
+    };
+    // don't match other directives that share the prefix.
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let names: Vec<&str> = rest
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|name| !name.is_empty())
+        .collect();
+    if names.is_empty() {
+        return Some((scope, Ignored::All));
+    }
+    Some((
+        scope,
+        Ignored::Rules(
+            names
+                .into_iter()
+                .filter_map(|name| RuleViolationKind::from_str(name).ok())
+                .collect(),
+        ),
+    ))
+}
+
+fn merge(ignored: Option<Ignored>, directive: Ignored) -> Ignored {
+    match ignored {
+        Some(existing) => existing.merge(directive),
+        None => directive,
+    }
+}
+
+/// The directives in the comments preceding a statement.
+#[derive(Debug, Default)]
+struct Directives {
+    /// Rules ignored for this statement.
+    statement: Option<Ignored>,
+    /// Rules ignored for this statement and every following statement.
+    file: Option<Ignored>,
+}
+
+/// `CheckSqlConfig::ignore_psql_meta_commands` moves the start of a statement
+/// past the psql meta-commands before it, so look back over them, and the
+/// comments between them, to the start Postgres gave the statement.
+fn directives_start(sql: &str, start: usize, prev_end: usize) -> usize {
+    let Some(before) = sql.get(prev_end..start) else {
+        return start;
+    };
+    let mut directives_start = start;
+    let mut line_end = start;
+    let mut after_meta_command = false;
+    for line in before.rsplit('\n') {
+        let line_start = line_end - line.len();
+        let text = line.trim();
+        if text.starts_with('\\') {
+            after_meta_command = true;
+        } else if !after_meta_command || !(text.is_empty() || text.starts_with("--")) {
+            break;
+        }
+        directives_start = line_start;
+        line_end = line_start.saturating_sub(1);
+    }
+    directives_start
+}
+
+/// Find the ignore directives in the comments preceding a statement.
+///
+/// The span Postgres gives us for a statement starts right after the previous
+/// statement, so any comments between the two are at the start of the span.
+fn statement_directives(sql: &str, stmt: &RawStmt, prev_end: usize) -> Directives {
+    #[allow(clippy::cast_sign_loss)]
+    let start = stmt.stmt_location.max(0) as usize;
+    #[allow(clippy::cast_sign_loss)]
+    let end = stmt
+        .stmt_len
+        .map_or(sql.len(), |len| start + len.max(0) as usize)
+        .min(sql.len());
+    let Some(text) = sql.get(directives_start(sql, start, prev_end)..end) else {
+        return Directives::default();
+    };
+
+    let mut directives = Directives::default();
+    // a `squawk-ignore-next-line` directive from the previous line.
+    let mut next_line = None;
+    for line in text.lines() {
+        let line = line.trim();
+        // psql meta-commands, e.g. `\set`, aren't part of the statement.
+        if line.is_empty() || line.starts_with('\\') {
+            next_line = None;
+            continue;
+        }
+        let Some(comment) = line.strip_prefix("--") else {
+            if let Some(directive) = next_line {
+                directives.statement = Some(merge(directives.statement, directive));
+            }
+            break;
+        };
+        next_line = None;
+        match parse_directive(comment) {
+            Some((Scope::Statement, directive)) => {
+                directives.statement = Some(merge(directives.statement, directive));
+            }
+            Some((Scope::NextLine, directive)) => next_line = Some(directive),
+            Some((Scope::File, directive)) => {
+                directives.file = Some(merge(directives.file, directive));
+            }
+            None => {}
+        }
+    }
+    directives
+}
+
+/// The rules ignored for each statement, keyed by the start of the
+/// statement's span.
+#[derive(Debug, Default)]
+pub struct Ignores(HashMap<i32, Ignored>);
+
+impl Ignores {
+    pub fn new(sql: &str, tree: &[RawStmt]) -> Self {
+        let mut ignores: HashMap<i32, Ignored> = HashMap::new();
+        // rules ignored by `squawk-ignore-file` directives so far.
+        let mut file_ignored: Option<Ignored> = None;
+        let mut prev_end = 0;
+        for stmt in tree {
+            let directives = statement_directives(sql, stmt, prev_end);
+            if let Some(len) = stmt.stmt_len {
+                #[allow(clippy::cast_sign_loss)]
+                let end = (stmt.stmt_location + len).max(0) as usize;
+                prev_end = end;
+            }
+            if let Some(directive) = directives.file {
+                file_ignored = Some(merge(file_ignored, directive));
+            }
+            let ignored = match directives.statement {
+                Some(statement) => Some(merge(file_ignored.clone(), statement)),
+                None => file_ignored.clone(),
+            };
+            if let Some(ignored) = ignored {
+                ignores.insert(stmt.stmt_location, ignored);
+            }
+        }
+        Self(ignores)
+    }
+
+    /// Whether a directive that applies to the violation's statement lists
+    /// its rule, so a narrower directive can't re-enable a rule ignored by
+    /// `-- squawk-ignore-file`.
+    pub fn contains(&self, violation: &RuleViolation) -> bool {
+        self.0
+            .get(&violation.span.start)
+            .map_or(false, |ignored| ignored.contains(&violation.kind))
+    }
+}
+
+/// Drop any violations suppressed by an ignore directive.
+pub fn remove_ignored_violations(
+    sql: &str,
+    tree: &[RawStmt],
+    violations: Vec<RuleViolation>,
+) -> Vec<RuleViolation> {
+    let ignores = Ignores::new(sql, tree);
+    if ignores.0.is_empty() {
+        return violations;
+    }
+    violations
+        .into_iter()
+        .filter(|v| !ignores.contains(v))
+        .collect()
+}
+
+#[cfg(test)]
+mod test_ignores {
+    use crate::{check_sql, config::CheckSqlConfig, violations::RuleViolationKind};
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolationKind> {
+        let config = CheckSqlConfig {
+            excluded_rules: vec![RuleViolationKind::PreferRobustStmts],
+            ..CheckSqlConfig::default()
+        };
+        check_sql(sql, &config)
+            .unwrap()
+            .into_iter()
+            .map(|v| v.kind)
+            .collect()
+    }
+
+    #[test]
+    fn test_ignore_rule_for_next_statement() {
+        let sql = r#"
+CREATE INDEX "field_name_idx" ON "table_name" ("field_name");
+-- squawk-ignore require-concurrent-index-creation
+CREATE INDEX "other_field_idx" ON "table_name" ("other_field");
+CREATE INDEX "another_field_idx" ON "table_name" ("another_field");
+"#;
+        let res = check_sql(sql, &CheckSqlConfig::default()).unwrap();
+        let kinds_and_starts: Vec<(RuleViolationKind, i32)> = res
+            .into_iter()
+            .filter(|v| v.kind == RuleViolationKind::RequireConcurrentIndexCreation)
+            .map(|v| (v.kind, v.span.start))
+            .collect();
+        assert_eq!(
+            kinds_and_starts,
+            vec![
+                (RuleViolationKind::RequireConcurrentIndexCreation, 0),
+                (RuleViolationKind::RequireConcurrentIndexCreation, 177),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignore_only_listed_rules() {
+        let sql = r#"
+SELECT 1;
+-- squawk-ignore ban-drop-column
+ALTER TABLE "foo" DROP COLUMN "bar", ALTER COLUMN "baz" DROP NOT NULL;
+"#;
+        assert_eq!(lint_sql(sql), vec![RuleViolationKind::BanDropNotNull]);
+    }
+
+    #[test]
+    fn test_ignore_multiple_rules() {
+        let sql = r#"
+SELECT 1;
+-- some other comment
+-- squawk-ignore ban-drop-column, ban-drop-not-null
+ALTER TABLE "foo" DROP COLUMN "bar", ALTER COLUMN "baz" DROP NOT NULL;
+"#;
+        assert_eq!(lint_sql(sql), vec![]);
+    }
+
+    #[test]
+    fn test_bare_ignore_suppresses_all_rules() {
+        let sql = r#"
+ALTER TABLE "foo" DROP COLUMN "bar";
+-- squawk-ignore
+ALTER TABLE "foo" DROP COLUMN "bar", ALTER COLUMN "baz" DROP NOT NULL;
+"#;
+        assert_eq!(lint_sql(sql), vec![RuleViolationKind::BanDropColumn]);
+    }
+
+    /// `ignore_psql_meta_commands` moves the start of a statement past the
+    /// meta-commands before it, but directives above them still apply.
+    #[test]
+    fn test_ignore_above_psql_meta_command() {
+        let sql = r#"
+-- squawk-ignore ban-drop-column
+\set ON_ERROR_STOP on
+ALTER TABLE "foo" DROP COLUMN "bar";
+-- squawk-ignore ban-drop-column
+\echo 'dropping baz'
+-- drop the column
+\set VERBOSITY verbose
+ALTER TABLE "foo" DROP COLUMN "baz";
+\echo 'dropping qux'
+ALTER TABLE "foo" DROP COLUMN "qux";
+"#;
+        let config = CheckSqlConfig {
+            excluded_rules: vec![RuleViolationKind::PreferRobustStmts],
+            ignore_psql_meta_commands: true,
+            ..CheckSqlConfig::default()
+        };
+        let res = check_sql(sql, &config).unwrap();
+        assert_eq!(
+            res.iter().map(|v| v.kind.clone()).collect::<Vec<_>>(),
+            vec![RuleViolationKind::BanDropColumn]
+        );
+        assert_eq!(res[0].location(sql), (11, 1));
+    }
+
+    /// Comments inside of a statement aren't treated as directives.
+    #[test]
+    fn test_ignore_comment_after_statement_start() {
+        let sql = r#"
+ALTER TABLE "foo"
+-- squawk-ignore
+DROP COLUMN "bar";
+"#;
+        assert_eq!(lint_sql(sql), vec![RuleViolationKind::BanDropColumn]);
+    }
+
+    #[test]
+    fn test_ignore_next_line() {
+        let sql = r#"
+SELECT 1;
+-- squawk-ignore-next-line ban-drop-column
+ALTER TABLE "foo" DROP COLUMN "bar";
+"#;
+        assert_eq!(lint_sql(sql), vec![]);
+
+        // the directive only applies to the line right after it.
+        let sql = r#"
+SELECT 1;
+-- squawk-ignore-next-line ban-drop-column
+-- drop the column
+ALTER TABLE "foo" DROP COLUMN "bar";
+SELECT 1;
+-- squawk-ignore-next-line ban-drop-column
+
+ALTER TABLE "foo" DROP COLUMN "bar";
+"#;
+        assert_eq!(
+            lint_sql(sql),
+            vec![
+                RuleViolationKind::BanDropColumn,
+                RuleViolationKind::BanDropColumn
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignore_file() {
+        let sql = r#"
+ALTER TABLE "foo" DROP COLUMN "bar";
+-- squawk-ignore-file ban-drop-column
+ALTER TABLE "foo" DROP COLUMN "baz";
+SELECT 1;
+ALTER TABLE "foo" DROP COLUMN "qux", ALTER COLUMN "baz" DROP NOT NULL;
+"#;
+        let res = check_sql(sql, &CheckSqlConfig::default()).unwrap();
+        let kinds_and_starts: Vec<(RuleViolationKind, i32)> = res
+            .into_iter()
+            .filter(|v| {
+                matches!(
+                    v.kind,
+                    RuleViolationKind::BanDropColumn | RuleViolationKind::BanDropNotNull
+                )
+            })
+            .map(|v| (v.kind, v.span.start))
+            .collect();
+        // only the statements after the directive are ignored.
+        assert_eq!(
+            kinds_and_starts,
+            vec![
+                (RuleViolationKind::BanDropColumn, 0),
+                (RuleViolationKind::BanDropNotNull, 122),
+            ]
+        );
+    }
+
+    /// File and statement directives both apply, and a statement directive
+    /// can't re-enable a rule ignored for the file.
+    #[test]
+    fn test_ignore_file_with_inline_ignore() {
+        let sql = r#"
+-- squawk-ignore-file ban-drop-column
+ALTER TABLE "foo" DROP COLUMN "bar";
+-- squawk-ignore ban-drop-not-null
+ALTER TABLE "foo" DROP COLUMN "bar", ALTER COLUMN "baz" DROP NOT NULL;
+-- squawk-ignore-next-line ban-drop-not-null
+ALTER TABLE "foo" DROP COLUMN "bar", ALTER COLUMN "baz" DROP NOT NULL;
+ALTER TABLE "foo" DROP COLUMN "bar", ALTER COLUMN "baz" DROP NOT NULL;
+"#;
+        assert_eq!(lint_sql(sql), vec![RuleViolationKind::BanDropNotNull]);
+    }
+
+    #[test]
+    fn test_bare_ignore_file_suppresses_all_rules() {
+        let sql = r#"
+-- squawk-ignore-file
+ALTER TABLE "foo" DROP COLUMN "bar";
+ALTER TABLE "foo" ALTER COLUMN "baz" DROP NOT NULL;
+"#;
+        assert_eq!(lint_sql(sql), vec![]);
+    }
+}
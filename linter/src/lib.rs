@@ -1,19 +1,66 @@
 #![allow(clippy::shadow_unrelated)]
 #![allow(clippy::missing_errors_doc)]
+#[allow(clippy::module_name_repetitions)]
+pub mod baseline;
+#[allow(clippy::module_name_repetitions)]
+pub mod config;
+pub mod diff;
 pub mod errors;
+pub mod fixes;
+pub mod format;
+mod ignores;
+mod psql;
 pub mod rules;
 pub mod versions;
 pub mod violations;
 #[macro_use]
 extern crate lazy_static;
 
-use crate::errors::CheckSqlError;
+use crate::baseline::statement_text;
+use crate::config::{CheckSqlConfig, RuleOptions, SortOrder};
+use crate::errors::{CheckFileError, CheckSqlError};
+use crate::ignores::{remove_ignored_violations, Ignores};
+use crate::rules::adding_column_with_domain_type;
+use crate::rules::adding_enum_value_in_transaction;
+use crate::rules::adding_identity_column;
 use crate::rules::adding_required_field;
+use crate::rules::adding_serial_column;
+use crate::rules::adding_stored_generated_column;
+use crate::rules::ban_alter_owner;
+use crate::rules::ban_cluster;
 use crate::rules::ban_concurrent_index_creation_in_transaction;
+use crate::rules::ban_create_table_as;
+use crate::rules::ban_deprecated_type;
+use crate::rules::ban_disabling_statement_timeout;
 use crate::rules::ban_drop_not_null;
+use crate::rules::ban_grant_in_migration;
+use crate::rules::ban_money_type;
+use crate::rules::ban_set_unlogged;
+use crate::rules::ban_truncate;
+use crate::rules::ban_vacuum_full;
+use crate::rules::changing_object_schema;
+use crate::rules::changing_storage_parameter;
+use crate::rules::disallow_exclusion_constraint;
+use crate::rules::duplicate_index;
+use crate::rules::mixing_ddl_and_dml;
+use crate::rules::naming_convention;
+use crate::rules::non_immutable_check_constraint;
+use crate::rules::partition_lock_hazard;
 use crate::rules::prefer_big_int;
 use crate::rules::prefer_identity;
+use crate::rules::prefer_jsonb;
+use crate::rules::prefer_primary_key;
+use crate::rules::renaming_constraint;
+use crate::rules::require_concurrent_reindex;
+use crate::rules::require_if_not_exists;
+use crate::rules::require_lock_timeout;
+use crate::rules::require_replica_identity;
+use crate::rules::require_validate_constraint;
+use crate::rules::setting_not_null_on_existing_column;
+use crate::rules::setting_search_path;
+use crate::rules::too_many_locking_statements;
 use crate::rules::transaction_nesting;
+use crate::rules::unbounded_data_modification;
 use crate::rules::{
     adding_field_with_default, adding_foreign_key_constraint, adding_not_nullable_field,
     adding_primary_key_constraint, ban_char_type, ban_drop_column, ban_drop_database,
@@ -22,24 +69,83 @@ use crate::rules::{
     prefer_timestamptz, renaming_column, renaming_table, require_concurrent_index_creation,
     require_concurrent_index_deletion,
 };
-use crate::violations::{RuleViolation, RuleViolationKind, ViolationMessage};
+use crate::violations::{
+    LockLevel, RuleViolation, RuleViolationKind, Severity, StatementKind, ViolationMessage,
+};
 use squawk_parser::ast::RawStmt;
-use squawk_parser::parse::parse_sql_query;
-use std::collections::HashSet;
+use squawk_parser::parse::{parse_sql_query, parse_sql_query_with_recovery, SkippedStmt};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use versions::Version;
 
 #[derive(Clone)]
 pub struct SquawkRule {
     pub name: RuleViolationKind,
-    func: fn(&[RawStmt], Option<Version>, bool) -> Vec<RuleViolation>,
+    /// Default severity for violations of this rule.
+    pub severity: Severity,
+    /// The table lock taken by statements this rule reports, if known.
+    pub lock_level: Option<LockLevel>,
+    /// Only run the rule when it has an entry in `CheckSqlConfig::rules`.
+    pub opt_in: bool,
+    /// Called with the parsed statements, `CheckSqlConfig::pg_version`,
+    /// `CheckSqlConfig::assume_in_transaction`, and the rule's options.
+    pub func: fn(&[RawStmt], Option<Version>, bool, &RuleOptions) -> Vec<RuleViolation>,
     pub messages: Vec<ViolationMessage>,
+    /// Longer explanation of the problem and how to fix it, in markdown.
+    pub explanation: Option<&'static str>,
 }
 
 lazy_static! {
     pub static ref RULES: Vec<SquawkRule> = vec![
+    SquawkRule {
+        name: RuleViolationKind::AddingColumnWithDomainType,
+        severity: Severity::Info,
+        lock_level: Some(LockLevel::AccessExclusive),
+        opt_in: true,
+        func: adding_column_with_domain_type,
+        messages: vec![
+            ViolationMessage::Note(
+                "Adding a column with a domain type that has NOT NULL or CHECK constraints rewrites the table while holding an ACCESS EXCLUSIVE lock.".into()
+            ),
+            ViolationMessage::Help(
+                "Add the column with the domain's base type, then add the constraints as NOT VALID and validate them separately.".into()
+            ),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-altertable.html".into()
+            ),
+        ],
+        explanation: Some(r#"Adding a column is normally a quick catalog change, but when the column's type is a domain with `NOT NULL` or `CHECK` constraints, Postgres rewrites the whole table to check them, holding an `ACCESS EXCLUSIVE` lock that blocks reads and writes.
+
+Squawk can't see the domain's definition without the database, so any type that isn't built-in is noted, unless it's created in the same file.
+
+Add the column with the domain's base type instead, then add the constraints as `NOT VALID` and validate them separately."#),
+    },
+    SquawkRule {
+        name: RuleViolationKind::AddingEnumValueInTransaction,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
+        func: adding_enum_value_in_transaction,
+        messages: vec![
+            ViolationMessage::Note(
+                "Before Postgres 12, ALTER TYPE ... ADD VALUE can't run inside a transaction, and in later versions the new value can't be used until the transaction commits.".into()
+            ),
+            ViolationMessage::Help(
+                "Run the statement outside of a transaction, and use the new value in a later migration.".into()
+            ),
+        ],
+        explanation: None,
+    },
     // see ChangingColumnType
     SquawkRule {
         name: RuleViolationKind::AddingFieldWithDefault,
+        severity: Severity::Warning,
+        lock_level: Some(LockLevel::AccessExclusive),
+        opt_in: false,
         func: adding_field_with_default,
         messages: vec![
             ViolationMessage::Note(
@@ -48,33 +154,93 @@ lazy_static! {
             ViolationMessage::Help(
                 "Add the field as nullable, then set a default, backfill, and remove nullabilty.".into(),
             ),
-
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-altertable.html".into()
+            ),
         ],
+        explanation: Some(r#"Before Postgres 11, adding a column with a default rewrites the whole table to fill in the value, holding an `ACCESS EXCLUSIVE` lock that blocks reads and writes.
+
+On Postgres 11+ a non-volatile default is stored in the catalog instead, so only volatile defaults like `gen_random_uuid()` rewrite the table.
+
+To add the column without a rewrite:
+
+1. Add the column without a default.
+2. Set the default with `ALTER TABLE ... ALTER COLUMN ... SET DEFAULT`, which only applies to new rows.
+3. Backfill existing rows in batches."#),
     },
     SquawkRule {
         name: RuleViolationKind::AddingForeignKeyConstraint,
+        severity: Severity::Warning,
+        lock_level: Some(LockLevel::ShareRowExclusive),
+        opt_in: false,
         func: adding_foreign_key_constraint,
         messages: vec![
             ViolationMessage::Note(
                 "Requires a table scan of the table you're altering and a SHARE ROW EXCLUSIVE lock on both tables, which blocks writes to both tables while your table is scanned.".into()
             ),
             ViolationMessage::Help("Add NOT VALID to the constraint in one transaction and then VALIDATE the constraint in a separate transaction.".into()),
-        ]
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-altertable.html".into()
+            ),
+        ],
+        explanation: Some(r#"Adding a foreign key takes a `SHARE ROW EXCLUSIVE` lock on both tables and scans the table to check every existing row, blocking writes until the scan finishes.
+
+Add the constraint as `NOT VALID` so only new rows are checked, then validate it separately:
+
+```sql
+ALTER TABLE "email" ADD CONSTRAINT "fk_user" FOREIGN KEY ("user_id") REFERENCES "user" ("id") NOT VALID;
+ALTER TABLE "email" VALIDATE CONSTRAINT "fk_user";
+```
+
+`VALIDATE CONSTRAINT` only takes a `SHARE UPDATE EXCLUSIVE` lock, so reads and writes continue during the scan."#),
+    },
+    SquawkRule {
+        name: RuleViolationKind::AddingIdentityColumn,
+        severity: Severity::Warning,
+        lock_level: Some(LockLevel::AccessExclusive),
+        opt_in: false,
+        func: adding_identity_column,
+        messages: vec![
+            ViolationMessage::Note(
+                "Adding an identity column fills in a value for every existing row, rewriting the table while holding an ACCESS EXCLUSIVE lock.".into()
+            ),
+            ViolationMessage::Help(
+                "Add a nullable column, backfill it in batches, set it NOT NULL, then make it an identity column with ALTER COLUMN ... ADD GENERATED BY DEFAULT AS IDENTITY.".into()
+            ),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-altertable.html".into()
+            ),
+        ],
+        explanation: Some(r#"Adding an identity column, e.g. `GENERATED ALWAYS AS IDENTITY`, fills in a sequence value for every existing row, rewriting the table while holding an `ACCESS EXCLUSIVE` lock that blocks reads and writes.
+
+Add a nullable column instead and backfill it in batches. Once it's `NOT NULL`, make it an identity column with `ALTER COLUMN ... ADD GENERATED BY DEFAULT AS IDENTITY`, which doesn't rewrite the table."#),
     },
     // usually paired with a DEFAULT
     SquawkRule {
         name: RuleViolationKind::AddingNotNullableField,
+        severity: Severity::Warning,
+        lock_level: Some(LockLevel::AccessExclusive),
+        opt_in: false,
         func: adding_not_nullable_field,
         messages: vec![
             // https://www.postgresql.org/docs/10/sql-altertable.html
             ViolationMessage::Note(
                 "Adding a NOT NULL field requires exclusive locks and table rewrites.".into(),
             ),
-            ViolationMessage::Help("Make the field nullable.".into())
+            ViolationMessage::Help("Make the field nullable.".into()),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-altertable.html".into()
+            ),
         ],
+        explanation: Some(r#"Setting a column `NOT NULL` scans the whole table to check for nulls while holding an `ACCESS EXCLUSIVE` lock.
+
+Add a `CHECK ("column" IS NOT NULL) NOT VALID` constraint and `VALIDATE` it separately instead. On Postgres 12+, a validated check constraint lets `SET NOT NULL` skip the scan."#),
     },
     SquawkRule {
         name: RuleViolationKind::AddingRequiredField,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
         func: adding_required_field,
         messages: vec![
             ViolationMessage::Note(
@@ -84,9 +250,39 @@ lazy_static! {
                 "Make the field nullable or add a non-VOLATILE DEFAULT (Postgres 11+).".into()
             ),
         ],
+        explanation: None,
+    },
+    // > Adding a column with a volatile DEFAULT or changing the type of an
+    // > existing column will require the entire table and its indexes to be
+    // > rewritten.
+    // serial columns are backed by a `nextval(...)` DEFAULT, which is volatile.
+    // https://www.postgresql.org/docs/current/sql-altertable.html#SQL-ALTERTABLE-NOTES
+    SquawkRule {
+        name: RuleViolationKind::AddingSerialColumn,
+        severity: Severity::Warning,
+        lock_level: Some(LockLevel::AccessExclusive),
+        opt_in: false,
+        func: adding_serial_column,
+        messages: vec![
+            ViolationMessage::Note(
+                "Adding a serial column requires a table rewrite with an ACCESS EXCLUSIVE lock, blocking reads and writes.".into()
+            ),
+            ViolationMessage::Help(
+                "Add the column as a nullable integer, create a sequence separately, then backfill the column.".into()
+            ),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-altertable.html".into()
+            ),
+        ],
+        explanation: Some(r#"Adding a `serial` column fills in a value for every existing row, rewriting the table while holding an `ACCESS EXCLUSIVE` lock.
+
+Add a nullable column, attach a sequence with `SET DEFAULT nextval(...)`, and backfill the existing rows in batches."#),
     },
     SquawkRule {
         name: RuleViolationKind::AddingSerialPrimaryKeyField,
+        severity: Severity::Warning,
+        lock_level: Some(LockLevel::AccessExclusive),
+        opt_in: false,
         func: adding_primary_key_constraint,
         messages: vec![
             ViolationMessage::Note(
@@ -95,65 +291,306 @@ lazy_static! {
             ViolationMessage::Help(
                 "Add the PRIMARY KEY constraint USING an index.".into(),
             ),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-altertable.html".into()
+            ),
+        ],
+        explanation: Some(r#"Adding a primary key builds a unique index while holding an `ACCESS EXCLUSIVE` lock, blocking reads and writes until the index is built.
+
+Build the index concurrently, then add the constraint using it:
+
+```sql
+CREATE UNIQUE INDEX CONCURRENTLY "account_pk_idx" ON "account" ("id");
+ALTER TABLE "account" ADD CONSTRAINT "account_pk" PRIMARY KEY USING INDEX "account_pk_idx";
+```"#),
+    },
+    SquawkRule {
+        name: RuleViolationKind::AddingStoredGeneratedColumn,
+        severity: Severity::Warning,
+        lock_level: Some(LockLevel::AccessExclusive),
+        opt_in: false,
+        func: adding_stored_generated_column,
+        messages: vec![
+            ViolationMessage::Note(
+                "Adding a stored generated column computes the value for every existing row, rewriting the table while holding an ACCESS EXCLUSIVE lock.".into()
+            ),
+            ViolationMessage::Help(
+                "Add a nullable column, backfill it in batches, and keep it up to date with a trigger instead.".into()
+            ),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-altertable.html".into()
+            ),
+        ],
+        explanation: Some(r#"Adding a stored generated column computes the value for every existing row, rewriting the table while holding an `ACCESS EXCLUSIVE` lock.
 
+Add a nullable column, backfill it in batches, and keep it up to date with a trigger instead."#),
+    },
+    SquawkRule {
+        name: RuleViolationKind::BanAlterOwner,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: true,
+        func: ban_alter_owner,
+        messages: vec![
+            ViolationMessage::Note(
+                "Changing ownership in schema migrations ties the schema to roles that can differ between environments.".into()
+            ),
+            ViolationMessage::Help(
+                "Manage ownership separately from schema migrations, e.g. with the tooling that manages roles and grants.".into()
+            ),
         ],
+        explanation: None,
     },
     SquawkRule {
         name: RuleViolationKind::BanCharField,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
         func: ban_char_type,
         messages: vec![
             ViolationMessage::Help(
                 "Use text or varchar instead.".into()
             ),
-        ]
+        ],
+        explanation: None,
+    },
+    SquawkRule {
+        name: RuleViolationKind::BanCluster,
+        severity: Severity::Warning,
+        lock_level: Some(LockLevel::AccessExclusive),
+        opt_in: false,
+        func: ban_cluster,
+        messages: vec![
+            ViolationMessage::Note(
+                "CLUSTER rewrites the table while holding an ACCESS EXCLUSIVE lock, blocking reads and writes.".into()
+            ),
+            ViolationMessage::Help(
+                "Use pg_repack to reorder the table without blocking reads and writes.".into()
+            ),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-cluster.html".into()
+            ),
+        ],
+        explanation: Some(r#"`CLUSTER` rewrites the table in index order while holding an `ACCESS EXCLUSIVE` lock, blocking reads and writes for the whole rewrite.
+
+Use `pg_repack` to reorder the table online, or run `CLUSTER` out-of-band during a maintenance window."#),
     },
     SquawkRule {
         name: RuleViolationKind::BanConcurrentIndexCreationInTransaction,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
         func: ban_concurrent_index_creation_in_transaction,
         messages: vec![
             ViolationMessage::Note(
-                "Concurrent index creation is not allowed inside a transaction.".into()
+                "Concurrent index operations are not allowed inside a transaction.".into()
+            ),
+            ViolationMessage::Help(
+                "Run the statement outside any transactions.".into()
+            ),
+        ],
+        explanation: None,
+    },
+    SquawkRule {
+        name: RuleViolationKind::BanCreateTableAs,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
+        func: ban_create_table_as,
+        messages: vec![
+            ViolationMessage::Note(
+                "CREATE TABLE AS and SELECT INTO copy the data while scanning the source tables, and create a table without indexes or constraints.".into()
+            ),
+            ViolationMessage::Help(
+                "Define the table with CREATE TABLE, then backfill it in batches.".into()
+            ),
+        ],
+        explanation: None,
+    },
+    SquawkRule {
+        name: RuleViolationKind::BanDeprecatedType,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
+        func: ban_deprecated_type,
+        messages: vec![
+            ViolationMessage::Note(
+                "Deprecated types like abstime, reltime, tinterval, and oid shouldn't be used for columns.".into()
+            ),
+            ViolationMessage::Help(
+                "Use a modern replacement, like timestamptz, interval, tstzrange, or bigint.".into()
+            ),
+        ],
+        explanation: None,
+    },
+    SquawkRule {
+        name: RuleViolationKind::BanDisablingStatementTimeout,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
+        func: ban_disabling_statement_timeout,
+        messages: vec![
+            ViolationMessage::Note(
+                "Without a statement_timeout, a slow statement, or one stuck waiting for a lock, can run and block other queries indefinitely.".into()
             ),
             ViolationMessage::Help(
-                "Build the index outside any transactions.".into()
+                "Set a non-zero statement_timeout, e.g. SET statement_timeout = '5min', for statements that need longer to run.".into()
             ),
         ],
+        explanation: None,
     },
     SquawkRule {
         name: RuleViolationKind::BanDropColumn,
+        severity: Severity::Error,
+        lock_level: None,
+        opt_in: false,
         func: ban_drop_column,
         messages: vec![
             ViolationMessage::Note(
                 "Dropping a column may break existing clients.".into()
             ),
+            ViolationMessage::Help(
+                "Deploy a code change to stop reading and writing the column before dropping it.".into()
+            ),
         ],
+        explanation: None,
     },
     SquawkRule {
         name: RuleViolationKind::BanDropDatabase,
+        severity: Severity::Error,
+        lock_level: None,
+        opt_in: false,
         func: ban_drop_database,
         messages: vec![
             ViolationMessage::Note(
                 "Dropping a database may break existing clients.".into()
             )
         ],
+        explanation: None,
     },
     SquawkRule {
         name: RuleViolationKind::BanDropNotNull,
+        severity: Severity::Info,
+        lock_level: None,
+        opt_in: false,
         func: ban_drop_not_null,
         messages: vec![
             ViolationMessage::Note(
                 "Dropping a NOT NULL constraint may break existing clients.".into()
             ),
         ],
+        explanation: None,
     },
     SquawkRule {
         name: RuleViolationKind::BanDropTable,
+        severity: Severity::Error,
+        lock_level: None,
+        opt_in: false,
         func: ban_drop_table,
         messages: vec![
             ViolationMessage::Note(
                 "Dropping a table may break existing clients.".into()
-            )
+            ),
+            ViolationMessage::Help(
+                "Remove all references to the table from your application code and deploy that change before dropping the table.".into()
+            ),
+        ],
+        explanation: None,
+    },
+    SquawkRule {
+        name: RuleViolationKind::BanGrantInMigration,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: true,
+        func: ban_grant_in_migration,
+        messages: vec![
+            ViolationMessage::Note(
+                "Permission changes in schema migrations can drift between environments.".into()
+            ),
+            ViolationMessage::Help(
+                "Manage GRANT and REVOKE separately from schema migrations.".into()
+            ),
+        ],
+        explanation: None,
+    },
+    SquawkRule {
+        name: RuleViolationKind::BanMoneyType,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
+        func: ban_money_type,
+        messages: vec![
+            ViolationMessage::Note(
+                "The money type's output and input depend on the lc_monetary setting, and it's limited to the fractional precision of the locale's currency.".into()
+            ),
+            ViolationMessage::Help(
+                "Use numeric instead, with a separate column for the currency if needed.".into()
+            ),
+        ],
+        explanation: None,
+    },
+    SquawkRule {
+        name: RuleViolationKind::BanSetUnlogged,
+        severity: Severity::Warning,
+        lock_level: Some(LockLevel::AccessExclusive),
+        opt_in: false,
+        func: ban_set_unlogged,
+        messages: vec![
+            ViolationMessage::Note(
+                "Changing a table between LOGGED and UNLOGGED rewrites the entire table while holding an ACCESS EXCLUSIVE lock.".into()
+            ),
+            ViolationMessage::Help(
+                "Create a new table with the desired persistence, copy the rows over, and swap the tables.".into()
+            ),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-altertable.html".into()
+            ),
+        ],
+        explanation: Some(r#"`SET LOGGED` and `SET UNLOGGED` rewrite the whole table while holding an `ACCESS EXCLUSIVE` lock.
+
+Create a new table with the persistence you want, copy the rows over, and swap the tables by renaming them."#),
+    },
+    SquawkRule {
+        name: RuleViolationKind::BanTruncate,
+        severity: Severity::Error,
+        lock_level: Some(LockLevel::AccessExclusive),
+        opt_in: false,
+        func: ban_truncate,
+        messages: vec![
+            ViolationMessage::Note(
+                "TRUNCATE deletes every row in the table, which can't be undone once committed, and holds an ACCESS EXCLUSIVE lock that blocks reads and writes.".into()
+            ),
+            ViolationMessage::Help(
+                "Remove the TRUNCATE, or delete the rows outside of a migration if you really need to.".into()
+            ),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-truncate.html".into()
+            ),
+        ],
+        explanation: Some(r#"`TRUNCATE` takes an `ACCESS EXCLUSIVE` lock and removes every row, which can't be undone once committed.
+
+Delete rows in batches with `DELETE`, or drop the table in a separate migration once nothing uses it."#),
+    },
+    SquawkRule {
+        name: RuleViolationKind::BanVacuumFull,
+        severity: Severity::Error,
+        lock_level: Some(LockLevel::AccessExclusive),
+        opt_in: false,
+        func: ban_vacuum_full,
+        messages: vec![
+            ViolationMessage::Note(
+                "VACUUM FULL rewrites the entire table while holding an ACCESS EXCLUSIVE lock, blocking reads and writes.".into()
+            ),
+            ViolationMessage::Help(
+                "Use pg_repack to reclaim space without blocking, or run VACUUM FULL out-of-band during a maintenance window.".into()
+            ),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-vacuum.html".into()
+            ),
         ],
+        explanation: Some(r#"`VACUUM FULL` rewrites the table into a new file while holding an `ACCESS EXCLUSIVE` lock, blocking reads and writes until it finishes. `VACUUM` also can't run in a transaction.
+
+Use `pg_repack` to reclaim space online, or run `VACUUM FULL` out-of-band during a maintenance window."#),
     },
     // > Adding a column with a volatile DEFAULT or changing the type of an
     // > existing column will require the entire table and its indexes to be
@@ -168,13 +605,56 @@ lazy_static! {
     // https://www.postgresql.org/docs/current/sql-altertable.html
     SquawkRule {
         name: RuleViolationKind::ChangingColumnType,
+        severity: Severity::Warning,
+        lock_level: Some(LockLevel::AccessExclusive),
+        opt_in: false,
         func: changing_column_type,
         messages: vec![
             ViolationMessage::Note("Requires an ACCESS EXCLUSIVE lock on the table which blocks reads.".into()),
             ViolationMessage::Note("Changing the type may break existing clients.".into()),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-altertable.html".into()
+            ),
+        ],
+        explanation: Some(r#"Changing a column's type usually rewrites the table and its indexes while holding an `ACCESS EXCLUSIVE` lock. Clients that depend on the old type may also break.
+
+Add a new column with the new type, backfill it in batches, move clients over, then drop the old column."#),
+    },
+
+    SquawkRule {
+        name: RuleViolationKind::ChangingObjectSchema,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
+        func: changing_object_schema,
+        messages: vec![
+            ViolationMessage::Note(
+                "Moving an object to another schema may break existing clients.".into()
+            ),
         ],
+        explanation: None,
     },
+    SquawkRule {
+        name: RuleViolationKind::ChangingStorageParameter,
+        severity: Severity::Info,
+        lock_level: Some(LockLevel::ShareUpdateExclusive),
+        opt_in: false,
+        func: changing_storage_parameter,
+        messages: vec![
+            ViolationMessage::Note(
+                "Changing this storage parameter only applies to newly written rows, the existing rows keep the old setting until the table is rewritten, e.g. by VACUUM FULL or CLUSTER, which hold an ACCESS EXCLUSIVE lock.".into()
+            ),
+            ViolationMessage::Help(
+                "Rewrite the table without blocking reads and writes, e.g. with pg_repack, or leave the existing rows as they are.".into()
+            ),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-createtable.html#SQL-CREATETABLE-STORAGE-PARAMETERS".into()
+            ),
+        ],
+        explanation: Some(r#"`ALTER TABLE ... SET (fillfactor = 70)` and `SET (toast_tuple_target = ...)` only take a `SHARE UPDATE EXCLUSIVE` lock, but they only apply to newly written rows. The existing rows keep the old setting until the table is rewritten, and `VACUUM FULL` and `CLUSTER` hold an `ACCESS EXCLUSIVE` lock while they rewrite it.
 
+Rewrite the table without blocking reads and writes, e.g. with `pg_repack`, or leave the existing rows as they are. Parameters that only change vacuum or planner behaviour, like `autovacuum_enabled`, aren't reported."#),
+    },
     // > Scanning a large table to verify a new foreign key or check
     // > constraint can take a long time, and other updates to the table are
     // > locked out until the ALTER TABLE ADD CONSTRAINT command is committed.
@@ -198,11 +678,49 @@ lazy_static! {
     // https://www.postgresql.org/docs/current/sql-altertable.html#SQL-ALTERTABLE-NOTES
     SquawkRule {
         name: RuleViolationKind::ConstraintMissingNotValid,
+        severity: Severity::Warning,
+        lock_level: Some(LockLevel::AccessExclusive),
+        opt_in: false,
         func: constraint_missing_not_valid,
         messages: vec![
             ViolationMessage::Note("Requires a table scan to verify constraint and an ACCESS EXCLUSIVE lock which blocks reads.".into()),
             ViolationMessage::Help("Add NOT VALID to the constraint in one transaction and then VALIDATE the constraint in a separate transaction.".into()),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-altertable.html".into()
+            ),
+        ],
+        explanation: Some(r#"Adding a `CHECK` or foreign key constraint scans the table to check every existing row while holding a lock that blocks writes.
+
+Add the constraint as `NOT VALID` so only new rows are checked, then run `ALTER TABLE ... VALIDATE CONSTRAINT` in a separate transaction, which doesn't block reads or writes."#),
+    },
+    SquawkRule {
+        name: RuleViolationKind::DisallowedExclusionConstraint,
+        severity: Severity::Warning,
+        lock_level: Some(LockLevel::AccessExclusive),
+        opt_in: false,
+        func: disallow_exclusion_constraint,
+        messages: vec![
+            ViolationMessage::Note(
+                "Adding an EXCLUDE constraint builds an index while holding an ACCESS EXCLUSIVE lock, which blocks reads and writes.".into()
+            ),
+            ViolationMessage::Help(
+                "Exclusion constraints can't be added USING an index built CONCURRENTLY, so add them when the table is created, or on a small table with a short lock_timeout.".into()
+            ),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-altertable.html".into()
+            ),
         ],
+        explanation: Some(r#"Adding an `EXCLUDE` constraint builds its index while holding an `ACCESS EXCLUSIVE` lock, blocking reads and writes until every row is indexed and checked.
+
+Unlike `UNIQUE` constraints, exclusion constraints can't be added `USING INDEX`, so the index can't be built `CONCURRENTLY` first. Add the constraint when the table is created:
+
+```sql
+CREATE TABLE "reservation" (
+    "room_id" bigint NOT NULL,
+    "during" tstzrange NOT NULL,
+    CONSTRAINT "reservation_no_overlap" EXCLUDE USING gist ("room_id" WITH =, "during" WITH &&)
+);
+```"#),
     },
     // > Although most forms of ADD table_constraint require an ACCESS
     // > EXCLUSIVE lock, ADD FOREIGN KEY requires only a SHARE ROW EXCLUSIVE
@@ -210,6 +728,9 @@ lazy_static! {
     // https://www.postgresql.org/docs/current/sql-altertable.html
     SquawkRule {
         name: RuleViolationKind::DisallowedUniqueConstraint,
+        severity: Severity::Warning,
+        lock_level: Some(LockLevel::AccessExclusive),
+        opt_in: false,
         func: disallow_unique_constraint,
         messages: vec![
             ViolationMessage::Note(
@@ -218,11 +739,115 @@ lazy_static! {
             ViolationMessage::Help(
                 "Create an index CONCURRENTLY and create the constraint using the index.".into(),
             ),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-altertable.html".into()
+            ),
+        ],
+        explanation: Some(r#"Adding a `UNIQUE` constraint builds a unique index while holding an `ACCESS EXCLUSIVE` lock.
+
+Build the index concurrently, then add the constraint using it:
 
+```sql
+CREATE UNIQUE INDEX CONCURRENTLY "dist_id_temp_idx" ON "distributors" ("dist_id");
+ALTER TABLE "distributors" ADD CONSTRAINT "dist_id_unique" UNIQUE USING INDEX "dist_id_temp_idx";
+```"#),
+    },
+    SquawkRule {
+        name: RuleViolationKind::DuplicateIndex,
+        severity: Severity::Info,
+        lock_level: None,
+        opt_in: false,
+        func: duplicate_index,
+        messages: vec![
+            ViolationMessage::Note(
+                "The index has the same definition as an index created earlier in the file.".into()
+            ),
+            ViolationMessage::Help(
+                "Remove the duplicate CREATE INDEX.".into()
+            ),
+        ],
+        explanation: None,
+    },
+    SquawkRule {
+        name: RuleViolationKind::MixingDdlAndDml,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
+        func: mixing_ddl_and_dml,
+        messages: vec![
+            ViolationMessage::Note(
+                "Modifying data in the same migration as schema changes holds the schema change locks until the data changes finish.".into()
+            ),
+            ViolationMessage::Help(
+                "Move the data changes to a separate migration and backfill in batches.".into()
+            ),
+        ],
+        explanation: None,
+    },
+    SquawkRule {
+        name: RuleViolationKind::NamingConvention,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: true,
+        func: naming_convention,
+        messages: vec![
+            ViolationMessage::Note(
+                "Index and constraint names should match the configured naming convention.".into()
+            ),
+            ViolationMessage::Help(
+                "Rename it to match the configured naming convention.".into()
+            ),
+        ],
+        explanation: None,
+    },
+    SquawkRule {
+        name: RuleViolationKind::NonImmutableCheckConstraint,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
+        func: non_immutable_check_constraint,
+        messages: vec![
+            ViolationMessage::Note(
+                "CHECK constraints calling functions that aren't IMMUTABLE are only checked when rows are inserted or updated, so existing rows can stop satisfying them.".into()
+            ),
+            ViolationMessage::Help(
+                "Compare against a fixed value, or use a trigger to check values against the current time or settings.".into()
+            ),
+        ],
+        explanation: None,
+    },
+    SquawkRule {
+        name: RuleViolationKind::PartitionLockHazard,
+        severity: Severity::Warning,
+        lock_level: Some(LockLevel::AccessExclusive),
+        opt_in: false,
+        func: partition_lock_hazard,
+        messages: vec![
+            ViolationMessage::Note(
+                "Attaching or detaching a partition can block reads and writes to the partitioned table.".into()
+            ),
+            ViolationMessage::Help(
+                "Use DETACH PARTITION CONCURRENTLY, and add a CHECK constraint matching the partition bound before ATTACH PARTITION.".into()
+            ),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/ddl-partitioning.html".into()
+            ),
         ],
+        explanation: Some(r#"`ALTER TABLE ... DETACH PARTITION` takes an `ACCESS EXCLUSIVE` lock on the partitioned table, blocking reads and writes to every partition. On Postgres 14+, `DETACH PARTITION CONCURRENTLY` only takes a `SHARE UPDATE EXCLUSIVE` lock.
+
+`ALTER TABLE ... ATTACH PARTITION` scans the partition to check every row matches the partition bound while holding an `ACCESS EXCLUSIVE` lock on the partition. Add a `CHECK` constraint matching the bound first, with `NOT VALID` and `VALIDATE CONSTRAINT`, so the attach can skip the scan:
+
+```sql
+ALTER TABLE "measurement_y2023" ADD CONSTRAINT "y2023_bound" CHECK ("logdate" >= '2023-01-01' AND "logdate" < '2024-01-01') NOT VALID;
+ALTER TABLE "measurement_y2023" VALIDATE CONSTRAINT "y2023_bound";
+ALTER TABLE "measurement" ATTACH PARTITION "measurement_y2023" FOR VALUES FROM ('2023-01-01') TO ('2024-01-01');
+```"#),
     },
     SquawkRule {
         name: RuleViolationKind::PreferBigInt,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
         func: prefer_big_int,
         messages: vec![
             ViolationMessage::Note(
@@ -232,9 +857,13 @@ lazy_static! {
                 "Use 64bit integer values instead to prevent hitting this limit.".into()
             ),
         ],
+        explanation: None,
     },
     SquawkRule {
         name: RuleViolationKind::PreferBigintOverInt,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
         func: prefer_bigint_over_int,
         messages: vec![
             ViolationMessage::Note(
@@ -244,9 +873,13 @@ lazy_static! {
                 "Use 64bit integer values instead to prevent hitting this limit.".into()
             ),
         ],
+        explanation: None,
     },
     SquawkRule {
         name: RuleViolationKind::PreferBigintOverSmallint,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
         func: prefer_bigint_over_smallint,
         messages: vec![
             ViolationMessage::Note(
@@ -256,9 +889,13 @@ lazy_static! {
                 "Use 64bit integer values instead to prevent hitting this limit.".into()
             ),
         ],
+        explanation: None,
     },
     SquawkRule {
         name: RuleViolationKind::PreferIdentity,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
         func: prefer_identity,
         messages: vec![
             ViolationMessage::Note(
@@ -268,19 +905,59 @@ lazy_static! {
                 "Use identity columns instead for more features and better usability.".into()
             ),
         ],
+        explanation: None,
+    },
+    SquawkRule {
+        name: RuleViolationKind::PreferJsonb,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
+        func: prefer_jsonb,
+        messages: vec![
+            ViolationMessage::Note(
+                "The json type stores the raw text and can't be indexed efficiently.".into()
+            ),
+            ViolationMessage::Help(
+                "Use jsonb instead of json for your column type.".into()
+            ),
+        ],
+        explanation: None,
+    },
+    SquawkRule {
+        name: RuleViolationKind::PreferPrimaryKey,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
+        func: prefer_primary_key,
+        messages: vec![
+            ViolationMessage::Note(
+                "Tables without a primary key can't replicate updates and deletes with logical replication, and rows can't be reliably identified.".into()
+            ),
+            ViolationMessage::Help(
+                "Add a primary key, e.g. \"id\" bigint GENERATED ALWAYS AS IDENTITY PRIMARY KEY.".into()
+            ),
+        ],
+        explanation: None,
     },
     SquawkRule {
         name: RuleViolationKind::PreferRobustStmts,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
         func: prefer_robust_stmts,
         messages: vec![
             ViolationMessage::Help(
                 "Consider wrapping in a transaction or adding a IF NOT EXISTS clause if the statement supports it.".into()
             ),
-        ]
+        ],
+        explanation: None,
     },
     // see ConstraintMissingNotValid for more docs
     SquawkRule {
         name: RuleViolationKind::PreferTextField,
+        severity: Severity::Warning,
+        lock_level: Some(LockLevel::AccessExclusive),
+        opt_in: false,
         func: prefer_text_field,
         messages: vec![
             ViolationMessage::Note(
@@ -289,10 +966,19 @@ lazy_static! {
             ViolationMessage::Help(
                 "Use a text field with a check constraint.".into()
             ),
-        ]
-    },
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-altertable.html".into()
+            ),
+        ],
+        explanation: Some(r#"Changing the length of a `varchar` column can rewrite the table while holding an `ACCESS EXCLUSIVE` lock.
+
+Use `text` with a `CHECK (char_length("column") <= n)` constraint instead. The constraint can be changed with `NOT VALID` and `VALIDATE CONSTRAINT` without blocking reads or writes."#),
+    },
     SquawkRule {
         name: RuleViolationKind::PreferTimestampTz,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
         func: prefer_timestamptz,
         messages: vec![
             ViolationMessage::Note(
@@ -301,7 +987,8 @@ lazy_static! {
             ViolationMessage::Help(
                 "Use timestamptz instead of timestamp for your column type.".into()
             ),
-        ]
+        ],
+        explanation: None,
     },
     // > The RENAME forms change the name of a table (or an index, sequence,
     // > view, materialized view, or foreign table), the name of an individual
@@ -310,51 +997,238 @@ lazy_static! {
     // https://www.postgresql.org/docs/10/sql-altertable.html
     SquawkRule {
         name: RuleViolationKind::RenamingColumn,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
         func: renaming_column,
         messages: vec![
             ViolationMessage::Note(
                 "Renaming a column may break existing clients.".into()
             ),
         ],
+        explanation: None,
+    },
+    // see RenamingColumn rule
+    SquawkRule {
+        name: RuleViolationKind::RenamingConstraint,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
+        func: renaming_constraint,
+        messages: vec![
+            ViolationMessage::Note(
+                "Renaming a constraint may break existing clients.".into()
+            ),
+        ],
+        explanation: None,
     },
     // see RenamingColumn rule
     SquawkRule {
         name: RuleViolationKind::RenamingTable,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
         func: renaming_table,
         messages: vec![
             ViolationMessage::Note(
                 "Renaming a table may break existing clients.".into()
             ),
         ],
+        explanation: None,
     },
     // https://www.postgresql.org/docs/10/sql-createindex.html#SQL-CREATEINDEX-CONCURRENTLY
     SquawkRule {
         name: RuleViolationKind::RequireConcurrentIndexCreation,
+        severity: Severity::Warning,
+        lock_level: Some(LockLevel::Share),
+        opt_in: false,
         func: require_concurrent_index_creation,
         messages: vec![
             ViolationMessage::Note(
                 "Creating an index blocks writes.".into()
             ),
             ViolationMessage::Help(
-                "Create the index CONCURRENTLY.".into()
+                "Create the index CONCURRENTLY, outside of a transaction.".into()
+            ),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-createindex.html".into()
             ),
         ],
+        explanation: Some(r#"`CREATE INDEX` takes a `SHARE` lock on the table, blocking writes until the index is built.
+
+Use `CREATE INDEX CONCURRENTLY`, which builds the index without blocking writes. It's slower, can't run in a transaction, and leaves an invalid index behind if it fails, which should be dropped before retrying."#),
     },
     // https://www.postgresql.org/docs/10/sql-dropindex.html
     SquawkRule {
         name: RuleViolationKind::RequireConcurrentIndexDeletion,
+        severity: Severity::Warning,
+        lock_level: Some(LockLevel::AccessExclusive),
+        opt_in: false,
         func: require_concurrent_index_deletion,
         messages: vec![
             ViolationMessage::Note(
                 "Deleting an index blocks selects, inserts, updates, and deletes on the index's table.".into()
             ),
             ViolationMessage::Help(
-                "Delete the index CONCURRENTLY.".into()
+                "Delete the index CONCURRENTLY, outside of a transaction.".into()
+            ),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-dropindex.html".into()
+            ),
+        ],
+        explanation: Some(r#"`DROP INDEX` takes an `ACCESS EXCLUSIVE` lock on the table, blocking reads and writes until the index is dropped.
+
+Use `DROP INDEX CONCURRENTLY`, which waits for queries using the index to finish instead of blocking new ones. It can't run in a transaction."#),
+    },
+    SquawkRule {
+        name: RuleViolationKind::RequireConcurrentReindex,
+        severity: Severity::Warning,
+        lock_level: Some(LockLevel::Share),
+        opt_in: false,
+        func: require_concurrent_reindex,
+        messages: vec![
+            ViolationMessage::Note(
+                "REINDEX without CONCURRENTLY blocks writes to the table, and reads that use the index, while the index is rebuilt.".into()
+            ),
+            ViolationMessage::Help(
+                "Use REINDEX CONCURRENTLY, which requires Postgres 12+.".into()
+            ),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-reindex.html".into()
+            ),
+        ],
+        explanation: Some(r#"`REINDEX` takes a `SHARE` lock on the table while the index is rebuilt, blocking writes, and blocks reads that use the index.
+
+Use `REINDEX CONCURRENTLY` on Postgres 12+. On older versions, create a replacement index concurrently, drop the old index concurrently, and rename the new one."#),
+    },
+    SquawkRule {
+        name: RuleViolationKind::RequireIfNotExists,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: true,
+        func: require_if_not_exists,
+        messages: vec![
+            ViolationMessage::Note(
+                "If the migration fails part way through outside of a transaction, rerunning it will fail on the objects that were already created.".into()
+            ),
+            ViolationMessage::Help(
+                "Add IF NOT EXISTS to the statement so the migration can be rerun.".into()
             ),
         ],
+        explanation: None,
+    },
+    // https://www.postgresql.org/docs/current/runtime-config-client.html#GUC-LOCK-TIMEOUT
+    SquawkRule {
+        name: RuleViolationKind::RequireLockTimeout,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: true,
+        func: require_lock_timeout,
+        messages: vec![
+            ViolationMessage::Note(
+                "Without a lock_timeout, a statement waiting for a lock blocks all other queries on the table.".into()
+            ),
+            ViolationMessage::Help(
+                "Run SET lock_timeout before the statement so it fails instead of waiting indefinitely.".into()
+            ),
+        ],
+        explanation: None,
+    },
+    SquawkRule {
+        name: RuleViolationKind::RequireReplicaIdentity,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
+        func: require_replica_identity,
+        messages: vec![
+            ViolationMessage::Note(
+                "REPLICA IDENTITY NOTHING makes updates and deletes fail on tables published for logical replication.".into()
+            ),
+            ViolationMessage::Help(
+                "Use REPLICA IDENTITY DEFAULT with a primary key, or USING INDEX with a unique index on NOT NULL columns.".into()
+            ),
+        ],
+        explanation: None,
+    },
+    SquawkRule {
+        name: RuleViolationKind::RequireValidateConstraint,
+        severity: Severity::Info,
+        lock_level: None,
+        opt_in: true,
+        func: require_validate_constraint,
+        messages: vec![
+            ViolationMessage::Note(
+                "A NOT VALID constraint isn't checked against existing rows until it's validated.".into()
+            ),
+            ViolationMessage::Help(
+                "Run ALTER TABLE ... VALIDATE CONSTRAINT in a later migration.".into()
+            ),
+        ],
+        explanation: None,
+    },
+    SquawkRule {
+        name: RuleViolationKind::SettingNotNullOnExistingColumn,
+        severity: Severity::Warning,
+        lock_level: Some(LockLevel::AccessExclusive),
+        opt_in: false,
+        func: setting_not_null_on_existing_column,
+        messages: vec![
+            ViolationMessage::Note(
+                "Setting a column NOT NULL scans the entire table while holding an ACCESS EXCLUSIVE lock, blocking reads and writes.".into()
+            ),
+            ViolationMessage::Help(
+                "Add a CHECK (column IS NOT NULL) NOT VALID constraint, VALIDATE it, then SET NOT NULL, which Postgres 12+ can do without scanning the table.".into()
+            ),
+            ViolationMessage::Url(
+                "https://www.postgresql.org/docs/current/sql-altertable.html".into()
+            ),
+        ],
+        explanation: Some(r#"`SET NOT NULL` scans the whole table to check for nulls while holding an `ACCESS EXCLUSIVE` lock.
+
+On Postgres 12+:
+
+1. Add a `CHECK ("column" IS NOT NULL) NOT VALID` constraint.
+2. `VALIDATE CONSTRAINT`, which scans the table without blocking reads or writes.
+3. `SET NOT NULL`, which uses the validated constraint to skip the scan.
+4. Drop the check constraint."#),
+    },
+    SquawkRule {
+        name: RuleViolationKind::SettingSearchPath,
+        severity: Severity::Info,
+        lock_level: None,
+        opt_in: false,
+        func: setting_search_path,
+        messages: vec![
+            ViolationMessage::Note(
+                "Changing search_path in a migration changes which schema later unqualified names resolve to.".into()
+            ),
+            ViolationMessage::Help(
+                "Qualify the names with their schema, or set search_path outside of the migration.".into()
+            ),
+        ],
+        explanation: None,
+    },
+    SquawkRule {
+        name: RuleViolationKind::TooManyLockingStatements,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
+        func: too_many_locking_statements,
+        messages: vec![
+            ViolationMessage::Note(
+                "The migration has many statements that take strong locks, which together can block reads and writes for a long time.".into()
+            ),
+            ViolationMessage::Help(
+                "Split the migration into smaller migrations so fewer locks are held at once.".into()
+            ),
+        ],
+        explanation: None,
     },
     SquawkRule {
         name: RuleViolationKind::TransactionNesting,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
         func: transaction_nesting,
         messages: vec![
             ViolationMessage::Note(
@@ -364,32 +1238,375 @@ lazy_static! {
                 "COMMIT the previous transaction before issuing a BEGIN or START TRANSACTION statement.".into()
             ),
         ],
+        explanation: None,
+    },
+    SquawkRule {
+        name: RuleViolationKind::UnboundedDataModification,
+        severity: Severity::Warning,
+        lock_level: None,
+        opt_in: false,
+        func: unbounded_data_modification,
+        messages: vec![
+            ViolationMessage::Note(
+                "Updating or deleting every row in one statement locks the rows until the transaction commits and leaves dead rows behind.".into()
+            ),
+            ViolationMessage::Help(
+                "Modify the rows in batches, e.g. with a WHERE clause on a range of ids, committing between batches.".into()
+            ),
+        ],
+        explanation: None,
     },
     // generator::new-rule-above
     ];
 
 }
 
-pub fn check_sql(
+/// Find the rule for `kind`, e.g. to show its messages without running a lint.
+#[must_use]
+pub fn rule_by_kind(kind: &RuleViolationKind) -> Option<&'static SquawkRule> {
+    RULES.iter().find(|r| r.name == *kind)
+}
+
+/// Names of every rule, in the same order as `RULES`.
+#[must_use]
+pub fn all_rule_names() -> Vec<String> {
+    RULES.iter().map(|r| r.name.to_string()).collect()
+}
+
+/// Run `rules` over the tree, skipping the rules `config` disables.
+fn run_rules<'a>(
+    tree: &[RawStmt],
+    rules: impl Iterator<Item = &'a SquawkRule>,
+    config: &CheckSqlConfig,
+) -> Vec<RuleViolation> {
+    let default_options = RuleOptions::new();
+
+    let mut errs = vec![];
+    for rule in rules.filter(|r| should_run(r, config)) {
+        let rule_options = config.rules.get(&rule.name).unwrap_or(&default_options);
+        errs.extend(
+            (rule.func)(
+                tree,
+                config.pg_version,
+                config.assume_in_transaction,
+                rule_options,
+            )
+            .into_iter()
+            .map(|mut violation| {
+                if let Some(severity) = config.severity_overrides.get(&violation.kind) {
+                    violation.severity = *severity;
+                }
+                violation
+            }),
+        );
+    }
+    errs
+}
+
+fn should_run(rule: &SquawkRule, config: &CheckSqlConfig) -> bool {
+    if config.only.is_some() {
+        return config.is_enabled(&rule.name);
+    }
+    // custom rules aren't in `RULES`, so `is_enabled` can't tell if they're
+    // opt-in.
+    config.is_enabled(&rule.name) && (!rule.opt_in || config.rules.contains_key(&rule.name))
+}
+
+fn parse_tree(
     sql: &str,
-    excluded_rules: &[RuleViolationKind],
-    pg_version: Option<Version>,
-    assume_in_transaction: bool,
+    config: &CheckSqlConfig,
+) -> Result<(Vec<RawStmt>, Vec<SkippedStmt>), CheckSqlError> {
+    let blanked = if config.ignore_psql_meta_commands {
+        psql::blank_meta_commands(sql)
+    } else {
+        None
+    };
+    let sql = blanked
+        .as_ref()
+        .map_or(sql, |(blanked, _)| blanked.as_str());
+    // e.g. an empty migration stub, which doesn't need to be parsed.
+    if sql.trim().is_empty() {
+        return Ok((vec![], vec![]));
+    }
+    let (mut tree, skipped) = if config.recover_from_parse_errors {
+        parse_sql_query_with_recovery(sql)
+    } else {
+        (parse_sql_query(sql)?, vec![])
+    };
+    if let Some((blanked, lines)) = &blanked {
+        psql::skip_meta_commands(&mut tree, blanked, lines);
+    }
+    Ok((tree, skipped))
+}
+
+fn invalid_statement_violation(stmt: SkippedStmt) -> RuleViolation {
+    RuleViolation {
+        kind: RuleViolationKind::InvalidStatement,
+        severity: Severity::Error,
+        lock_level: None,
+        span: stmt.span,
+        statement_kind: StatementKind::Other,
+        messages: vec![
+            ViolationMessage::Note(stmt.error.to_string()),
+            ViolationMessage::Help("Modify your Postgres statement to use valid syntax.".into()),
+        ],
+        snippet: None,
+    }
+}
+
+/// Run the enabled rules over a tree from `parse_sql_query`, for callers
+/// that already parsed the SQL.
+///
+/// Violations are in the order of `RULES`. `-- squawk-ignore` comments and
+/// the baseline need the SQL text, so they're only applied by `check_sql`.
+#[must_use]
+pub fn check_tree(tree: &[RawStmt], config: &CheckSqlConfig) -> Vec<RuleViolation> {
+    run_rules(tree, RULES.iter(), config)
+}
+
+/// Parse `sql` and run the enabled rules over it.
+///
+/// SQL that's empty or only contains whitespace or comments has no
+/// statements, so it returns no violations rather than an error.
+pub fn check_sql(sql: &str, config: &CheckSqlConfig) -> Result<Vec<RuleViolation>, CheckSqlError> {
+    check_sql_with_rules(sql, &[], config)
+}
+
+/// Like `check_sql`, but also run `rules`, e.g. for conventions specific to
+/// a project that don't belong in squawk.
+///
+/// Custom rules are named with `RuleViolationKind::Custom`. `RuleViolation::new`
+/// only knows the built-in rules, so violations of a custom rule get the
+/// rule's severity and lock level, and its messages when they have none.
+/// `CheckSqlConfig::severity_overrides` applies to custom rules too.
+pub fn check_sql_with_rules(
+    sql: &str,
+    rules: &[SquawkRule],
+    config: &CheckSqlConfig,
 ) -> Result<Vec<RuleViolation>, CheckSqlError> {
-    let tree = parse_sql_query(sql)?;
+    check_sql_in_file(sql, None, rules, config)
+}
 
-    let excluded_rules: HashSet<RuleViolationKind> = excluded_rules.iter().cloned().collect();
+/// `check_sql_with_rules` for the SQL of `path`, so baseline entries recorded
+/// for that file apply.
+fn check_sql_in_file(
+    sql: &str,
+    path: Option<&Path>,
+    rules: &[SquawkRule],
+    config: &CheckSqlConfig,
+) -> Result<Vec<RuleViolation>, CheckSqlError> {
+    let (tree, skipped) = parse_tree(sql, config)?;
 
-    let mut errs = vec![];
-    for rule in RULES.iter().filter(|r| !excluded_rules.contains(&r.name)) {
-        errs.extend((rule.func)(&tree, pg_version, assume_in_transaction));
+    let mut errs = check_tree(&tree, config);
+    errs.extend(
+        run_rules(&tree, rules.iter(), config)
+            .into_iter()
+            .map(|mut violation| {
+                if let Some(rule) = rules.iter().find(|r| r.name == violation.kind) {
+                    violation.severity = config
+                        .severity_overrides
+                        .get(&rule.name)
+                        .copied()
+                        .unwrap_or(rule.severity);
+                    violation.lock_level = rule.lock_level.or(violation.lock_level);
+                    if violation.messages.is_empty() {
+                        violation.messages = rule.messages.clone();
+                    }
+                }
+                violation
+            }),
+    );
+
+    let mut errs = remove_ignored_violations(sql, &tree, errs);
+    errs.extend(skipped.into_iter().map(invalid_statement_violation));
+    if let Some(baseline) = &config.baseline {
+        errs = baseline.filter_file(path, sql, errs);
+    }
+    if config.include_snippets {
+        add_snippets(sql, &mut errs);
     }
 
-    errs.sort_by_key(|v| v.span.start);
+    sort_by_position(&mut errs);
+    match config.sort_order {
+        SortOrder::Position => {}
+        SortOrder::Severity => errs.sort_by_key(|v| Reverse(v.severity)),
+        SortOrder::Rule => errs.sort_by(|a, b| a.kind.cmp(&b.kind)),
+    }
 
     Ok(errs)
 }
 
+/// Set each violation's snippet to the text of its statement.
+fn add_snippets(sql: &str, errs: &mut [RuleViolation]) {
+    for violation in errs {
+        violation.snippet = Some(statement_text(sql, violation).to_string());
+    }
+}
+
+/// Sort violations by where their statement starts, breaking ties by where it
+/// ends and then by rule id, so the order doesn't depend on the order the
+/// rules ran in. Sorts are stable, so violations of the same rule for the same
+/// statement keep the order the rule reported them in.
+fn sort_by_position(errs: &mut [RuleViolation]) {
+    errs.sort_by_cached_key(|v| {
+        // spans without a length run to the end of the SQL.
+        let end = v.span.end().unwrap_or(i32::MAX);
+        (v.span.start, end, v.kind.id())
+    });
+}
+
+/// Violations from `check_sql_iter`, checked one rule at a time as the
+/// iterator advances.
+pub struct Violations<'a> {
+    sql: &'a str,
+    config: &'a CheckSqlConfig,
+    tree: Vec<RawStmt>,
+    ignores: Ignores,
+    rules: std::slice::Iter<'static, SquawkRule>,
+    /// Violations of the last rule that ran.
+    pending: std::vec::IntoIter<RuleViolation>,
+    skipped: std::vec::IntoIter<SkippedStmt>,
+}
+
+impl Iterator for Violations<'_> {
+    type Item = RuleViolation;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(violation) = self.pending.next() {
+                return Some(violation);
+            }
+            let mut errs = match self.rules.next() {
+                Some(rule) => {
+                    let mut errs = run_rules(&self.tree, std::iter::once(rule), self.config);
+                    errs.retain(|v| !self.ignores.contains(v));
+                    errs
+                }
+                None => vec![invalid_statement_violation(self.skipped.next()?)],
+            };
+            if let Some(baseline) = &self.config.baseline {
+                errs = baseline.filter_file(None, self.sql, errs);
+            }
+            if self.config.include_snippets {
+                add_snippets(self.sql, &mut errs);
+            }
+            self.pending = errs.into_iter();
+        }
+    }
+}
+
+/// Like `check_sql`, but return the violations as an iterator that runs each
+/// rule as it's reached, so only one rule's violations are held at a time,
+/// e.g. to stream the violations for a large SQL dump.
+///
+/// Violations are grouped by rule in the order of `RULES`, followed by the
+/// `invalid-statement` violations. They aren't sorted, so `sort_order` is
+/// ignored.
+pub fn check_sql_iter<'a>(
+    sql: &'a str,
+    config: &'a CheckSqlConfig,
+) -> Result<Violations<'a>, CheckSqlError> {
+    let (tree, skipped) = parse_tree(sql, config)?;
+    Ok(Violations {
+        sql,
+        config,
+        ignores: Ignores::new(sql, &tree),
+        tree,
+        rules: RULES.iter(),
+        pending: vec![].into_iter(),
+        skipped: skipped.into_iter(),
+    })
+}
+
+/// Read all of `reader` and lint it with `check_sql`, e.g. for a migration
+/// streamed from object storage.
+///
+/// The SQL is read into a single buffer that's checked for UTF-8 in place.
+///
+/// # Errors
+///
+/// Returns `CheckFileError::IoError` if the read fails and
+/// `CheckFileError::InvalidUtf8` if the SQL isn't valid UTF-8.
+pub fn check_reader<R: Read>(
+    reader: R,
+    config: &CheckSqlConfig,
+) -> Result<Vec<RuleViolation>, CheckFileError> {
+    check_reader_in_file(reader, None, config)
+}
+
+fn check_reader_in_file<R: Read>(
+    mut reader: R,
+    path: Option<&Path>,
+    config: &CheckSqlConfig,
+) -> Result<Vec<RuleViolation>, CheckFileError> {
+    let mut buf = vec![];
+    reader.read_to_end(&mut buf)?;
+    let sql = String::from_utf8(buf)?;
+    Ok(check_sql_in_file(&sql, path, &[], config)?)
+}
+
+fn check_file(path: &Path, config: &CheckSqlConfig) -> Result<Vec<RuleViolation>, CheckFileError> {
+    check_reader_in_file(std::fs::File::open(path)?, Some(path), config)
+}
+
+/// Lint each file with `check_sql`.
+///
+/// A file that can't be read or parsed gets an error entry instead of
+/// stopping the run, so the violations in the other files are still returned.
+///
+/// With `CheckSqlConfig::threads` set, the files are split between that many
+/// threads. The results are keyed by path either way, so the order doesn't
+/// depend on which thread finishes first.
+#[must_use]
+pub fn check_files(
+    paths: &[PathBuf],
+    config: &CheckSqlConfig,
+) -> BTreeMap<PathBuf, Result<Vec<RuleViolation>, CheckFileError>> {
+    let threads = config.threads.map_or(1, NonZeroUsize::get).min(paths.len());
+    if threads <= 1 {
+        return paths
+            .iter()
+            .map(|path| (path.clone(), check_file(path, config)))
+            .collect();
+    }
+    // threads take the next unchecked file so a few large files don't hold
+    // up the rest.
+    let next = AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = vec![];
+                    while let Some(path) = paths.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        results.push((path.clone(), check_file(path, config)));
+                    }
+                    results
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|err| std::panic::resume_unwind(err))
+            })
+            .collect()
+    })
+}
+
+/// Count how many times each rule was violated, e.g. to track the most common
+/// violations across many runs. Keys are ordered like the
+/// `RuleViolationKind` variants.
+#[must_use]
+pub fn summarize(violations: &[RuleViolation]) -> BTreeMap<RuleViolationKind, usize> {
+    let mut counts = BTreeMap::new();
+    for violation in violations {
+        *counts.entry(violation.kind.clone()).or_insert(0) += 1;
+    }
+    counts
+}
+
 pub fn check_sql_with_rule(
     sql: &str,
     rule_kind: &RuleViolationKind,
@@ -400,11 +1617,16 @@ pub fn check_sql_with_rule(
     let mut errs = vec![];
     for rule in RULES.iter() {
         if rule.name == *rule_kind {
-            errs.extend((rule.func)(&tree, pg_version, assume_in_transaction));
+            errs.extend((rule.func)(
+                &tree,
+                pg_version,
+                assume_in_transaction,
+                &RuleOptions::new(),
+            ));
         }
     }
 
-    errs.sort_by_key(|v| v.span.start);
+    sort_by_position(&mut errs);
 
     Ok(errs)
 }
@@ -413,6 +1635,8 @@ pub fn check_sql_with_rule(
 mod test_rules {
     use super::*;
     use insta::{assert_debug_snapshot, assert_display_snapshot};
+    use serde_json::json;
+    use squawk_parser::ast::{Stmt, TableElt};
     use std::convert::TryFrom;
     use std::str::FromStr;
 
@@ -433,6 +1657,40 @@ mod test_rules {
             assert_eq!(RuleViolationKind::try_from(rule_str.as_ref()), Ok(rule));
         }
     }
+    #[test]
+    fn test_rule_id_round_trip() {
+        for rule in RULES.iter().map(|r| r.name.clone()) {
+            let id = rule.id();
+            assert_eq!(id, rule.to_string());
+            assert_eq!(RuleViolationKind::try_from(id.as_ref()), Ok(rule.clone()));
+            assert_eq!(rule.doc_url(), format!("https://squawkhq.com/docs/{id}"));
+        }
+    }
+    #[test]
+    fn test_explanation() {
+        let explanation = RuleViolationKind::RequireConcurrentIndexCreation
+            .explanation()
+            .unwrap();
+        assert!(explanation.contains("CREATE INDEX CONCURRENTLY"));
+        // every rule that reports a lock explains how to avoid it.
+        for rule in RULES.iter().filter(|r| r.lock_level.is_some()) {
+            assert!(rule.name.explanation().is_some(), "{}", rule.name);
+        }
+        assert_eq!(RuleViolationKind::PreferTimestampTz.explanation(), None);
+    }
+    #[test]
+    fn test_rule_lookup() {
+        for rule in RULES.iter() {
+            let found = rule_by_kind(&rule.name).expect("rule for every kind in RULES");
+            assert_eq!(found.name, rule.name);
+            assert_eq!(found.messages, rule.messages);
+        }
+        assert!(rule_by_kind(&RuleViolationKind::InvalidStatement).is_none());
+        assert_eq!(
+            all_rule_names(),
+            RULES.iter().map(|r| r.name.to_string()).collect::<Vec<_>>()
+        );
+    }
     /// Ensure rule names don't change
     #[test]
     fn test_rule_names_debug_snap() {
@@ -445,6 +1703,587 @@ mod test_rules {
         assert_display_snapshot!(rule_names.join("\n"));
     }
 
+    #[test]
+    fn test_violations_use_rule_severity() {
+        let sql = r#"
+DROP DATABASE "table_name";
+ALTER TABLE "core_foo" ADD COLUMN "bar" varchar(100);
+  "#;
+
+        let config = CheckSqlConfig {
+            excluded_rules: vec![RuleViolationKind::PreferRobustStmts],
+            ..CheckSqlConfig::default()
+        };
+        let res = check_sql(sql, &config).expect("valid parsing of SQL");
+        let severities: Vec<(RuleViolationKind, Severity)> =
+            res.into_iter().map(|v| (v.kind, v.severity)).collect();
+        assert_eq!(
+            severities,
+            vec![
+                (RuleViolationKind::BanDropDatabase, Severity::Error),
+                (RuleViolationKind::PreferTextField, Severity::Warning),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_severity_overrides() {
+        let sql = r#"
+ALTER TABLE "core_foo" ADD COLUMN "bar" varchar(100);
+CREATE TABLE "core_bar" ("id" bigint PRIMARY KEY, "code" char(10));
+  "#;
+
+        let config = CheckSqlConfig::builder()
+            .severity(RuleViolationKind::PreferTextField, Severity::Error)
+            .severity(RuleViolationKind::BanCharField, Severity::Info)
+            .build();
+        let is_overridden = |v: &RuleViolation| {
+            matches!(
+                v.kind,
+                RuleViolationKind::PreferTextField | RuleViolationKind::BanCharField
+            )
+        };
+        let severities: Vec<(RuleViolationKind, Severity)> = check_sql(sql, &config)
+            .expect("valid parsing of SQL")
+            .into_iter()
+            .filter(is_overridden)
+            .map(|v| (v.kind, v.severity))
+            .collect();
+        assert_eq!(
+            severities,
+            vec![
+                (RuleViolationKind::PreferTextField, Severity::Error),
+                (RuleViolationKind::BanCharField, Severity::Info),
+            ]
+        );
+
+        let severities: Vec<(RuleViolationKind, Severity)> = check_sql_iter(sql, &config)
+            .expect("valid parsing of SQL")
+            .filter(is_overridden)
+            .map(|v| (v.kind, v.severity))
+            .collect();
+        assert_eq!(
+            severities,
+            vec![
+                (RuleViolationKind::BanCharField, Severity::Info),
+                (RuleViolationKind::PreferTextField, Severity::Error),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_only() {
+        let sql = r#"
+ALTER TABLE "core_recipe" DROP COLUMN "foo";
+ALTER TABLE "core_recipe" ADD COLUMN "bar" varchar(100);
+ALTER TABLE "core_recipe" OWNER TO "app";
+  "#;
+        let kinds = |config: &CheckSqlConfig| -> Vec<RuleViolationKind> {
+            check_sql(sql, config)
+                .expect("valid parsing of SQL")
+                .into_iter()
+                .map(|v| v.kind)
+                .collect()
+        };
+
+        let config = CheckSqlConfig::builder()
+            .only(RuleViolationKind::BanDropColumn)
+            .build();
+        assert_eq!(kinds(&config), vec![RuleViolationKind::BanDropColumn]);
+
+        // `only` wins over the excluded rules, and opt-in rules run when listed.
+        let config = CheckSqlConfig::builder()
+            .exclude(RuleViolationKind::BanDropColumn)
+            .only(RuleViolationKind::BanDropColumn)
+            .only(RuleViolationKind::BanAlterOwner)
+            .build();
+        assert_eq!(
+            kinds(&config),
+            vec![
+                RuleViolationKind::BanDropColumn,
+                RuleViolationKind::BanAlterOwner,
+            ]
+        );
+        assert!(!config.is_enabled(&RuleViolationKind::PreferTextField));
+    }
+
+    #[test]
+    fn test_ignore_psql_meta_commands() {
+        let sql = r#"\set ON_ERROR_STOP on
+ALTER TABLE "core_recipe" DROP COLUMN "foo";
+\echo 'dropped foo'
+  \set table core_bar
+ALTER TABLE "core_bar" DROP COLUMN "bar";
+"#;
+        let config = CheckSqlConfig::builder()
+            .only(RuleViolationKind::BanDropColumn)
+            .include_snippets(true);
+        assert!(check_sql(sql, &config.clone().build()).is_err());
+
+        let config = config.ignore_psql_meta_commands(true).build();
+        let res = check_sql(sql, &config).expect("valid parsing of SQL");
+        assert_eq!(
+            res.iter().map(|v| v.location(sql)).collect::<Vec<_>>(),
+            vec![(2, 1), (5, 1)]
+        );
+        assert_eq!(
+            res.iter()
+                .map(|v| v.snippet.as_deref().unwrap())
+                .collect::<Vec<_>>(),
+            vec![
+                "\nALTER TABLE \"core_recipe\" DROP COLUMN \"foo\"",
+                "\nALTER TABLE \"core_bar\" DROP COLUMN \"bar\"",
+            ]
+        );
+
+        let kinds: Vec<RuleViolationKind> = check_sql_iter(sql, &config)
+            .expect("valid parsing of SQL")
+            .map(|v| v.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                RuleViolationKind::BanDropColumn,
+                RuleViolationKind::BanDropColumn,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_include_snippets() {
+        let sql = r#"
+-- drop the unused column
+ALTER TABLE "core_recipe" DROP COLUMN "foo";
+ALTER TABLE "core_recipe" ADD COLUMN "bar" varchar(100);
+"#;
+        let violation_snippets = |config: &CheckSqlConfig| -> Vec<Option<String>> {
+            check_sql(sql, config)
+                .expect("valid parsing of SQL")
+                .into_iter()
+                .map(|v| v.snippet)
+                .collect()
+        };
+        let config = CheckSqlConfig::builder()
+            .only(RuleViolationKind::BanDropColumn)
+            .only(RuleViolationKind::PreferTextField);
+        assert_eq!(
+            violation_snippets(&config.clone().build()),
+            vec![None, None]
+        );
+
+        let config = config.include_snippets(true).build();
+        let res = check_sql(sql, &config).expect("valid parsing of SQL");
+        assert_eq!(res.len(), 2);
+        for violation in &res {
+            #[allow(clippy::cast_sign_loss)]
+            let start = violation.span.start as usize;
+            #[allow(clippy::cast_sign_loss)]
+            let end = start + violation.span.len.unwrap() as usize;
+            assert_eq!(violation.snippet.as_deref(), Some(&sql[start..end]));
+        }
+        assert_eq!(
+            res[0].snippet.as_deref(),
+            Some("\n-- drop the unused column\nALTER TABLE \"core_recipe\" DROP COLUMN \"foo\"")
+        );
+        assert_eq!(
+            serde_json::to_value(&res[1]).unwrap()["snippet"],
+            "\nALTER TABLE \"core_recipe\" ADD COLUMN \"bar\" varchar(100)"
+        );
+
+        let snippets: Vec<Option<String>> = check_sql_iter(sql, &config)
+            .expect("valid parsing of SQL")
+            .map(|v| v.snippet)
+            .collect();
+        assert!(snippets.iter().all(Option::is_some));
+    }
+
+    #[test]
+    fn test_empty_sql() {
+        let recover = CheckSqlConfig::builder()
+            .recover_from_parse_errors(true)
+            .build();
+        for sql in [
+            "",
+            "  \n\t\n",
+            "-- TODO: add the migration\n",
+            "/* nothing to do */\n-- really\n",
+        ] {
+            for config in [&CheckSqlConfig::default(), &recover] {
+                assert_eq!(check_sql(sql, config), Ok(vec![]), "{sql:?}");
+                assert_eq!(
+                    check_sql_iter(sql, config).map(Iterator::count),
+                    Ok(0),
+                    "{sql:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_check_tree() {
+        let sql = r#"
+ALTER TABLE "core_recipe" DROP COLUMN "foo";
+CREATE INDEX "core_recipe_bar_idx" ON "core_recipe" ("bar");
+"#;
+        let config = CheckSqlConfig::default();
+        let tree = parse_sql_query(sql).unwrap();
+        let mut from_tree = check_tree(&tree, &config);
+        from_tree.sort_by_key(|v| v.span.start);
+        assert_eq!(from_tree, check_sql(sql, &config).unwrap());
+
+        let config = CheckSqlConfig {
+            excluded_rules: vec![RuleViolationKind::BanDropColumn],
+            ..CheckSqlConfig::default()
+        };
+        assert!(check_tree(&tree, &config)
+            .iter()
+            .all(|v| v.kind != RuleViolationKind::BanDropColumn));
+    }
+
+    #[test]
+    fn test_check_sql_iter() {
+        let sql = r#"
+ALTER TABLE "core_recipe" DROP COLUMN "foo";
+-- squawk-ignore require-concurrent-index-creation
+CREATE INDEX "core_recipe_bar_idx" ON "core_recipe" ("bar");
+CREATE INDEX "core_recipe_baz_idx" ON "core_recipe" ("baz");
+"#;
+        let config = CheckSqlConfig::default();
+        let mut streamed: Vec<RuleViolation> = check_sql_iter(sql, &config).unwrap().collect();
+        streamed.sort_by_key(|v| v.span.start);
+        assert_eq!(streamed, check_sql(sql, &config).unwrap());
+        assert!(streamed
+            .iter()
+            .any(|v| v.kind == RuleViolationKind::RequireConcurrentIndexCreation));
+
+        let config = CheckSqlConfig {
+            recover_from_parse_errors: true,
+            ..CheckSqlConfig::default()
+        };
+        let last = check_sql_iter("SELECT 1;\nSELEC 2;", &config)
+            .unwrap()
+            .last()
+            .unwrap();
+        assert_eq!(last.kind, RuleViolationKind::InvalidStatement);
+    }
+
+    fn require_created_at(
+        tree: &[RawStmt],
+        _pg_version: Option<Version>,
+        _assume_in_transaction: bool,
+        _rule_options: &RuleOptions,
+    ) -> Vec<RuleViolation> {
+        let missing_created_at = |stmt: &Stmt| match stmt {
+            Stmt::CreateStmt(stmt) => !stmt.table_elts.iter().any(|elt| match elt {
+                TableElt::ColumnDef(column) => column.colname.as_deref() == Some("created_at"),
+                _ => false,
+            }),
+            _ => false,
+        };
+        tree.iter()
+            .filter(|raw_stmt| missing_created_at(&raw_stmt.stmt))
+            .map(|raw_stmt| {
+                RuleViolation::new(
+                    RuleViolationKind::Custom("require-created-at".into()),
+                    raw_stmt,
+                    None,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_check_sql_with_rules() {
+        let rule = SquawkRule {
+            name: RuleViolationKind::Custom("require-created-at".into()),
+            severity: Severity::Error,
+            lock_level: None,
+            opt_in: false,
+            func: require_created_at,
+            messages: vec![ViolationMessage::Help(
+                "Add a created_at timestamptz column.".into(),
+            )],
+            explanation: None,
+        };
+        let sql = r#"
+CREATE TABLE "core_foo" ("id" bigint PRIMARY KEY);
+CREATE TABLE "core_bar" ("id" bigint PRIMARY KEY, "created_at" timestamptz);
+"#;
+        let config = CheckSqlConfig::default();
+        let res: Vec<RuleViolation> = check_sql_with_rules(sql, &[rule.clone()], &config)
+            .unwrap()
+            .into_iter()
+            .filter(|v| v.kind == rule.name)
+            .collect();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].span.start, 0);
+        assert_eq!(res[0].severity, Severity::Error);
+        assert_eq!(res[0].messages, rule.messages);
+        assert_eq!(res[0].kind.to_string(), "require-created-at");
+        assert_eq!(
+            serde_json::to_value(&res[0].kind).unwrap(),
+            json!("require-created-at")
+        );
+
+        // custom rules can be excluded like built-in rules.
+        let config = CheckSqlConfig {
+            excluded_rules: vec![rule.name.clone()],
+            ..CheckSqlConfig::default()
+        };
+        assert!(check_sql_with_rules(sql, &[rule.clone()], &config)
+            .unwrap()
+            .iter()
+            .all(|v| v.kind != rule.name));
+    }
+
+    #[test]
+    fn test_violations_at_same_span() {
+        let rule = SquawkRule {
+            name: RuleViolationKind::Custom("require-created-at".into()),
+            severity: Severity::Error,
+            lock_level: None,
+            opt_in: false,
+            func: require_created_at,
+            messages: vec![],
+            explanation: None,
+        };
+        let sql = r#"
+CREATE TABLE "core_foo" ("id" bigint PRIMARY KEY);
+CREATE TABLE "core_bar" ("id" bigint PRIMARY KEY, "created_at" timestamptz);
+"#;
+        // custom rules run after the built-in rules, but are sorted by id with
+        // them.
+        let kinds: Vec<RuleViolationKind> =
+            check_sql_with_rules(sql, &[rule.clone()], &CheckSqlConfig::default())
+                .unwrap()
+                .into_iter()
+                .filter(|v| v.span.start == 0)
+                .map(|v| v.kind)
+                .filter(|kind| *kind == rule.name || *kind == RuleViolationKind::PreferRobustStmts)
+                .collect();
+        assert_eq!(kinds, vec![RuleViolationKind::PreferRobustStmts, rule.name]);
+    }
+
+    #[test]
+    fn test_summarize() {
+        let sql = r#"
+ALTER TABLE "core_foo" DROP COLUMN "bar";
+DROP DATABASE "table_name";
+ALTER TABLE "core_foo" DROP COLUMN "baz";
+  "#;
+
+        let config = CheckSqlConfig {
+            excluded_rules: vec![RuleViolationKind::PreferRobustStmts],
+            ..CheckSqlConfig::default()
+        };
+        let res = check_sql(sql, &config).expect("valid parsing of SQL");
+        let summary: Vec<(RuleViolationKind, usize)> = summarize(&res).into_iter().collect();
+        assert_eq!(
+            summary,
+            vec![
+                (RuleViolationKind::BanDropDatabase, 1),
+                (RuleViolationKind::BanDropColumn, 2),
+            ]
+        );
+        assert!(summarize(&[]).is_empty());
+    }
+
+    /// Ensure the JSON schema of violations stays stable for tooling.
+    #[test]
+    fn test_violations_serialize_to_json() {
+        let sql = r#"DROP TABLE "foo";"#;
+        let res = check_sql_with_rule(sql, &RuleViolationKind::BanDropTable, None, false)
+            .expect("valid parsing of SQL");
+        assert_eq!(
+            serde_json::to_value(&res).unwrap(),
+            json!([{
+                "kind": "ban-drop-table",
+                "severity": "error",
+                "lock_level": null,
+                "span": { "start": 0, "end": 16, "len": 16 },
+                "statement_kind": "drop_table",
+                "messages": [
+                    { "Note": "Dropping a table may break existing clients." },
+                    { "Help": "Remove all references to the table from your application code and deploy that change before dropping the table." },
+                ],
+            }])
+        );
+    }
+
+    #[test]
+    fn test_statement_kind() {
+        let sql = r#"
+BEGIN;
+CREATE INDEX "field_name_idx" ON "table_name" ("field_name");
+ALTER TABLE "table_name" DROP COLUMN "foo";
+DROP TABLE "foo";
+COMMIT;
+"#;
+        let config = CheckSqlConfig::builder()
+            .only(RuleViolationKind::RequireConcurrentIndexCreation)
+            .only(RuleViolationKind::BanDropColumn)
+            .only(RuleViolationKind::BanDropTable)
+            .build();
+        let res = check_sql(sql, &config).unwrap();
+        assert_eq!(
+            res.iter().map(|v| v.statement_kind).collect::<Vec<_>>(),
+            vec![
+                StatementKind::CreateIndex,
+                StatementKind::AlterTable,
+                StatementKind::DropTable,
+            ]
+        );
+
+        let config = CheckSqlConfig::builder()
+            .recover_from_parse_errors(true)
+            .build();
+        let res = check_sql("SELECT * FROM;", &config).unwrap();
+        assert_eq!(res[0].kind, RuleViolationKind::InvalidStatement);
+        assert_eq!(res[0].statement_kind, StatementKind::Other);
+    }
+
+    #[test]
+    fn test_check_files() {
+        let dir = std::env::temp_dir().join(format!("squawk-check-files-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let valid = dir.join("valid.sql");
+        std::fs::write(&valid, r#"DROP TABLE "foo";"#).unwrap();
+        let invalid = dir.join("invalid.sql");
+        std::fs::write(&invalid, "SELECT * FROM;").unwrap();
+        let missing = dir.join("missing.sql");
+
+        let config = CheckSqlConfig {
+            excluded_rules: vec![RuleViolationKind::PreferRobustStmts],
+            ..CheckSqlConfig::default()
+        };
+        let res = check_files(&[valid.clone(), invalid.clone(), missing.clone()], &config);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(res.len(), 3);
+        let kinds: Vec<RuleViolationKind> = res[&valid]
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|v| v.kind.clone())
+            .collect();
+        assert_eq!(kinds, vec![RuleViolationKind::BanDropTable]);
+        assert!(matches!(res[&invalid], Err(CheckFileError::CheckSql(_))));
+        assert!(matches!(res[&missing], Err(CheckFileError::IoError(_))));
+    }
+
+    #[test]
+    fn test_check_reader() {
+        let sql = r#"ALTER TABLE "core_recipe" DROP COLUMN "foo";"#;
+        let config = CheckSqlConfig::builder()
+            .only(RuleViolationKind::BanDropColumn)
+            .build();
+        let res = check_reader(sql.as_bytes(), &config).unwrap();
+        assert_eq!(res, check_sql(sql, &config).unwrap());
+        assert_eq!(res.len(), 1);
+
+        let err = check_reader(&b"SELECT '\xff';"[..], &config).unwrap_err();
+        assert!(matches!(err, CheckFileError::InvalidUtf8(_)));
+        assert_eq!(
+            err.to_string(),
+            "SQL isn't valid UTF-8, invalid byte at offset 8"
+        );
+
+        let err = check_reader(&b"SELECT * FROM;"[..], &config).unwrap_err();
+        assert!(matches!(err, CheckFileError::CheckSql(_)));
+    }
+
+    #[test]
+    fn test_check_files_threads() {
+        let dir =
+            std::env::temp_dir().join(format!("squawk-check-files-threads-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut paths = vec![];
+        for i in 0..20 {
+            let path = dir.join(format!("{i:02}.sql"));
+            let sql = if i % 3 == 0 {
+                "SELECT * FROM;".to_string()
+            } else {
+                format!(r#"ALTER TABLE "foo" DROP COLUMN "bar_{i}";"#)
+            };
+            std::fs::write(&path, sql).unwrap();
+            paths.push(path);
+        }
+        paths.reverse();
+
+        let config = CheckSqlConfig {
+            excluded_rules: vec![RuleViolationKind::PreferRobustStmts],
+            ..CheckSqlConfig::default()
+        };
+        let kinds = |config: &CheckSqlConfig| -> Vec<(PathBuf, Option<Vec<RuleViolationKind>>)> {
+            check_files(&paths, config)
+                .into_iter()
+                .map(|(path, res)| {
+                    let kinds = res
+                        .ok()
+                        .map(|violations| violations.into_iter().map(|v| v.kind).collect());
+                    (path, kinds)
+                })
+                .collect()
+        };
+        let sequential = kinds(&config);
+        let parallel = kinds(&CheckSqlConfig {
+            threads: NonZeroUsize::new(4),
+            ..config.clone()
+        });
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(parallel, sequential);
+        assert_eq!(parallel.len(), 20);
+        assert!(parallel.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(parallel[0].1, None);
+        assert_eq!(parallel[1].1, Some(vec![RuleViolationKind::BanDropColumn]));
+    }
+
+    #[test]
+    fn test_recover_from_parse_errors() {
+        let sql = r#"
+ALTER TABLE "foo" DROP COLUMN "bar";
+CREATE FOO;
+DROP TABLE "foo";
+"#;
+        let config = CheckSqlConfig {
+            excluded_rules: vec![RuleViolationKind::PreferRobustStmts],
+            ..CheckSqlConfig::default()
+        };
+        assert!(check_sql(sql, &config).is_err());
+
+        let config = CheckSqlConfig {
+            recover_from_parse_errors: true,
+            ..config
+        };
+        let res = check_sql(sql, &config).expect("recovered from parse error");
+        let kinds_and_spans: Vec<(RuleViolationKind, i32, Option<i32>)> = res
+            .iter()
+            .map(|v| (v.kind.clone(), v.span.start, v.span.len))
+            .collect();
+        assert_eq!(
+            kinds_and_spans,
+            vec![
+                (RuleViolationKind::BanDropColumn, 0, Some(36)),
+                (RuleViolationKind::InvalidStatement, 37, Some(11)),
+                (RuleViolationKind::BanDropTable, 49, Some(17)),
+            ]
+        );
+        assert_eq!(res[1].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_parse_error_offset() {
+        let sql = "SELECT 1;\nALTER TABLE \"core_foo\" ADD COLUMN;\n";
+        let err = check_sql(sql, &CheckSqlConfig::default()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"Postgres failed to parse query: syntax error at or near ";""#
+        );
+        // the `;` after `ADD COLUMN`, rather than the start of the file.
+        assert_eq!(err.offset(), Some(43));
+    }
+
     /// Ensure we stort the resulting violations by where they occur in the file.
     #[test]
     fn test_check_rules_orderin() {
@@ -453,12 +2292,61 @@ mod test_rules {
   CREATE INDEX "field_name_idx" ON "table_name" ("field_name");
   "#;
 
-        let res = check_sql(sql, &[RuleViolationKind::PreferRobustStmts], None, false)
-            .expect("valid parsing of SQL");
+        let config = CheckSqlConfig {
+            excluded_rules: vec![RuleViolationKind::PreferRobustStmts],
+            ..CheckSqlConfig::default()
+        };
+        let res = check_sql(sql, &config).expect("valid parsing of SQL");
         let mut prev_span_start = -1;
         for violation in &res {
             assert!(violation.span.start > prev_span_start);
             prev_span_start = violation.span.start;
         }
     }
+
+    #[test]
+    fn test_sort_order() {
+        let sql = r#"
+ALTER TABLE "core_foo" ADD COLUMN "bar" varchar(100);
+DROP TABLE "core_bar";
+ALTER TABLE "core_foo" ADD COLUMN "baz" varchar(100);
+  "#;
+
+        let kinds_and_starts = |sort_order: SortOrder| -> Vec<(RuleViolationKind, i32)> {
+            let config = CheckSqlConfig {
+                excluded_rules: vec![RuleViolationKind::PreferRobustStmts],
+                sort_order,
+                ..CheckSqlConfig::default()
+            };
+            check_sql(sql, &config)
+                .expect("valid parsing of SQL")
+                .into_iter()
+                .map(|v| (v.kind, v.span.start))
+                .collect()
+        };
+        assert_eq!(
+            kinds_and_starts(SortOrder::Position),
+            vec![
+                (RuleViolationKind::PreferTextField, 0),
+                (RuleViolationKind::BanDropTable, 54),
+                (RuleViolationKind::PreferTextField, 77),
+            ]
+        );
+        assert_eq!(
+            kinds_and_starts(SortOrder::Severity),
+            vec![
+                (RuleViolationKind::BanDropTable, 54),
+                (RuleViolationKind::PreferTextField, 0),
+                (RuleViolationKind::PreferTextField, 77),
+            ]
+        );
+        assert_eq!(
+            kinds_and_starts(SortOrder::Rule),
+            vec![
+                (RuleViolationKind::PreferTextField, 0),
+                (RuleViolationKind::PreferTextField, 77),
+                (RuleViolationKind::BanDropTable, 54),
+            ]
+        );
+    }
 }
@@ -0,0 +1,79 @@
+use std::convert::TryFrom;
+use std::ops::Range;
+
+use squawk_parser::ast::RawStmt;
+
+/// Byte ranges of the lines that start with a psql meta-command, e.g. `\set`
+/// or `\i`, without their line break.
+///
+/// Lines inside a string or dollar-quoted body aren't told apart, so a line
+/// starting with a backslash there is treated as a meta-command too.
+fn meta_command_lines(sql: &str) -> Vec<Range<usize>> {
+    let mut lines = vec![];
+    let mut start = 0;
+    for line in sql.split_inclusive('\n') {
+        let text = line.trim_end_matches(['\n', '\r']);
+        if text.trim_start().starts_with('\\') {
+            lines.push(start..start + text.len());
+        }
+        start += line.len();
+    }
+    lines
+}
+
+/// `sql` with psql meta-commands replaced by spaces, so byte offsets into it
+/// are the same as into `sql`, along with the ranges that were replaced.
+/// `None` when there aren't any.
+pub(crate) fn blank_meta_commands(sql: &str) -> Option<(String, Vec<Range<usize>>)> {
+    let lines = meta_command_lines(sql);
+    if lines.is_empty() {
+        return None;
+    }
+    let mut blanked = sql.to_string();
+    for line in &lines {
+        blanked.replace_range(line.clone(), &" ".repeat(line.len()));
+    }
+    Some((blanked, lines))
+}
+
+/// Postgres includes everything after the previous statement in a
+/// statement's span, so move the start of statements preceded by
+/// meta-commands past them, e.g. so the location of a violation doesn't point
+/// at a `\set` line.
+pub(crate) fn skip_meta_commands(tree: &mut [RawStmt], blanked: &str, lines: &[Range<usize>]) {
+    for raw_stmt in tree {
+        #[allow(clippy::cast_sign_loss)]
+        let start = raw_stmt.stmt_location.max(0) as usize;
+        let rest = blanked.get(start..).unwrap_or_default();
+        let stmt_start = start + (rest.len() - rest.trim_start().len());
+        let Some(last) = lines
+            .iter()
+            .rfind(|line| line.start >= start && line.end <= stmt_start)
+        else {
+            continue;
+        };
+        let Ok(shift) = i32::try_from(last.end - start) else {
+            continue;
+        };
+        raw_stmt.stmt_location += shift;
+        raw_stmt.stmt_len = raw_stmt.stmt_len.map(|len| len - shift);
+    }
+}
+
+#[cfg(test)]
+mod test_psql {
+    use super::blank_meta_commands;
+
+    #[test]
+    fn test_blank_meta_commands() {
+        let sql = "\\set ON_ERROR_STOP on\nSELECT 1;\n  \\echo 'done'\r\nSELECT '\\x';\n";
+        let (blanked, lines) = blank_meta_commands(sql).unwrap();
+        assert_eq!(lines, vec![0..21, 32..46]);
+        assert_eq!(blanked.len(), sql.len());
+        assert_eq!(
+            blanked,
+            "                     \nSELECT 1;\n              \r\nSELECT '\\x';\n"
+        );
+        assert_eq!(blank_meta_commands("SELECT 1;\n"), None);
+    }
+}
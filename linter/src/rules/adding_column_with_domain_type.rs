@@ -0,0 +1,232 @@
+use std::collections::HashSet;
+
+use crate::{
+    config::RuleOptions,
+    rules::utils::tables_created_in_transaction,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind, Severity, ViolationMessage},
+};
+
+use serde_json::Value;
+use squawk_parser::ast::{AlterTableCmds, AlterTableDef, AlterTableType, RawStmt, Stmt, TypeName};
+
+// The base, range and multirange types from:
+//      select typname from pg_type where typnamespace = 'pg_catalog'::regnamespace;
+// along with type aliases and common extension types like citext and postgis.
+const BUILT_IN_TYPES: &str = include_str!("built_in_types.txt");
+
+fn last_name(names: &Value) -> Option<&str> {
+    names.as_array()?.last()?["String"]["sval"].as_str()
+}
+
+/// The name of the column's type when it isn't a known built-in type. Arrays
+/// are skipped since an array of a domain isn't a domain.
+fn custom_type_name<'a>(
+    type_name: &'a TypeName,
+    built_in_types: &HashSet<&str>,
+) -> Option<&'a str> {
+    if !type_name.array_bounds.is_empty() {
+        return None;
+    }
+    let (name, schema) = type_name.names.split_last()?;
+    let name = name.string.sval.as_str();
+    match schema {
+        [schema] if schema.string.sval == "pg_catalog" => None,
+        [] if built_in_types.contains(name) => None,
+        _ => Some(name),
+    }
+}
+
+/// Adding a column with a domain type that has constraints rewrites the table
+/// to check them, unlike adding a column with the domain's base type. Domains
+/// can't be told apart from other types without the database, so any type
+/// that isn't built-in is reported, except for types created in the file.
+#[must_use]
+pub fn adding_column_with_domain_type(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let built_in_types: HashSet<_> = BUILT_IN_TYPES
+        .lines()
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .collect();
+    let mut created_types = HashSet::new();
+    let mut constrained_domains = HashSet::new();
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            Stmt::CreateDomainStmt(stmt) => {
+                if let Some(name) = last_name(&stmt["domainname"]) {
+                    let has_constraints = stmt["constraints"]
+                        .as_array()
+                        .map_or(false, |constraints| !constraints.is_empty());
+                    if has_constraints {
+                        constrained_domains.insert(name);
+                    } else {
+                        created_types.insert(name);
+                    }
+                }
+            }
+            Stmt::CreateEnumStmt(stmt) | Stmt::CreateRangeStmt(stmt) => {
+                created_types.extend(last_name(&stmt["typeName"]));
+            }
+            Stmt::CompositeTypeStmt(stmt) => {
+                created_types.extend(stmt["typevar"]["relname"].as_str());
+            }
+            _ => continue,
+        }
+    }
+    let tables_created = tables_created_in_transaction(tree, assume_in_transaction);
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            Stmt::AlterTableStmt(stmt) if !tables_created.contains(&stmt.relation.relname) => {
+                for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
+                    if cmd.subtype != AlterTableType::AddColumn {
+                        continue;
+                    }
+                    let Some(AlterTableDef::ColumnDef(column_def)) = &cmd.def else {
+                        continue;
+                    };
+                    let Some(name) = custom_type_name(&column_def.type_name, &built_in_types)
+                    else {
+                        continue;
+                    };
+                    if created_types.contains(name) {
+                        continue;
+                    }
+                    let help = ViolationMessage::Help(
+                        "Add the column with the domain's base type, then add the constraints as NOT VALID and validate them separately.".into(),
+                    );
+                    if constrained_domains.contains(name) {
+                        let mut violation = RuleViolation::new(
+                            RuleViolationKind::AddingColumnWithDomainType,
+                            raw_stmt,
+                            Some(vec![
+                                ViolationMessage::Note(format!(
+                                    r#"Domain "{name}" has constraints, so adding a column with it rewrites the table while holding an ACCESS EXCLUSIVE lock."#
+                                )),
+                                help,
+                            ]),
+                        );
+                        violation.severity = Severity::Warning;
+                        errs.push(violation);
+                    } else {
+                        errs.push(RuleViolation::new(
+                            RuleViolationKind::AddingColumnWithDomainType,
+                            raw_stmt,
+                            Some(vec![
+                                ViolationMessage::Note(format!(
+                                    r#"Type "{name}" isn't a built-in type. If it's a domain with NOT NULL or CHECK constraints, adding the column rewrites the table while holding an ACCESS EXCLUSIVE lock."#
+                                )),
+                                help,
+                            ]),
+                        ));
+                    }
+                }
+            }
+            _ => continue,
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind, Severity, ViolationMessage},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(
+            sql,
+            &RuleViolationKind::AddingColumnWithDomainType,
+            None,
+            false,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_custom_type() {
+        let bad_sql = r#"
+ALTER TABLE "core_recipe" ADD COLUMN "rating" positive_int;
+ALTER TABLE "core_recipe" ADD COLUMN "code" "app"."country_code";
+  "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![
+                RuleViolationKind::AddingColumnWithDomainType,
+                RuleViolationKind::AddingColumnWithDomainType,
+            ]
+        );
+        assert_eq!(res[0].severity, Severity::Info);
+        assert_eq!(
+            res[1].messages[0],
+            ViolationMessage::Note(r#"Type "country_code" isn't a built-in type. If it's a domain with NOT NULL or CHECK constraints, adding the column rewrites the table while holding an ACCESS EXCLUSIVE lock."#.into())
+        );
+    }
+
+    #[test]
+    fn test_built_in_types() {
+        let ok_sql = r#"
+ALTER TABLE "core_recipe"
+    ADD COLUMN "a" integer,
+    ADD COLUMN "b" int8,
+    ADD COLUMN "c" text,
+    ADD COLUMN "d" varchar(100),
+    ADD COLUMN "e" timestamp with time zone,
+    ADD COLUMN "f" timestamptz,
+    ADD COLUMN "g" double precision,
+    ADD COLUMN "h" uuid,
+    ADD COLUMN "i" jsonb,
+    ADD COLUMN "j" numeric(10, 2),
+    ADD COLUMN "k" boolean,
+    ADD COLUMN "l" bytea,
+    ADD COLUMN "m" inet,
+    ADD COLUMN "n" tstzrange,
+    ADD COLUMN "o" citext,
+    ADD COLUMN "p" pg_catalog.int4,
+    ADD COLUMN "q" positive_int[];
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_types_created_in_file() {
+        let ok_sql = r#"
+CREATE TYPE "mood" AS ENUM ('happy', 'sad');
+CREATE DOMAIN "email" AS text;
+ALTER TABLE "core_recipe" ADD COLUMN "mood" "mood";
+ALTER TABLE "core_recipe" ADD COLUMN "email" "email";
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+
+        let bad_sql = r#"
+CREATE DOMAIN "positive_int" AS integer CHECK (VALUE > 0);
+ALTER TABLE "core_recipe" ADD COLUMN "rating" "positive_int";
+  "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::AddingColumnWithDomainType]
+        );
+        assert_eq!(res[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_table_created_in_transaction() {
+        let ok_sql = r#"
+BEGIN;
+CREATE TABLE "core_recipe" ("id" bigint);
+ALTER TABLE "core_recipe" ADD COLUMN "rating" positive_int;
+COMMIT;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
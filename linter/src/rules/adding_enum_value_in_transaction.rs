@@ -0,0 +1,129 @@
+use crate::config::RuleOptions;
+use crate::rules::utils::TransactionTracker;
+use crate::versions::Version;
+use crate::violations::{RuleViolation, RuleViolationKind};
+
+use serde_json::Value;
+use squawk_parser::ast::{RawStmt, Stmt};
+
+/// `ALTER TYPE ... ADD VALUE`, `RENAME VALUE` also parses to an `AlterEnumStmt`
+/// but is fine in a transaction.
+fn is_add_value(stmt: &Value) -> bool {
+    stmt.get("oldVal").is_none()
+}
+
+#[must_use]
+pub fn adding_enum_value_in_transaction(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let mut transaction = TransactionTracker::new(assume_in_transaction);
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        transaction.update(&raw_stmt.stmt);
+        match &raw_stmt.stmt {
+            Stmt::AlterEnumStmt(stmt) if transaction.inside_transaction() && is_add_value(stmt) => {
+                errs.push(RuleViolation::new(
+                    RuleViolationKind::AddingEnumValueInTransaction,
+                    raw_stmt,
+                    None,
+                ));
+            }
+            _ => continue,
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(
+            sql,
+            &RuleViolationKind::AddingEnumValueInTransaction,
+            None,
+            false,
+        )
+        .unwrap()
+    }
+
+    fn lint_sql_assuming_in_transaction(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(
+            sql,
+            &RuleViolationKind::AddingEnumValueInTransaction,
+            None,
+            true,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_add_value_outside_transaction() {
+        let ok_sql = r#"
+ALTER TYPE "mood" ADD VALUE 'happy';
+ALTER TYPE "mood" ADD VALUE IF NOT EXISTS 'sad' BEFORE 'happy';
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_add_value_in_transaction() {
+        let bad_sql = r#"
+BEGIN;
+ALTER TYPE "mood" ADD VALUE 'happy';
+COMMIT;
+ALTER TYPE "mood" ADD VALUE 'sad';
+  "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::AddingEnumValueInTransaction]
+        );
+        assert_eq!(res[0].span.start, 7);
+    }
+
+    #[test]
+    fn test_add_value_in_start_transaction() {
+        let bad_sql = r#"
+START TRANSACTION;
+ALTER TYPE "mood" ADD VALUE 'happy';
+ROLLBACK;
+ALTER TYPE "mood" ADD VALUE 'sad';
+  "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::AddingEnumValueInTransaction]
+        );
+        assert_eq!(res[0].span.start, 19);
+    }
+
+    #[test]
+    fn test_add_value_assuming_in_transaction() {
+        let bad_sql = r#"
+ALTER TYPE "mood" ADD VALUE 'happy';
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql_assuming_in_transaction(bad_sql)),
+            vec![RuleViolationKind::AddingEnumValueInTransaction]
+        );
+    }
+
+    #[test]
+    fn test_rename_value_in_transaction() {
+        let ok_sql = r#"
+BEGIN;
+ALTER TYPE "mood" RENAME VALUE 'happy' TO 'glad';
+COMMIT;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
@@ -1,8 +1,11 @@
 use std::collections::HashSet;
+use std::convert::TryFrom;
 
 use crate::{
+    config::RuleOptions,
+    rules::utils::VOLATILE_FUNCTIONS,
     versions::Version,
-    violations::{RuleViolation, RuleViolationKind},
+    violations::{RuleViolation, RuleViolationKind, Severity, ViolationMessage},
 };
 
 use serde_json::{json, Value};
@@ -27,6 +30,57 @@ fn is_non_volatile_func_call(raw_expr: &Value, non_volatile_funcs: &HashSet<Stri
     raw_expr["FuncCall"]["args"] == Value::Null && non_volatile_funcs.contains(func_name)
 }
 
+/// The name of the known volatile function called by the default, e.g.
+/// `random()` or `random()::integer`.
+fn volatile_func_name(raw_expr: &Value) -> Option<String> {
+    let func_call = if raw_expr["TypeCast"] == Value::Null {
+        &raw_expr["FuncCall"]
+    } else {
+        &raw_expr["TypeCast"]["arg"]["FuncCall"]
+    };
+    // the name can be schema qualified, e.g. `public.uuid_generate_v4()`.
+    let func_name = func_call["funcname"].as_array()?.last()?["String"]["sval"]
+        .as_str()?
+        .to_lowercase();
+    VOLATILE_FUNCTIONS
+        .contains(&func_name.as_str())
+        .then_some(func_name)
+}
+
+/// The number of elements in a literal array or composite default, e.g.
+/// `ARRAY[1, 2, 3]`, `'{1,2,3}'::integer[]`, or `ROW(1, 'a')`. `None` when the
+/// default isn't made up of constants.
+fn literal_elements(raw_expr: &Value) -> Option<usize> {
+    if raw_expr["TypeCast"] != Value::Null {
+        return literal_elements(&raw_expr["TypeCast"]["arg"]);
+    }
+    let elements = if raw_expr["A_ArrayExpr"] != Value::Null {
+        &raw_expr["A_ArrayExpr"]["elements"]
+    } else if raw_expr["RowExpr"] != Value::Null {
+        &raw_expr["RowExpr"]["args"]
+    } else if let Some(text) = raw_expr["A_Const"]["sval"]["sval"].as_str() {
+        // array and composite literals like '{1,2,3}' and '(1,a)'. Quoted
+        // commas are counted too, which only overestimates the size.
+        let inner = text.trim();
+        let is_literal = (inner.starts_with('{') && inner.ends_with('}'))
+            || (inner.starts_with('(') && inner.ends_with(')'));
+        return Some(if is_literal && inner.len() > 2 {
+            inner.matches(',').count() + 1
+        } else {
+            1
+        });
+    } else if raw_expr["A_Const"] != Value::Null {
+        return Some(1);
+    } else {
+        return None;
+    };
+    match elements.as_array() {
+        Some(elements) => elements.iter().map(literal_elements).sum(),
+        // e.g. `ARRAY[]::integer[]`.
+        None => Some(0),
+    }
+}
+
 // Generated via the following Postgres query:
 //      select proname from pg_proc where provolatile <> 'v';
 const NON_VOLATILE_BUILT_IN_FUNCTIONS: &str = include_str!("non_volatile_built_in_functions.txt");
@@ -36,7 +90,13 @@ pub fn adding_field_with_default(
     tree: &[RawStmt],
     pg_version: Option<Version>,
     _assume_in_transaction: bool,
+    rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
+    // literal defaults with more elements than this are noted, e.g. a large
+    // array. Off by default.
+    let max_default_elements = rule_options
+        .get("max_default_elements")
+        .and_then(Value::as_u64);
     let mut errs = vec![];
 
     let non_volatile_funcs: HashSet<_> = NON_VOLATILE_BUILT_IN_FUNCTIONS
@@ -55,10 +115,52 @@ pub fn adding_field_with_default(
                         Some(AlterTableDef::ColumnDef(def)) => {
                             for ColumnDefConstraint::Constraint(constraint) in &def.constraints {
                                 if constraint.contype == ConstrType::Default {
+                                    let def = json!({});
+                                    let raw_expr = constraint.raw_expr.as_ref().unwrap_or(&def);
+                                    // a volatile default always rewrites the table.
+                                    if let Some(func_name) = volatile_func_name(raw_expr) {
+                                        let messages = if func_name == "nextval" {
+                                            // what a serial column desugars to.
+                                            vec![
+                                                ViolationMessage::Note("nextval() is VOLATILE, like the DEFAULT of a serial column, so adding a field with it as the DEFAULT requires a table rewrite with an ACCESS EXCLUSIVE lock, even in Postgres 11+.".into()),
+                                                ViolationMessage::Help("Add the field as nullable, then SET DEFAULT nextval(...) for new rows and backfill the existing rows in batches.".into()),
+                                            ]
+                                        } else {
+                                            vec![
+                                                ViolationMessage::Note(format!("{func_name}() is VOLATILE, so adding a field with it as the DEFAULT requires a table rewrite with an ACCESS EXCLUSIVE lock, even in Postgres 11+.")),
+                                                ViolationMessage::Help("Add the field as nullable, then set a default, backfill, and remove nullability.".into()),
+                                            ]
+                                        };
+                                        let mut violation = RuleViolation::new(
+                                            RuleViolationKind::AddingFieldWithDefault,
+                                            raw_stmt,
+                                            Some(messages),
+                                        );
+                                        violation.severity = Severity::Error;
+                                        errs.push(violation);
+                                        continue;
+                                    }
+                                    // large defaults are noted for every version, since
+                                    // they're copied into each row whenever the table is
+                                    // rewritten, as well as the rewrite before Postgres 11.
+                                    if let (Some(max), Some(elements)) =
+                                        (max_default_elements, literal_elements(raw_expr))
+                                    {
+                                        if u64::try_from(elements).map_or(true, |n| n > max) {
+                                            let mut violation = RuleViolation::new(
+                                                RuleViolationKind::AddingFieldWithDefault,
+                                                raw_stmt,
+                                                Some(vec![
+                                                    ViolationMessage::Note(format!("The DEFAULT is a literal with {elements} elements. It's copied into every row when the table is rewritten, e.g. before Postgres 11 or by a later ALTER TABLE, which writes a lot of WAL.")),
+                                                    ViolationMessage::Help("Add the field without a default, or with a smaller one, and backfill the rows in batches.".into()),
+                                                ]),
+                                            );
+                                            violation.severity = Severity::Info;
+                                            errs.push(violation);
+                                        }
+                                    }
                                     if let Some(pg_version) = pg_version {
-                                        let def = json!({});
-                                        let raw_expr = constraint.raw_expr.as_ref().unwrap_or(&def);
-                                        if pg_version > Version::new(11, None, None)
+                                        if pg_version >= Version::new(11, None, None)
                                             && (constraint_has_constant_expr(raw_expr)
                                                 || is_non_volatile_func_call(
                                                     raw_expr,
@@ -70,7 +172,7 @@ pub fn adding_field_with_default(
                                     }
                                     errs.push(RuleViolation::new(
                                         RuleViolationKind::AddingFieldWithDefault,
-                                        raw_stmt.into(),
+                                        raw_stmt,
                                         None,
                                     ));
                                 }
@@ -91,10 +193,13 @@ mod test_rules {
     use std::str::FromStr;
 
     use crate::{
-        check_sql_with_rule,
+        check_sql, check_sql_with_rule,
+        config::{CheckSqlConfig, RuleOptions},
+        rules::test_utils::violations_to_kinds,
         versions::Version,
-        violations::{RuleViolation, RuleViolationKind},
+        violations::{RuleViolation, RuleViolationKind, Severity, ViolationMessage},
     };
+    use serde_json::json;
 
     use insta::assert_debug_snapshot;
 
@@ -149,6 +254,23 @@ ALTER TABLE "core_recipe" ADD COLUMN "foo" integer DEFAULT 10;
         assert_debug_snapshot!(lint_sql(ok_sql, pg_version_11));
     }
 
+    /// `--pg-version=11` should be treated the same as `11.0.0`.
+    #[test]
+    fn test_default_integer_major_version_only() {
+        let sql = r#"
+ALTER TABLE "core_recipe" ADD COLUMN "foo" integer DEFAULT 10;
+"#;
+
+        let pg_version_10 = Some(Version::from_str("10").unwrap());
+        assert_eq!(
+            violations_to_kinds(&lint_sql(sql, pg_version_10)),
+            vec![RuleViolationKind::AddingFieldWithDefault]
+        );
+
+        let pg_version_11 = Some(Version::from_str("11").unwrap());
+        assert_eq!(lint_sql(sql, pg_version_11), vec![]);
+    }
+
     #[test]
     fn test_default_uuid_err() {
         let bad_sql = r#"
@@ -232,6 +354,110 @@ ALTER TABLE "core_recipe" ADD COLUMN "foo" timestamptz DEFAULT now();
         let pg_version_11 = Some(Version::from_str("11.0.0").unwrap());
         assert_debug_snapshot!(lint_sql(ok_sql, pg_version_11));
     }
+    /// Volatile functions give every row a different value, so the table is
+    /// rewritten even in Postgres 11+. `now()` is STABLE, it returns the start
+    /// time of the transaction for every row, so it doesn't need a rewrite.
+    #[test]
+    fn test_volatile_default_pg_12() {
+        let pg_version_12 = Some(Version::from_str("12").unwrap());
+        let bad_sql = r#"
+ALTER TABLE "core_recipe" ADD COLUMN "foo" float DEFAULT random();
+ALTER TABLE "core_recipe" ADD COLUMN "bar" uuid DEFAULT public.uuid_generate_v4();
+ALTER TABLE "core_recipe" ADD COLUMN "baz" integer DEFAULT (random() * 10)::integer;
+"#;
+        let res = lint_sql(bad_sql, pg_version_12);
+        let severities: Vec<Severity> = res.iter().map(|v| v.severity).collect();
+        assert_eq!(
+            severities,
+            vec![Severity::Error, Severity::Error, Severity::Warning]
+        );
+
+        let ok_sql = r#"
+ALTER TABLE "core_recipe" ADD COLUMN "foo" integer DEFAULT 0;
+ALTER TABLE "core_recipe" ADD COLUMN "bar" timestamptz DEFAULT now();
+"#;
+        assert_eq!(lint_sql(ok_sql, pg_version_12), vec![]);
+    }
+
+    /// An explicit `nextval` default rewrites the table just like a serial
+    /// column.
+    #[test]
+    fn test_nextval_default() {
+        let pg_version_13 = Some(Version::from_str("13").unwrap());
+        let bad_sql = r#"
+ALTER TABLE "core_recipe" ADD COLUMN "position" bigint DEFAULT nextval('s');
+ALTER TABLE "core_recipe" ADD COLUMN "other_position" bigint NOT NULL DEFAULT nextval('core_recipe_position_seq'::regclass);
+"#;
+        let res = lint_sql(bad_sql, pg_version_13);
+        let severities: Vec<Severity> = res.iter().map(|v| v.severity).collect();
+        assert_eq!(severities, vec![Severity::Error, Severity::Error]);
+        assert_eq!(
+            res[0].messages[0],
+            ViolationMessage::Note("nextval() is VOLATILE, like the DEFAULT of a serial column, so adding a field with it as the DEFAULT requires a table rewrite with an ACCESS EXCLUSIVE lock, even in Postgres 11+.".into())
+        );
+    }
+
+    #[test]
+    fn test_large_literal_default() {
+        let sql = r#"
+ALTER TABLE "core_recipe" ADD COLUMN "tags" text[] DEFAULT ARRAY['a', 'b', 'c', 'd'];
+ALTER TABLE "core_recipe" ADD COLUMN "scores" integer[] DEFAULT '{1,2,3,4,5}'::integer[];
+ALTER TABLE "core_recipe" ADD COLUMN "pair" integer[] DEFAULT '{1,2}';
+ALTER TABLE "core_recipe" ADD COLUMN "foo" integer DEFAULT 10;
+"#;
+        let pg_version_11 = Some(Version::from_str("11").unwrap());
+        // off by default, constant defaults are fine in Postgres 11+.
+        assert_eq!(
+            violations_to_kinds(&lint_sql(sql, pg_version_11)),
+            vec![RuleViolationKind::AddingFieldWithDefault]
+        );
+
+        let lint_with_max_elements = |pg_version: &str| -> Vec<RuleViolation> {
+            let mut options = RuleOptions::new();
+            options.insert("max_default_elements".into(), json!(3));
+            let mut config = CheckSqlConfig {
+                pg_version: Some(Version::from_str(pg_version).unwrap()),
+                ..CheckSqlConfig::default()
+            };
+            config
+                .rules
+                .insert(RuleViolationKind::AddingFieldWithDefault, options);
+            check_sql(sql, &config)
+                .unwrap()
+                .into_iter()
+                .filter(|v| v.kind == RuleViolationKind::AddingFieldWithDefault)
+                .collect()
+        };
+        let severities =
+            |res: &[RuleViolation]| -> Vec<Severity> { res.iter().map(|v| v.severity).collect() };
+
+        // the note is in addition to the violations without the option.
+        let res = lint_with_max_elements("11");
+        assert_eq!(
+            severities(&res),
+            vec![Severity::Info, Severity::Warning, Severity::Info]
+        );
+        assert_eq!(
+            res[2].messages[0],
+            ViolationMessage::Note("The DEFAULT is a literal with 5 elements. It's copied into every row when the table is rewritten, e.g. before Postgres 11 or by a later ALTER TABLE, which writes a lot of WAL.".into())
+        );
+
+        // the table is rewritten before Postgres 11, which is still reported
+        // for large defaults.
+        let res = lint_with_max_elements("10");
+        assert_eq!(
+            severities(&res),
+            vec![
+                Severity::Info,
+                Severity::Warning,
+                Severity::Info,
+                Severity::Warning,
+                Severity::Warning,
+                Severity::Warning,
+            ]
+        );
+    }
+
     #[test]
     fn test_add_numbers_ok() {
         // This should be okay, but we don't handle expressions like this at the moment.
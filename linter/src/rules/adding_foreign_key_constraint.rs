@@ -1,6 +1,7 @@
 use crate::{
+    config::RuleOptions,
     versions::Version,
-    violations::{RuleViolation, RuleViolationKind},
+    violations::{RuleViolation, RuleViolationKind, ViolationMessage},
 };
 
 use squawk_parser::ast::{
@@ -13,11 +14,15 @@ use squawk_parser::ast::{
 /// Adding the constraint as NOT VALID in one transaction and then using
 /// VALIDATE in another transaction will allow writes when adding the
 /// constraint.
+///
+/// Foreign keys added inline with `ADD COLUMN ... REFERENCES` can't be
+/// `NOT VALID`, so the column and the constraint need to be added separately.
 #[must_use]
 pub fn adding_foreign_key_constraint(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
     let mut errs = vec![];
     for raw_stmt in tree {
@@ -35,7 +40,7 @@ pub fn adding_foreign_key_constraint(
                                     if constraint.contype == ConstrType::Foreign {
                                         errs.push(RuleViolation::new(
                                             RuleViolationKind::AddingForeignKeyConstraint,
-                                            raw_stmt.into(),
+                                            raw_stmt,
                                             None,
                                         ));
                                     }
@@ -50,8 +55,15 @@ pub fn adding_foreign_key_constraint(
                                         {
                                             errs.push(RuleViolation::new(
                                                 RuleViolationKind::AddingForeignKeyConstraint,
-                                                raw_stmt.into(),
-                                                None,
+                                                raw_stmt,
+                                                Some(vec![
+                                                    ViolationMessage::Note(
+                                                        "Adding a column with REFERENCES adds a foreign key that can't be NOT VALID, so the table is scanned while holding a SHARE ROW EXCLUSIVE lock on both tables.".into(),
+                                                    ),
+                                                    ViolationMessage::Help(
+                                                        "Add the column without REFERENCES, then add the foreign key with ADD CONSTRAINT ... NOT VALID and VALIDATE the constraint in a separate transaction.".into(),
+                                                    ),
+                                                ]),
                                             ));
                                         }
                                     }
@@ -71,7 +83,7 @@ pub fn adding_foreign_key_constraint(
 mod test_rules {
     use crate::{
         check_sql_with_rule,
-        violations::{RuleViolation, RuleViolationKind},
+        violations::{RuleViolation, RuleViolationKind, ViolationMessage},
     };
 
     fn lint_sql(sql: &str) -> Vec<RuleViolation> {
@@ -140,6 +152,36 @@ ALTER TABLE "emails" ADD COLUMN "user_id" INT REFERENCES "user" ("id");
 COMMIT;
         "#;
 
+        let violations = lint_sql(sql);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].kind,
+            RuleViolationKind::AddingForeignKeyConstraint
+        );
+        assert_eq!(
+            violations[0].messages,
+            vec![
+                ViolationMessage::Note("Adding a column with REFERENCES adds a foreign key that can't be NOT VALID, so the table is scanned while holding a SHARE ROW EXCLUSIVE lock on both tables.".into()),
+                ViolationMessage::Help("Add the column without REFERENCES, then add the foreign key with ADD CONSTRAINT ... NOT VALID and VALIDATE the constraint in a separate transaction.".into()),
+                ViolationMessage::Url("https://www.postgresql.org/docs/current/sql-altertable.html".into()),
+            ]
+        );
+    }
+    #[test]
+    fn test_add_column_then_foreign_key_not_valid() {
+        let sql = r#"
+ALTER TABLE "emails" ADD COLUMN "user_id" INT;
+ALTER TABLE "emails" ADD CONSTRAINT "fk_user" FOREIGN KEY ("user_id") REFERENCES "user" ("id") NOT VALID;
+        "#;
+
+        assert_eq!(lint_sql(sql), vec![]);
+    }
+    #[test]
+    fn test_add_unnamed_foreign_key_constraint() {
+        let sql = r#"
+ALTER TABLE "email" ADD FOREIGN KEY ("user_id") REFERENCES "user" ("id");
+        "#;
+
         let violations = lint_sql(sql);
         assert_eq!(violations.len(), 1);
         assert_eq!(
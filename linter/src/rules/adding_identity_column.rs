@@ -0,0 +1,107 @@
+use crate::config::RuleOptions;
+use crate::rules::utils::tables_created_in_transaction;
+use crate::versions::Version;
+use crate::violations::{RuleViolation, RuleViolationKind};
+
+use squawk_parser::ast::{
+    AlterTableCmds, AlterTableDef, AlterTableType, ColumnDefConstraint, ConstrType, RawStmt, Stmt,
+};
+
+/// `GENERATED ALWAYS AS IDENTITY` and `GENERATED BY DEFAULT AS IDENTITY`.
+fn has_identity_constraint(constraints: &[ColumnDefConstraint]) -> bool {
+    constraints
+        .iter()
+        .any(|ColumnDefConstraint::Constraint(constraint)| {
+            constraint.contype == ConstrType::Identity
+        })
+}
+
+/// Adding an identity column to an existing table fills in a value for every
+/// row. Identity columns in `CREATE TABLE`, or on tables created in the same
+/// transaction, are fine.
+#[must_use]
+pub fn adding_identity_column(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let tables_created = tables_created_in_transaction(tree, assume_in_transaction);
+    let mut errs = vec![];
+
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            Stmt::AlterTableStmt(stmt) if !tables_created.contains(&stmt.relation.relname) => {
+                for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
+                    if cmd.subtype == AlterTableType::AddColumn {
+                        if let Some(AlterTableDef::ColumnDef(column_def)) = &cmd.def {
+                            if has_identity_constraint(&column_def.constraints) {
+                                errs.push(RuleViolation::new(
+                                    RuleViolationKind::AddingIdentityColumn,
+                                    raw_stmt,
+                                    None,
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
+            _ => continue,
+        }
+    }
+
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::AddingIdentityColumn, None, false).unwrap()
+    }
+
+    #[test]
+    fn test_adding_identity_column() {
+        let bad_sql = r#"
+ALTER TABLE "core_recipe" ADD COLUMN "id" bigint GENERATED ALWAYS AS IDENTITY;
+ALTER TABLE "core_recipe" ADD COLUMN "other_id" bigint GENERATED BY DEFAULT AS IDENTITY (START WITH 100);
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::AddingIdentityColumn,
+                RuleViolationKind::AddingIdentityColumn,
+            ]
+        );
+    }
+
+    /// Making an existing column an identity column doesn't touch the rows.
+    #[test]
+    fn test_add_identity_to_existing_column() {
+        let ok_sql = r#"
+ALTER TABLE "core_recipe" ALTER COLUMN "id" ADD GENERATED BY DEFAULT AS IDENTITY;
+ALTER TABLE "core_recipe" ADD COLUMN "total" integer GENERATED ALWAYS AS ("price" * "quantity") STORED;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_new_table() {
+        let ok_sql = r#"
+CREATE TABLE "core_recipe" (
+    "id" bigint GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    "name" text
+);
+BEGIN;
+CREATE TABLE "core_bar" ("name" text);
+ALTER TABLE "core_bar" ADD COLUMN "id" bigint GENERATED ALWAYS AS IDENTITY;
+COMMIT;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
@@ -1,43 +1,21 @@
+use crate::config::RuleOptions;
 use crate::versions::Version;
-use crate::violations::{RuleViolation, RuleViolationKind};
-use crate::ViolationMessage;
+use crate::violations::RuleViolation;
 
-use squawk_parser::ast::{AlterTableCmds, AlterTableType, RawStmt, Stmt};
+use squawk_parser::ast::RawStmt;
 
+/// `SET NOT NULL` is reported by setting-not-null-on-existing-column, which
+/// knows when the column is new or already checked, and adding a `NOT NULL`
+/// column is reported by adding-required-field and adding-field-with-default.
+/// The rule is kept so configs that exclude it still work.
 #[must_use]
 pub fn adding_not_nullable_field(
-    tree: &[RawStmt],
-    pg_version: Option<Version>,
+    _tree: &[RawStmt],
+    _pg_version: Option<Version>,
     _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
-    let mut errs = vec![];
-    if let Some(pg_version) = pg_version {
-        let pg_11 = Version::new(11, Some(0), Some(0));
-        if pg_version >= pg_11 {
-            return errs;
-        }
-    }
-
-    for raw_stmt in tree {
-        match &raw_stmt.stmt {
-            Stmt::AlterTableStmt(stmt) => {
-                for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
-                    if cmd.subtype == AlterTableType::SetNotNull {
-                        errs.push(RuleViolation::new(
-                            RuleViolationKind::AddingNotNullableField,
-                            raw_stmt.into(),
-                            Some(vec![
-                                ViolationMessage::Note("Setting a column NOT NULL blocks reads while the table is scanned.".into()),
-                                ViolationMessage::Help("Use a check constraint instead.".into())
-                            ]),
-                        ));
-                    }
-                }
-            }
-            _ => continue,
-        }
-    }
-    errs
+    vec![]
 }
 
 #[cfg(test)]
@@ -45,7 +23,9 @@ mod test_rules {
     use std::str::FromStr;
 
     use crate::{
-        check_sql_with_rule,
+        check_sql, check_sql_with_rule,
+        config::CheckSqlConfig,
+        rules::test_utils::violations_to_kinds,
         versions::Version,
         violations::{RuleViolation, RuleViolationKind},
     };
@@ -62,6 +42,7 @@ mod test_rules {
 
     use insta::assert_debug_snapshot;
 
+    /// Reported by setting-not-null-on-existing-column instead.
     #[test]
     fn test_set_not_null() {
         let sql = r#"
@@ -104,4 +85,37 @@ COMMIT;
         "#;
         assert_debug_snapshot!(lint_sql(ok_sql, Some(Version::from_str("11.0.0").unwrap()),));
     }
+
+    /// `NOT NULL` with a `DEFAULT` is only reported by adding-field-with-default,
+    /// which knows when the default avoids a table rewrite.
+    #[test]
+    fn test_adding_field_that_is_not_nullable_with_default_across_versions() {
+        let sql = r#"
+ALTER TABLE "core_recipe" ADD COLUMN "foo" integer NOT NULL DEFAULT 10;
+        "#;
+        let lint_not_null_rules = |pg_version: &str| -> Vec<RuleViolationKind> {
+            let config = CheckSqlConfig {
+                pg_version: Some(Version::from_str(pg_version).unwrap()),
+                ..CheckSqlConfig::default()
+            };
+            let res: Vec<RuleViolation> = check_sql(sql, &config)
+                .unwrap()
+                .into_iter()
+                .filter(|v| {
+                    matches!(
+                        v.kind,
+                        RuleViolationKind::AddingNotNullableField
+                            | RuleViolationKind::AddingFieldWithDefault
+                            | RuleViolationKind::AddingRequiredField
+                    )
+                })
+                .collect();
+            violations_to_kinds(&res)
+        };
+        assert_eq!(
+            lint_not_null_rules("10.0.0"),
+            vec![RuleViolationKind::AddingFieldWithDefault]
+        );
+        assert_eq!(lint_not_null_rules("12.0.0"), vec![]);
+    }
 }
@@ -1,17 +1,33 @@
 use crate::{
+    config::RuleOptions,
     versions::Version,
-    violations::{RuleViolation, RuleViolationKind},
+    violations::{RuleViolation, RuleViolationKind, ViolationMessage},
 };
 
 use squawk_parser::ast::{
     AlterTableCmds, AlterTableDef, AlterTableType, ColumnDefConstraint, ConstrType, RawStmt, Stmt,
 };
 
+/// An inline `PRIMARY KEY` on `ADD COLUMN` builds the unique index and makes
+/// the column `NOT NULL`, which neither the unique constraint nor the not null
+/// rules catch.
+fn inline_primary_key_messages() -> Vec<ViolationMessage> {
+    vec![
+        ViolationMessage::Note(
+            "Adding a column with a PRIMARY KEY builds a unique index and makes the column NOT NULL while blocking reads and writes.".into(),
+        ),
+        ViolationMessage::Help(
+            "Add the column without the PRIMARY KEY, create a unique index CONCURRENTLY, then add the PRIMARY KEY constraint USING the index.".into(),
+        ),
+    ]
+}
+
 #[must_use]
 pub fn adding_primary_key_constraint(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
     let mut errs = vec![];
     for raw_stmt in tree {
@@ -28,7 +44,7 @@ pub fn adding_primary_key_constraint(
                             {
                                 errs.push(RuleViolation::new(
                                     RuleViolationKind::AddingSerialPrimaryKeyField,
-                                    raw_stmt.into(),
+                                    raw_stmt,
                                     None,
                                 ));
                             }
@@ -40,8 +56,8 @@ pub fn adding_primary_key_constraint(
                                 {
                                     errs.push(RuleViolation::new(
                                         RuleViolationKind::AddingSerialPrimaryKeyField,
-                                        raw_stmt.into(),
-                                        None,
+                                        raw_stmt,
+                                        Some(inline_primary_key_messages()),
                                     ));
                                 }
                             }
@@ -60,6 +76,7 @@ pub fn adding_primary_key_constraint(
 mod test_rules {
     use crate::{
         check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
         violations::{RuleViolation, RuleViolationKind},
     };
     use insta::assert_debug_snapshot;
@@ -100,4 +117,24 @@ ALTER TABLE items ADD CONSTRAINT items_pk PRIMARY KEY USING INDEX items_pk;
 "#;
         assert_debug_snapshot!(lint_sql(ok_sql,));
     }
+
+    #[test]
+    fn test_add_column_inline_primary_key() {
+        let bad_sql = r#"
+ALTER TABLE "core_foo" ADD COLUMN "id" uuid PRIMARY KEY;
+"#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![RuleViolationKind::AddingSerialPrimaryKeyField]
+        );
+    }
+
+    #[test]
+    fn test_create_table_primary_key() {
+        let ok_sql = r#"
+CREATE TABLE "core_foo" ("id" uuid PRIMARY KEY, "name" text);
+CREATE TABLE "core_bar" ("id" uuid, PRIMARY KEY ("id"));
+"#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
 }
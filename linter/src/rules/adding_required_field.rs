@@ -1,3 +1,4 @@
+use crate::config::RuleOptions;
 use crate::versions::Version;
 use crate::violations::{RuleViolation, RuleViolationKind};
 
@@ -24,6 +25,7 @@ pub fn adding_required_field(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
     let mut errs = vec![];
 
@@ -36,7 +38,7 @@ pub fn adding_required_field(
                             if has_not_null_and_no_default_constraint(&column_def.constraints) {
                                 errs.push(RuleViolation::new(
                                     RuleViolationKind::AddingRequiredField,
-                                    raw_stmt.into(),
+                                    raw_stmt,
                                     None,
                                 ));
                             }
@@ -0,0 +1,140 @@
+use crate::config::RuleOptions;
+use crate::rules::utils::tables_created_in_transaction;
+use crate::versions::Version;
+use crate::violations::{RuleViolation, RuleViolationKind};
+
+use squawk_parser::ast::{
+    AlterTableCmds, AlterTableDef, AlterTableType, ColumnDefConstraint, ConstrType, RawStmt, Stmt,
+};
+
+use super::utils::is_serial_column;
+
+fn has_default_constraint(constraints: &[ColumnDefConstraint]) -> bool {
+    constraints
+        .iter()
+        .any(|ColumnDefConstraint::Constraint(constraint)| {
+            constraint.contype == ConstrType::Default
+        })
+}
+
+#[must_use]
+pub fn adding_serial_column(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let tables_created = tables_created_in_transaction(tree, assume_in_transaction);
+    let mut errs = vec![];
+
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            Stmt::AlterTableStmt(stmt) if !tables_created.contains(&stmt.relation.relname) => {
+                for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
+                    if cmd.subtype == AlterTableType::AddColumn {
+                        if let Some(AlterTableDef::ColumnDef(column_def)) = &cmd.def {
+                            // an explicit DEFAULT is reported by adding-field-with-default.
+                            if is_serial_column(column_def)
+                                && !has_default_constraint(&column_def.constraints)
+                            {
+                                errs.push(RuleViolation::new(
+                                    RuleViolationKind::AddingSerialColumn,
+                                    raw_stmt,
+                                    None,
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
+            _ => continue,
+        }
+    }
+
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::AddingSerialColumn, None, false).unwrap()
+    }
+
+    #[test]
+    fn test_adding_serial_column() {
+        let bad_sql = r#"
+ALTER TABLE "core_foo" ADD COLUMN "id" serial;
+ALTER TABLE "core_foo" ADD COLUMN "id" bigserial;
+ALTER TABLE "core_foo" ADD COLUMN "id" smallserial NOT NULL;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::AddingSerialColumn,
+                RuleViolationKind::AddingSerialColumn,
+                RuleViolationKind::AddingSerialColumn,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_adding_integer_column() {
+        let ok_sql = r#"
+ALTER TABLE "core_foo" ADD COLUMN "id" bigint;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_create_table_with_serial() {
+        let ok_sql = r#"
+CREATE TABLE "core_foo" ("id" bigserial PRIMARY KEY);
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_new_table() {
+        let ok_sql = r#"
+BEGIN;
+CREATE TABLE "core_bar" ("name" text);
+ALTER TABLE "core_bar" ADD COLUMN "id" bigserial;
+COMMIT;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    /// Once the transaction that created the table is committed, it can have
+    /// rows to rewrite.
+    #[test]
+    fn test_table_created_in_earlier_transaction() {
+        let bad_sql = r#"
+CREATE TABLE "core_bar" ("name" text);
+ALTER TABLE "core_bar" ADD COLUMN "id" bigserial;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![RuleViolationKind::AddingSerialColumn]
+        );
+    }
+
+    /// Only one of adding-serial-column or adding-field-with-default should
+    /// report a column.
+    #[test]
+    fn test_no_overlap_with_adding_field_with_default() {
+        let sql = r#"
+ALTER TABLE "core_foo" ADD COLUMN "id" bigserial;
+  "#;
+        assert_eq!(
+            check_sql_with_rule(sql, &RuleViolationKind::AddingFieldWithDefault, None, false)
+                .unwrap(),
+            vec![]
+        );
+    }
+}
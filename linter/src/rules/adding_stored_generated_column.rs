@@ -0,0 +1,113 @@
+use crate::config::RuleOptions;
+use crate::rules::utils::tables_created_in_transaction;
+use crate::versions::Version;
+use crate::violations::{RuleViolation, RuleViolationKind};
+
+use squawk_parser::ast::{
+    AlterTableCmds, AlterTableDef, AlterTableType, ColumnDefConstraint, ConstrType, RawStmt, Stmt,
+};
+
+/// `GENERATED ALWAYS AS (...) STORED` columns. Identity columns are
+/// `CONSTR_IDENTITY` instead.
+fn has_generated_constraint(constraints: &[ColumnDefConstraint]) -> bool {
+    constraints
+        .iter()
+        .any(|ColumnDefConstraint::Constraint(constraint)| {
+            constraint.contype == ConstrType::Generated
+        })
+}
+
+#[must_use]
+pub fn adding_stored_generated_column(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let tables_created = tables_created_in_transaction(tree, assume_in_transaction);
+    let mut errs = vec![];
+
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            Stmt::AlterTableStmt(stmt) if !tables_created.contains(&stmt.relation.relname) => {
+                for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
+                    if cmd.subtype == AlterTableType::AddColumn {
+                        if let Some(AlterTableDef::ColumnDef(column_def)) = &cmd.def {
+                            if has_generated_constraint(&column_def.constraints) {
+                                errs.push(RuleViolation::new(
+                                    RuleViolationKind::AddingStoredGeneratedColumn,
+                                    raw_stmt,
+                                    None,
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
+            _ => continue,
+        }
+    }
+
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(
+            sql,
+            &RuleViolationKind::AddingStoredGeneratedColumn,
+            None,
+            false,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_adding_stored_generated_column() {
+        let bad_sql = r#"
+ALTER TABLE "core_recipe" ADD COLUMN "total" integer GENERATED ALWAYS AS ("price" * "quantity") STORED;
+ALTER TABLE "core_recipe" ADD COLUMN "name_lower" text NOT NULL GENERATED ALWAYS AS (lower("name")) STORED;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::AddingStoredGeneratedColumn,
+                RuleViolationKind::AddingStoredGeneratedColumn,
+            ]
+        );
+    }
+
+    /// Identity columns use the `GENERATED` keyword too, but aren't computed
+    /// from an expression.
+    #[test]
+    fn test_identity_column() {
+        let ok_sql = r#"
+ALTER TABLE "core_recipe" ADD COLUMN "seq" bigint GENERATED ALWAYS AS IDENTITY;
+ALTER TABLE "core_recipe" ADD COLUMN "other_seq" bigint GENERATED BY DEFAULT AS IDENTITY;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_new_table() {
+        let ok_sql = r#"
+CREATE TABLE "core_recipe" (
+    "price" integer,
+    "quantity" integer,
+    "total" integer GENERATED ALWAYS AS ("price" * "quantity") STORED
+);
+BEGIN;
+CREATE TABLE "core_bar" ("price" integer, "quantity" integer);
+ALTER TABLE "core_bar" ADD COLUMN "total" integer GENERATED ALWAYS AS ("price" * "quantity") STORED;
+COMMIT;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
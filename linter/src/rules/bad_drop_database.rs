@@ -1,4 +1,5 @@
 use crate::{
+    config::RuleOptions,
     versions::Version,
     violations::{RuleViolation, RuleViolationKind},
 };
@@ -11,6 +12,7 @@ pub fn ban_drop_database(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
     let mut errs = vec![];
     for raw_stmt in tree {
@@ -18,7 +20,7 @@ pub fn ban_drop_database(
             Stmt::DropdbStmt(_) => {
                 errs.push(RuleViolation::new(
                     RuleViolationKind::BanDropDatabase,
-                    raw_stmt.into(),
+                    raw_stmt,
                     None,
                 ));
             }
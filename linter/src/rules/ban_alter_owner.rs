@@ -0,0 +1,108 @@
+use crate::{
+    config::RuleOptions,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind},
+};
+
+use squawk_parser::ast::{AlterTableCmds, AlterTableType, RawStmt, Stmt};
+
+/// `OWNER TO` on tables, sequences, views, and other objects, and
+/// `REASSIGN OWNED`.
+#[must_use]
+pub fn ban_alter_owner(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        let changes_owner = match &raw_stmt.stmt {
+            // tables, sequences, views, materialized views, and foreign
+            // tables use ALTER TABLE commands.
+            Stmt::AlterTableStmt(stmt) => {
+                stmt.cmds.iter().any(|AlterTableCmds::AlterTableCmd(cmd)| {
+                    cmd.subtype == AlterTableType::ChangeOwner
+                })
+            }
+            Stmt::AlterOwnerStmt(_) | Stmt::ReassignOwnedStmt(_) => true,
+            _ => false,
+        };
+        if changes_owner {
+            errs.push(RuleViolation::new(
+                RuleViolationKind::BanAlterOwner,
+                raw_stmt,
+                None,
+            ));
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql, check_sql_with_rule,
+        config::{CheckSqlConfig, RuleOptions},
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::BanAlterOwner, None, false).unwrap()
+    }
+
+    #[test]
+    fn test_owner_to() {
+        let bad_sql = r#"
+ALTER TABLE "core_recipe" OWNER TO "app";
+ALTER SEQUENCE "core_recipe_id_seq" OWNER TO "app";
+ALTER VIEW "core_recipe_view" OWNER TO CURRENT_USER;
+ALTER FUNCTION "core_slugify"(text) OWNER TO "app";
+ALTER SCHEMA "core" OWNER TO "app";
+REASSIGN OWNED BY "deploy" TO "app";
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::BanAlterOwner,
+                RuleViolationKind::BanAlterOwner,
+                RuleViolationKind::BanAlterOwner,
+                RuleViolationKind::BanAlterOwner,
+                RuleViolationKind::BanAlterOwner,
+                RuleViolationKind::BanAlterOwner,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_schema_changes() {
+        let ok_sql = r#"
+ALTER TABLE "core_recipe" ADD COLUMN "name" text;
+ALTER SEQUENCE "core_recipe_id_seq" RESTART WITH 100;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_opt_in() {
+        let sql = r#"
+ALTER TABLE "core_recipe" OWNER TO "app";
+  "#;
+        let kinds = |config: &CheckSqlConfig| -> Vec<RuleViolationKind> {
+            let res: Vec<RuleViolation> = check_sql(sql, config)
+                .unwrap()
+                .into_iter()
+                .filter(|v| v.kind == RuleViolationKind::BanAlterOwner)
+                .collect();
+            violations_to_kinds(&res)
+        };
+        let mut config = CheckSqlConfig::default();
+        assert_eq!(kinds(&config), vec![]);
+
+        config
+            .rules
+            .insert(RuleViolationKind::BanAlterOwner, RuleOptions::new());
+        assert_eq!(kinds(&config), vec![RuleViolationKind::BanAlterOwner]);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{
+    config::RuleOptions,
     versions::Version,
     violations::{RuleViolation, RuleViolationKind},
 };
@@ -10,6 +11,7 @@ pub fn ban_char_type(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
     let mut errs = vec![];
     for raw_stmt in tree {
@@ -22,7 +24,7 @@ pub fn ban_char_type(
                             if field_type_name.string.sval == "bpchar" {
                                 errs.push(RuleViolation::new(
                                     RuleViolationKind::BanCharField,
-                                    raw_stmt.into(),
+                                    raw_stmt,
                                     None,
                                 ));
                             }
@@ -0,0 +1,70 @@
+use crate::{
+    config::RuleOptions,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind},
+};
+
+use squawk_parser::ast::{RawStmt, Stmt};
+
+/// `CLUSTER` rewrites the table under an `ACCESS EXCLUSIVE` lock.
+#[must_use]
+pub fn ban_cluster(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            Stmt::ClusterStmt(_) => {
+                errs.push(RuleViolation::new(
+                    RuleViolationKind::BanCluster,
+                    raw_stmt,
+                    None,
+                ));
+            }
+            _ => continue,
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::BanCluster, None, false).unwrap()
+    }
+
+    #[test]
+    fn test_ban_cluster() {
+        let bad_sql = r#"
+CLUSTER "core_recipe" USING "core_recipe_name_idx";
+CLUSTER "core_recipe";
+CLUSTER;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::BanCluster,
+                RuleViolationKind::BanCluster,
+                RuleViolationKind::BanCluster,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_other_statements() {
+        let ok_sql = r#"
+ALTER TABLE "core_recipe" CLUSTER ON "core_recipe_name_idx";
+CREATE INDEX CONCURRENTLY "core_recipe_name_idx" ON "core_recipe" ("name");
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
@@ -1,40 +1,42 @@
+use crate::config::RuleOptions;
+use crate::rules::utils::{is_concurrent_reindex, TransactionTracker};
 use crate::versions::Version;
 use crate::violations::{RuleViolation, RuleViolationKind};
 
-use squawk_parser::ast::{RawStmt, Stmt, TransactionStmtKind};
+use squawk_parser::ast::{ObjectType, RawStmt, Stmt};
+
+/// `CREATE INDEX`, `DROP INDEX`, and `REINDEX` using `CONCURRENTLY`, which
+/// can't run in a transaction.
+fn is_concurrent_index_operation(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::IndexStmt(stmt) => stmt.concurrent,
+        Stmt::DropStmt(stmt) => stmt.concurrent && stmt.remove_type == ObjectType::Index,
+        Stmt::ReindexStmt(stmt) => is_concurrent_reindex(stmt),
+        _ => false,
+    }
+}
 
 #[must_use]
 pub fn ban_concurrent_index_creation_in_transaction(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
-    let mut in_transaction = assume_in_transaction;
+    let mut transaction = TransactionTracker::new(assume_in_transaction);
     let mut errs = vec![];
     for raw_stmt in tree {
-        match &raw_stmt.stmt {
-            Stmt::TransactionStmt(stmt) => {
-                if stmt.kind == TransactionStmtKind::Begin && !in_transaction {
-                    in_transaction = true;
-                }
-                if stmt.kind == TransactionStmtKind::Commit {
-                    in_transaction = false;
-                }
+        transaction.update(&raw_stmt.stmt);
+        if transaction.inside_transaction() && is_concurrent_index_operation(&raw_stmt.stmt) {
+            if assume_in_transaction && tree.len() == 1 {
+                // Migration tools should not require the transaction here so this is usually safe
+                continue;
             }
-            Stmt::IndexStmt(stmt) => {
-                if stmt.concurrent && in_transaction {
-                    if assume_in_transaction && tree.len() == 1 {
-                        // Migration tools should not require the transaction here so this is usually safe
-                        continue;
-                    }
-                    errs.push(RuleViolation::new(
-                        RuleViolationKind::BanConcurrentIndexCreationInTransaction,
-                        raw_stmt.into(),
-                        None,
-                    ));
-                }
-            }
-            _ => continue,
+            errs.push(RuleViolation::new(
+                RuleViolationKind::BanConcurrentIndexCreationInTransaction,
+                raw_stmt,
+                None,
+            ));
         }
     }
     errs
@@ -46,6 +48,7 @@ mod test_rules {
 
     use crate::{
         check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
         violations::{RuleViolation, RuleViolationKind},
     };
 
@@ -116,4 +119,47 @@ mod test_rules {
 
         assert_debug_snapshot!(lint_sql_assuming_in_transaction(ok_sql));
     }
+
+    #[test]
+    fn test_other_concurrent_index_operations_in_transaction() {
+        let bad_sql = r#"
+  BEGIN;
+  DROP INDEX CONCURRENTLY "field_name_idx";
+  REINDEX INDEX CONCURRENTLY "other_field_idx";
+  COMMIT;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::BanConcurrentIndexCreationInTransaction,
+                RuleViolationKind::BanConcurrentIndexCreationInTransaction,
+            ]
+        );
+
+        let ok_sql = r#"
+  BEGIN;
+  DROP INDEX "field_name_idx";
+  ROLLBACK;
+  DROP INDEX CONCURRENTLY "field_name_idx";
+  REINDEX INDEX CONCURRENTLY "other_field_idx";
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_other_concurrent_index_operations_with_assume_in_transaction() {
+        let bad_sql = r#"
+  DROP INDEX CONCURRENTLY "field_name_idx";
+  ALTER TABLE "table_name" DROP COLUMN "field_name";
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql_assuming_in_transaction(bad_sql)),
+            vec![RuleViolationKind::BanConcurrentIndexCreationInTransaction]
+        );
+
+        let ok_sql = r#"
+  DROP INDEX CONCURRENTLY "field_name_idx";
+  "#;
+        assert_eq!(lint_sql_assuming_in_transaction(ok_sql), vec![]);
+    }
 }
@@ -0,0 +1,96 @@
+use crate::{
+    config::RuleOptions,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind},
+};
+
+use serde_json::Value;
+use squawk_parser::ast::{RawStmt, Stmt};
+
+/// Temporary tables are dropped at the end of the session, so they're fine
+/// for scratch data.
+fn is_temporary(into: &Value) -> bool {
+    into["rel"]["relpersistence"].as_str() == Some("t")
+}
+
+#[must_use]
+pub fn ban_create_table_as(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        let creates_table = match &raw_stmt.stmt {
+            // materialized views are also a `CreateTableAsStmt`.
+            Stmt::CreateTableAsStmt(stmt) => {
+                stmt["objtype"].as_str() == Some("OBJECT_TABLE") && !is_temporary(&stmt["into"])
+            }
+            Stmt::SelectStmt(stmt) => stmt
+                .into_clause
+                .as_ref()
+                .map_or(false, |into| !is_temporary(into)),
+            _ => false,
+        };
+        if creates_table {
+            errs.push(RuleViolation::new(
+                RuleViolationKind::BanCreateTableAs,
+                raw_stmt,
+                None,
+            ));
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::BanCreateTableAs, None, false).unwrap()
+    }
+
+    #[test]
+    fn test_create_table_as() {
+        let bad_sql = r#"
+CREATE TABLE "core_recipe_archive" AS SELECT * FROM "core_recipe" WHERE "archived";
+CREATE TABLE IF NOT EXISTS "core_recipe_copy" AS TABLE "core_recipe" WITH NO DATA;
+SELECT * INTO "core_recipe_backup" FROM "core_recipe";
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::BanCreateTableAs,
+                RuleViolationKind::BanCreateTableAs,
+                RuleViolationKind::BanCreateTableAs,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_create_table() {
+        let ok_sql = r#"
+CREATE TABLE "core_recipe_archive" ("id" bigint PRIMARY KEY, "name" text NOT NULL);
+CREATE TABLE "core_recipe_copy" (LIKE "core_recipe" INCLUDING ALL);
+INSERT INTO "core_recipe_archive" SELECT "id", "name" FROM "core_recipe";
+SELECT * FROM "core_recipe";
+CREATE MATERIALIZED VIEW "core_recipe_stats" AS SELECT count(*) FROM "core_recipe";
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_temporary_table() {
+        let ok_sql = r#"
+CREATE TEMP TABLE "recipe_ids" AS SELECT "id" FROM "core_recipe";
+SELECT "id" INTO TEMP "other_ids" FROM "core_recipe";
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
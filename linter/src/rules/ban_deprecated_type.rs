@@ -0,0 +1,108 @@
+use crate::{
+    config::RuleOptions,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+};
+
+use squawk_parser::ast::{ColumnDef, RawStmt};
+
+use crate::rules::utils::{builtin_type_name, columns_create_or_modified};
+
+/// Deprecated types and what to use instead. `abstime`, `reltime`, and
+/// `tinterval` were removed in Postgres 12, and `oid` wraps around and isn't
+/// unique across tables.
+const DEPRECATED_TYPES: &[(&str, &str)] = &[
+    ("abstime", "timestamptz"),
+    ("reltime", "interval"),
+    ("tinterval", "tstzrange"),
+    ("oid", "bigint, or an identity column for generated ids"),
+];
+
+/// Types that are deprecated or only meant for the system catalogs.
+#[must_use]
+pub fn ban_deprecated_type(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        for column in columns_create_or_modified(&raw_stmt.stmt) {
+            check_column_def(&mut errs, raw_stmt, column);
+        }
+    }
+    errs
+}
+
+fn check_column_def(errs: &mut Vec<RuleViolation>, raw_stmt: &RawStmt, column_def: &ColumnDef) {
+    let Some(type_name) = builtin_type_name(column_def) else {
+        return;
+    };
+    if let Some((_, replacement)) = DEPRECATED_TYPES.iter().find(|(name, _)| *name == type_name) {
+        errs.push(RuleViolation::new(
+            RuleViolationKind::BanDeprecatedType,
+            raw_stmt,
+            Some(vec![
+                ViolationMessage::Note(format!("The {type_name} type is deprecated.")),
+                ViolationMessage::Help(format!("Use {replacement} instead.")),
+            ]),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::check_sql_with_rule;
+    use crate::rules::test_utils::violations_to_kinds;
+    use crate::violations::{RuleViolation, RuleViolationKind, ViolationMessage};
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::BanDeprecatedType, None, false).unwrap()
+    }
+
+    #[test]
+    fn test_deprecated_types() {
+        let bad_sql = r#"
+CREATE TABLE "core_event" (
+    "id" oid,
+    "created" abstime,
+    "duration" reltime
+);
+ALTER TABLE "core_event" ADD COLUMN "period" tinterval;
+ALTER TABLE "core_event" ALTER COLUMN "id" TYPE pg_catalog.oid;
+  "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![
+                RuleViolationKind::BanDeprecatedType,
+                RuleViolationKind::BanDeprecatedType,
+                RuleViolationKind::BanDeprecatedType,
+                RuleViolationKind::BanDeprecatedType,
+                RuleViolationKind::BanDeprecatedType,
+            ]
+        );
+        assert_eq!(
+            res[1].messages,
+            vec![
+                ViolationMessage::Note("The abstime type is deprecated.".into()),
+                ViolationMessage::Help("Use timestamptz instead.".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_modern_types() {
+        let ok_sql = r#"
+CREATE TABLE "core_event" (
+    "id" bigint GENERATED ALWAYS AS IDENTITY,
+    "created" timestamptz,
+    "duration" interval,
+    "period" tstzrange,
+    "legacy" "app"."oid"
+);
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{
+    config::RuleOptions,
+    rules::utils::is_zero_timeout,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind},
+};
+
+use squawk_parser::ast::{RawStmt, Stmt};
+
+/// `statement_timeout` cancels runaway statements, including DDL stuck
+/// waiting for a lock, so turning it off lets them block other queries
+/// indefinitely.
+#[must_use]
+pub fn ban_disabling_statement_timeout(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            Stmt::VariableSetStmt(stmt)
+                if stmt["name"].as_str() == Some("statement_timeout")
+                    && stmt["kind"].as_str() == Some("VAR_SET_VALUE")
+                    && stmt["args"].get(0).map_or(false, is_zero_timeout) =>
+            {
+                errs.push(RuleViolation::new(
+                    RuleViolationKind::BanDisablingStatementTimeout,
+                    raw_stmt,
+                    None,
+                ));
+            }
+            _ => continue,
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(
+            sql,
+            &RuleViolationKind::BanDisablingStatementTimeout,
+            None,
+            false,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_equals_zero() {
+        let bad_sql = r#"
+SET statement_timeout = 0;
+SET LOCAL statement_timeout = '0s';
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::BanDisablingStatementTimeout,
+                RuleViolationKind::BanDisablingStatementTimeout,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_zero() {
+        let bad_sql = r#"
+SET statement_timeout TO 0;
+SET statement_timeout TO '0';
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::BanDisablingStatementTimeout,
+                RuleViolationKind::BanDisablingStatementTimeout,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_non_zero() {
+        let ok_sql = r#"
+SET statement_timeout = '5min';
+SET statement_timeout TO 30000;
+SET statement_timeout TO DEFAULT;
+RESET statement_timeout;
+SET lock_timeout = 0;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{
+    config::RuleOptions,
     versions::Version,
     violations::{RuleViolation, RuleViolationKind},
 };
@@ -10,6 +11,7 @@ pub fn ban_drop_column(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
     let mut errs = vec![];
     for raw_stmt in tree {
@@ -20,7 +22,7 @@ pub fn ban_drop_column(
                     if cmd.subtype == AlterTableType::DropColumn {
                         errs.push(RuleViolation::new(
                             RuleViolationKind::BanDropColumn,
-                            raw_stmt.into(),
+                            raw_stmt,
                             None,
                         ));
                     }
@@ -36,6 +38,7 @@ pub fn ban_drop_column(
 mod test_rules {
     use crate::{
         check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
         violations::{RuleViolation, RuleViolationKind},
     };
     use insta::assert_debug_snapshot;
@@ -52,4 +55,42 @@ ALTER TABLE "bar_tbl" DROP COLUMN "foo_col" CASCADE;
 
         assert_debug_snapshot!(lint_sql(sql));
     }
+
+    #[test]
+    fn test_drop_column_if_exists() {
+        let sql = r#"
+ALTER TABLE "bar_tbl" DROP COLUMN IF EXISTS "foo_col";
+        "#;
+
+        assert_eq!(
+            violations_to_kinds(&lint_sql(sql)),
+            vec![RuleViolationKind::BanDropColumn]
+        );
+    }
+
+    /// We report each dropped column separately so excluding or fixing one
+    /// doesn't hide the others.
+    #[test]
+    fn test_drop_multiple_columns() {
+        let sql = r#"
+ALTER TABLE "bar_tbl" DROP COLUMN "foo_col", DROP COLUMN IF EXISTS "baz_col", ADD COLUMN "qux_col" text;
+        "#;
+
+        assert_eq!(
+            violations_to_kinds(&lint_sql(sql)),
+            vec![
+                RuleViolationKind::BanDropColumn,
+                RuleViolationKind::BanDropColumn
+            ]
+        );
+    }
+
+    #[test]
+    fn test_adding_column_ok() {
+        let sql = r#"
+ALTER TABLE "bar_tbl" ADD COLUMN "foo_col" text;
+        "#;
+
+        assert_eq!(lint_sql(sql), vec![]);
+    }
 }
@@ -1,4 +1,7 @@
-use crate::{versions::Version, violations::RuleViolation, violations::RuleViolationKind};
+use crate::{
+    config::RuleOptions, versions::Version, violations::RuleViolation,
+    violations::RuleViolationKind,
+};
 
 use squawk_parser::ast::{AlterTableCmds, AlterTableType, RawStmt, Stmt};
 
@@ -7,6 +10,7 @@ pub fn ban_drop_not_null(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
     let mut errs = vec![];
     for raw_stmt in tree {
@@ -17,7 +21,7 @@ pub fn ban_drop_not_null(
                     if cmd.subtype == AlterTableType::DropNotNull {
                         errs.push(RuleViolation::new(
                             RuleViolationKind::BanDropNotNull,
-                            raw_stmt.into(),
+                            raw_stmt,
                             None,
                         ));
                     }
@@ -33,7 +37,7 @@ pub fn ban_drop_not_null(
 mod test_rules {
     use crate::{
         check_sql_with_rule,
-        violations::{RuleViolation, RuleViolationKind},
+        violations::{RuleViolation, RuleViolationKind, Span},
     };
     use insta::assert_debug_snapshot;
 
@@ -48,4 +52,44 @@ ALTER TABLE "bar_tbl" ALTER COLUMN "foo_col" DROP NOT NULL;
   "#;
         assert_debug_snapshot!(lint_sql(bad_sql));
     }
+
+    /// Each `DROP NOT NULL` clause is reported with the span of its statement.
+    #[test]
+    fn test_ban_drop_not_null_per_clause() {
+        let bad_sql = r#"
+ALTER TABLE "bar_tbl" ALTER COLUMN "foo_col" DROP NOT NULL, ALTER COLUMN "bar_col" DROP NOT NULL;
+ALTER TABLE "bar_tbl" ALTER COLUMN "baz_col" SET NOT NULL;
+ALTER TABLE "bar_tbl" ALTER COLUMN "baz_col" DROP NOT NULL;
+  "#;
+        let spans: Vec<(RuleViolationKind, Span)> = lint_sql(bad_sql)
+            .into_iter()
+            .map(|v| (v.kind, v.span))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (
+                    RuleViolationKind::BanDropNotNull,
+                    Span {
+                        start: 0,
+                        len: Some(97)
+                    }
+                ),
+                (
+                    RuleViolationKind::BanDropNotNull,
+                    Span {
+                        start: 0,
+                        len: Some(97)
+                    }
+                ),
+                (
+                    RuleViolationKind::BanDropNotNull,
+                    Span {
+                        start: 157,
+                        len: Some(59)
+                    }
+                ),
+            ]
+        );
+    }
 }
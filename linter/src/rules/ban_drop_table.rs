@@ -1,4 +1,5 @@
 use crate::{
+    config::RuleOptions,
     versions::Version,
     violations::{RuleViolation, RuleViolationKind},
 };
@@ -10,6 +11,7 @@ pub fn ban_drop_table(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
     let mut errs = vec![];
     for raw_stmt in tree {
@@ -17,7 +19,7 @@ pub fn ban_drop_table(
             Stmt::DropStmt(stmt) if stmt.remove_type == ObjectType::Table => {
                 errs.push(RuleViolation::new(
                     RuleViolationKind::BanDropTable,
-                    raw_stmt.into(),
+                    raw_stmt,
                     None,
                 ));
             }
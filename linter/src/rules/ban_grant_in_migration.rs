@@ -0,0 +1,95 @@
+use crate::{
+    config::RuleOptions,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind},
+};
+
+use squawk_parser::ast::{RawStmt, Stmt};
+
+/// `GRANT` and `REVOKE` of privileges on objects, and of role memberships.
+#[must_use]
+pub fn ban_grant_in_migration(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            Stmt::GrantStmt(_) | Stmt::GrantRoleStmt(_) => {
+                errs.push(RuleViolation::new(
+                    RuleViolationKind::BanGrantInMigration,
+                    raw_stmt,
+                    None,
+                ));
+            }
+            _ => continue,
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql, check_sql_with_rule,
+        config::{CheckSqlConfig, RuleOptions},
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::BanGrantInMigration, None, false).unwrap()
+    }
+
+    #[test]
+    fn test_grant_and_revoke() {
+        let bad_sql = r#"
+GRANT SELECT ON "core_recipe" TO "reporting";
+REVOKE ALL ON ALL TABLES IN SCHEMA "public" FROM PUBLIC;
+GRANT "reporting" TO "analyst";
+REVOKE "reporting" FROM "analyst";
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::BanGrantInMigration,
+                RuleViolationKind::BanGrantInMigration,
+                RuleViolationKind::BanGrantInMigration,
+                RuleViolationKind::BanGrantInMigration,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_schema_changes() {
+        let ok_sql = r#"
+CREATE TABLE "core_recipe" ("id" bigint PRIMARY KEY);
+ALTER TABLE "core_recipe" ADD COLUMN "name" text;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_opt_in() {
+        let sql = r#"
+GRANT SELECT ON "core_recipe" TO "reporting";
+  "#;
+        let kinds = |config: &CheckSqlConfig| -> Vec<RuleViolationKind> {
+            let res: Vec<RuleViolation> = check_sql(sql, config)
+                .unwrap()
+                .into_iter()
+                .filter(|v| v.kind == RuleViolationKind::BanGrantInMigration)
+                .collect();
+            violations_to_kinds(&res)
+        };
+        let mut config = CheckSqlConfig::default();
+        assert_eq!(kinds(&config), vec![]);
+
+        config
+            .rules
+            .insert(RuleViolationKind::BanGrantInMigration, RuleOptions::new());
+        assert_eq!(kinds(&config), vec![RuleViolationKind::BanGrantInMigration]);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{
+    config::RuleOptions,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind},
+};
+
+use squawk_parser::ast::{ColumnDef, RawStmt};
+
+use crate::rules::utils::{builtin_type_name, columns_create_or_modified};
+
+/// `money` formats values using the `lc_monetary` locale setting, so changing
+/// the setting changes how existing data is read and written.
+#[must_use]
+pub fn ban_money_type(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        for column in columns_create_or_modified(&raw_stmt.stmt) {
+            check_column_def(&mut errs, raw_stmt, column);
+        }
+    }
+    errs
+}
+
+fn check_column_def(errs: &mut Vec<RuleViolation>, raw_stmt: &RawStmt, column_def: &ColumnDef) {
+    if builtin_type_name(column_def) == Some("money") {
+        errs.push(RuleViolation::new(
+            RuleViolationKind::BanMoneyType,
+            raw_stmt,
+            None,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::check_sql_with_rule;
+    use crate::rules::test_utils::violations_to_kinds;
+    use crate::violations::{RuleViolation, RuleViolationKind};
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::BanMoneyType, None, false).unwrap()
+    }
+
+    #[test]
+    fn test_create_table_with_money() {
+        let bad_sql = r#"
+CREATE TABLE "core_order" (
+    "id" bigint PRIMARY KEY,
+    "total" money NOT NULL
+);
+CREATE TABLE "core_refund" ("amount" pg_catalog.money);
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::BanMoneyType,
+                RuleViolationKind::BanMoneyType
+            ]
+        );
+    }
+
+    #[test]
+    fn test_add_column_with_money() {
+        let bad_sql = r#"
+ALTER TABLE "core_order" ADD COLUMN "total" money;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![RuleViolationKind::BanMoneyType]
+        );
+    }
+
+    /// Only the built-in column type matters, not names that contain "money"
+    /// or a "money" type in another schema.
+    #[test]
+    fn test_money_in_names() {
+        let ok_sql = r#"
+CREATE TABLE "money" (
+    "money" numeric(19, 4) NOT NULL,
+    "money_amount" numeric NOT NULL,
+    "price" money_amount NOT NULL
+);
+ALTER TABLE "core_order" ADD COLUMN "money" numeric;
+ALTER TABLE "core_order" ADD COLUMN "total" "myschema"."money";
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::config::RuleOptions;
+use crate::rules::utils::tables_created_in_transaction;
+use crate::versions::Version;
+use crate::violations::{RuleViolation, RuleViolationKind};
+
+use squawk_parser::ast::{AlterTableCmds, AlterTableType, RawStmt, Stmt};
+
+/// `SET LOGGED` and `SET UNLOGGED` both rewrite the table, since the WAL
+/// written for the table changes.
+#[must_use]
+pub fn ban_set_unlogged(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let tables_created = tables_created_in_transaction(tree, assume_in_transaction);
+    let mut errs = vec![];
+
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            Stmt::AlterTableStmt(stmt) if !tables_created.contains(&stmt.relation.relname) => {
+                for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
+                    if matches!(
+                        cmd.subtype,
+                        AlterTableType::SetLogged | AlterTableType::SetUnLogged
+                    ) {
+                        errs.push(RuleViolation::new(
+                            RuleViolationKind::BanSetUnlogged,
+                            raw_stmt,
+                            None,
+                        ));
+                    }
+                }
+            }
+            _ => continue,
+        }
+    }
+
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::BanSetUnlogged, None, false).unwrap()
+    }
+
+    #[test]
+    fn test_set_logged_and_unlogged() {
+        let bad_sql = r#"
+ALTER TABLE "core_event" SET UNLOGGED;
+ALTER TABLE "core_event" SET LOGGED;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::BanSetUnlogged,
+                RuleViolationKind::BanSetUnlogged,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_other_alter_table() {
+        let ok_sql = r#"
+ALTER TABLE "core_event" SET (fillfactor = 70);
+CREATE UNLOGGED TABLE "core_cache" ("key" text, "value" text);
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_new_table() {
+        let ok_sql = r#"
+BEGIN;
+CREATE TABLE "core_event" ("id" bigint);
+ALTER TABLE "core_event" SET UNLOGGED;
+COMMIT;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
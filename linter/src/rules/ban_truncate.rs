@@ -0,0 +1,80 @@
+use crate::{
+    config::RuleOptions,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind},
+};
+
+use squawk_parser::ast::{RawStmt, Stmt};
+
+/// `TRUNCATE` deletes all the data in the tables under an `ACCESS EXCLUSIVE`
+/// lock.
+#[must_use]
+pub fn ban_truncate(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            Stmt::TruncateStmt(_) => {
+                errs.push(RuleViolation::new(
+                    RuleViolationKind::BanTruncate,
+                    raw_stmt,
+                    None,
+                ));
+            }
+            _ => continue,
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::BanTruncate, None, false).unwrap()
+    }
+
+    #[test]
+    fn test_ban_truncate() {
+        let bad_sql = r#"
+TRUNCATE "core_recipe";
+TRUNCATE TABLE "core_recipe" RESTART IDENTITY CASCADE;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::BanTruncate,
+                RuleViolationKind::BanTruncate
+            ]
+        );
+    }
+
+    /// One violation for the statement, rather than one per table.
+    #[test]
+    fn test_multiple_tables() {
+        let bad_sql = r#"
+TRUNCATE TABLE "core_recipe", "core_ingredient";
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![RuleViolationKind::BanTruncate]
+        );
+    }
+
+    #[test]
+    fn test_other_statements() {
+        let ok_sql = r#"
+DELETE FROM "core_recipe" WHERE "id" = 1;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{
+    config::RuleOptions,
+    versions::Version,
+    violations::{LockLevel, RuleViolation, RuleViolationKind, Severity, ViolationMessage},
+};
+
+use serde_json::Value;
+use squawk_parser::ast::{RawStmt, Stmt};
+
+/// Whether the `DefElem` argument enables the option, an option without an
+/// argument like `VACUUM (FULL)` is enabled.
+fn is_enabled(arg: Option<&Value>) -> bool {
+    let Some(arg) = arg else {
+        return true;
+    };
+    if let Some(sval) = arg["String"]["sval"].as_str() {
+        return !matches!(sval.to_lowercase().as_str(), "false" | "off" | "0");
+    }
+    if let Some(ival) = arg.get("Integer") {
+        // libpg_query omits zero values from the JSON output.
+        return ival["ival"].as_i64().unwrap_or(0) != 0;
+    }
+    true
+}
+
+/// Whether the `VACUUM` uses `FULL`, either `VACUUM FULL` or `VACUUM (FULL)`,
+/// which both parse to a `full` option.
+fn is_vacuum_full(stmt: &Value) -> bool {
+    stmt["options"].as_array().map_or(false, |options| {
+        options.iter().any(|option| {
+            let def_elem = &option["DefElem"];
+            def_elem["defname"].as_str() == Some("full") && is_enabled(def_elem.get("arg"))
+        })
+    })
+}
+
+/// `VACUUM FULL` rewrites the table under an `ACCESS EXCLUSIVE` lock. Plain
+/// `VACUUM` is safe to run, but it can't run in a transaction, so it's only
+/// reported with the `flag_plain_vacuum` option.
+#[must_use]
+pub fn ban_vacuum_full(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let flag_plain_vacuum = rule_options
+        .get("flag_plain_vacuum")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            // `ANALYZE` also parses to a VacuumStmt, but can run in a
+            // transaction.
+            Stmt::VacuumStmt(stmt) if stmt["is_vacuumcmd"].as_bool() == Some(true) => {
+                if is_vacuum_full(stmt) {
+                    errs.push(RuleViolation::new(
+                        RuleViolationKind::BanVacuumFull,
+                        raw_stmt,
+                        None,
+                    ));
+                } else if flag_plain_vacuum {
+                    let mut violation = RuleViolation::new(
+                        RuleViolationKind::BanVacuumFull,
+                        raw_stmt,
+                        Some(vec![
+                            ViolationMessage::Note("VACUUM can't run inside a transaction block.".into()),
+                            ViolationMessage::Help("Run VACUUM outside of the migration, Postgres autovacuum usually handles it.".into()),
+                        ]),
+                    );
+                    violation.severity = Severity::Warning;
+                    violation.lock_level = Some(LockLevel::ShareUpdateExclusive);
+                    errs.push(violation);
+                }
+            }
+            _ => continue,
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql, check_sql_with_rule,
+        config::{CheckSqlConfig, RuleOptions},
+        rules::test_utils::violations_to_kinds,
+        violations::{LockLevel, RuleViolation, RuleViolationKind, Severity},
+    };
+    use serde_json::json;
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::BanVacuumFull, None, false).unwrap()
+    }
+
+    fn lint_sql_with_plain_vacuum(sql: &str) -> Vec<RuleViolation> {
+        let mut options = RuleOptions::new();
+        options.insert("flag_plain_vacuum".into(), json!(true));
+        let mut config = CheckSqlConfig::default();
+        config
+            .rules
+            .insert(RuleViolationKind::BanVacuumFull, options);
+        check_sql(sql, &config)
+            .unwrap()
+            .into_iter()
+            .filter(|v| v.kind == RuleViolationKind::BanVacuumFull)
+            .collect()
+    }
+
+    #[test]
+    fn test_vacuum_full() {
+        let bad_sql = r#"
+VACUUM FULL "core_foo";
+VACUUM (FULL) "core_foo";
+VACUUM (VERBOSE, FULL true) "core_foo";
+VACUUM FULL;
+  "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![
+                RuleViolationKind::BanVacuumFull,
+                RuleViolationKind::BanVacuumFull,
+                RuleViolationKind::BanVacuumFull,
+                RuleViolationKind::BanVacuumFull,
+            ]
+        );
+        assert!(res.iter().all(|v| v.severity == Severity::Error));
+    }
+
+    #[test]
+    fn test_vacuum_without_full() {
+        let ok_sql = r#"
+VACUUM "core_foo";
+VACUUM (VERBOSE, ANALYZE) "core_foo";
+VACUUM (FULL false) "core_foo";
+VACUUM (FULL 0) "core_foo";
+ANALYZE "core_foo";
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_flag_plain_vacuum() {
+        let sql = r#"
+VACUUM "core_foo";
+VACUUM FULL "core_foo";
+ANALYZE "core_foo";
+  "#;
+        let res = lint_sql_with_plain_vacuum(sql);
+        let severities: Vec<Severity> = res.iter().map(|v| v.severity).collect();
+        assert_eq!(severities, vec![Severity::Warning, Severity::Error]);
+        let lock_levels: Vec<Option<LockLevel>> = res.iter().map(|v| v.lock_level).collect();
+        assert_eq!(
+            lock_levels,
+            vec![
+                Some(LockLevel::ShareUpdateExclusive),
+                Some(LockLevel::AccessExclusive)
+            ]
+        );
+    }
+}
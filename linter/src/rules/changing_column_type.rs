@@ -1,28 +1,196 @@
+use std::collections::HashMap;
+
 use crate::{
+    config::RuleOptions,
     versions::Version,
-    violations::{RuleViolation, RuleViolationKind},
+    violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+};
+
+use squawk_parser::ast::{
+    AlterTableCmds, AlterTableDef, AlterTableType, ColumnDef, RawStmt, Stmt, TableElt,
 };
 
-use squawk_parser::ast::{AlterTableCmds, AlterTableType, RawStmt, Stmt};
+/// Pairs of different types where the old type is binary coercible to the
+/// new one, so Postgres doesn't need to rewrite the table.
+const COERCIBLE_TYPES: &[(&str, &str)] = &[
+    ("varchar", "text"),
+    ("text", "varchar"),
+    ("cidr", "inet"),
+    ("xml", "text"),
+];
+
+/// Types where increasing the length or precision, or removing it entirely,
+/// doesn't rewrite the table, e.g. `varchar(10)` to `varchar(20)`.
+const WIDENABLE_TYPES: &[&str] = &[
+    "varchar",
+    "varbit",
+    "numeric",
+    "time",
+    "timetz",
+    "timestamp",
+    "timestamptz",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+struct ColumnType {
+    name: String,
+    typmods: Vec<i64>,
+    /// The `COLLATE` clause, `None` for the type's default collation.
+    collation: Option<String>,
+}
+
+impl ColumnType {
+    fn new(column_def: &ColumnDef) -> Option<Self> {
+        let type_name = &column_def.type_name;
+        let name = type_name.names.last()?.string.sval.clone();
+        let typmods = type_name
+            .typmods
+            .iter()
+            .map(|typmod| typmod["A_Const"]["ival"]["ival"].as_i64())
+            .collect::<Option<_>>()?;
+        Some(Self {
+            name,
+            typmods,
+            collation: collation_name(column_def).map(str::to_string),
+        })
+    }
+}
+
+fn collation_name(column_def: &ColumnDef) -> Option<&str> {
+    column_def.coll_clause.as_ref()?["collname"]
+        .as_array()?
+        .last()?["String"]["sval"]
+        .as_str()
+}
+
+/// Whether the type change also changes the collation. Without `COLLATE` the
+/// column gets the new type's default collation, so we can only tell for
+/// columns defined earlier in the file.
+fn changes_collation(old: Option<&ColumnType>, new: &ColumnType) -> bool {
+    match old {
+        Some(old) => old.collation != new.collation,
+        None => new.collation.is_some(),
+    }
+}
+
+fn collation_messages(collation: Option<&str>) -> Vec<ViolationMessage> {
+    let collation = collation.map_or_else(
+        || "the type's default".to_string(),
+        |name| format!(r#""{name}""#),
+    );
+    vec![
+        ViolationMessage::Note(format!("Changing the collation to {collation} rebuilds the indexes on the column while holding an ACCESS EXCLUSIVE lock.")),
+        ViolationMessage::Note("The new collation can change how values sort and compare, which can break queries and unique constraints that depend on the old behavior.".into()),
+    ]
+}
+
+fn is_widened(old: &[i64], new: &[i64], is_numeric: bool) -> bool {
+    if new.is_empty() {
+        return true;
+    }
+    if old.is_empty() {
+        return false;
+    }
+    if is_numeric {
+        // numeric(precision, scale), the scale defaults to 0.
+        let old_scale = old.get(1).copied().unwrap_or(0);
+        let new_scale = new.get(1).copied().unwrap_or(0);
+        return new[0] >= old[0] && new_scale == old_scale;
+    }
+    new[0] >= old[0]
+}
+
+/// Whether changing from `old` to `new` requires rewriting the table.
+fn requires_rewrite(old: &ColumnType, new: &ColumnType) -> bool {
+    if old.name == new.name {
+        if old.typmods == new.typmods {
+            return false;
+        }
+        return !(WIDENABLE_TYPES.contains(&new.name.as_str())
+            && is_widened(&old.typmods, &new.typmods, new.name == "numeric"));
+    }
+    if new.name == "varchar" && !new.typmods.is_empty() {
+        return true;
+    }
+    !COERCIBLE_TYPES.contains(&(old.name.as_str(), new.name.as_str()))
+}
 
+/// Type changes where the old type is binary coercible to the new type skip
+/// the table rewrite, so we don't report them.
+///
+/// We only know the old type of columns defined earlier in the same file,
+/// other type changes are always reported.
 #[must_use]
 pub fn changing_column_type(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
     let mut errs = vec![];
+    let mut column_types: HashMap<(String, String), ColumnType> = HashMap::new();
     for raw_stmt in tree {
         match &raw_stmt.stmt {
+            Stmt::CreateStmt(stmt) => {
+                for elt in &stmt.table_elts {
+                    if let TableElt::ColumnDef(column_def) = elt {
+                        if let (Some(colname), Some(column_type)) =
+                            (&column_def.colname, ColumnType::new(column_def))
+                        {
+                            column_types.insert(
+                                (stmt.relation.relname.clone(), colname.clone()),
+                                column_type,
+                            );
+                        }
+                    }
+                }
+            }
             Stmt::AlterTableStmt(stmt) => {
                 for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
+                    let Some(AlterTableDef::ColumnDef(column_def)) = &cmd.def else {
+                        continue;
+                    };
+                    let colname = match cmd.subtype {
+                        AlterTableType::AddColumn => column_def.colname.as_ref(),
+                        AlterTableType::AlterColumnType => cmd.name.as_ref(),
+                        _ => continue,
+                    };
+                    let Some(colname) = colname else {
+                        continue;
+                    };
+                    let key = (stmt.relation.relname.clone(), colname.clone());
+                    let new_type = ColumnType::new(column_def);
                     if cmd.subtype == AlterTableType::AlterColumnType {
-                        errs.push(RuleViolation::new(
-                            RuleViolationKind::ChangingColumnType,
-                            raw_stmt.into(),
-                            None,
-                        ));
+                        let old_type = column_types.get(&key);
+                        let is_safe = match (old_type, &new_type) {
+                            (Some(old_type), Some(new_type)) => {
+                                !requires_rewrite(old_type, new_type)
+                            }
+                            _ => false,
+                        };
+                        // a collation change is reported even when the type
+                        // change is safe, since the indexes are rebuilt.
+                        if new_type
+                            .as_ref()
+                            .map_or(false, |new_type| changes_collation(old_type, new_type))
+                        {
+                            errs.push(RuleViolation::new(
+                                RuleViolationKind::ChangingColumnType,
+                                raw_stmt,
+                                Some(collation_messages(collation_name(column_def))),
+                            ));
+                        } else if !is_safe {
+                            errs.push(RuleViolation::new(
+                                RuleViolationKind::ChangingColumnType,
+                                raw_stmt,
+                                None,
+                            ));
+                        }
                     }
+                    match new_type {
+                        Some(new_type) => column_types.insert(key, new_type),
+                        None => column_types.remove(&key),
+                    };
                 }
             }
             _ => continue,
@@ -35,7 +203,8 @@ pub fn changing_column_type(
 mod test_rules {
     use crate::{
         check_sql_with_rule,
-        violations::{RuleViolation, RuleViolationKind},
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind, ViolationMessage},
     };
     use insta::assert_debug_snapshot;
 
@@ -67,4 +236,71 @@ COMMIT;
 
         assert_debug_snapshot!(lint_sql(bad_sql));
     }
+
+    #[test]
+    fn test_coercible_type_change() {
+        let ok_sql = r#"
+CREATE TABLE "core_recipe" ("id" bigint, "name" varchar(10), "price" numeric(10, 2));
+ALTER TABLE "core_recipe" ADD COLUMN "slug" varchar(10);
+ALTER TABLE "core_recipe" ALTER COLUMN "name" TYPE text;
+ALTER TABLE "core_recipe" ALTER COLUMN "slug" TYPE varchar(20);
+ALTER TABLE "core_recipe" ALTER COLUMN "slug" TYPE varchar;
+ALTER TABLE "core_recipe" ALTER COLUMN "price" TYPE numeric(12, 2);
+        "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_type_change_requiring_rewrite() {
+        let bad_sql = r#"
+CREATE TABLE "core_recipe" ("id" int, "name" varchar(20), "price" numeric(10, 2));
+ALTER TABLE "core_recipe" ALTER COLUMN "id" TYPE bigint;
+ALTER TABLE "core_recipe" ALTER COLUMN "name" TYPE varchar(10);
+ALTER TABLE "core_recipe" ALTER COLUMN "price" TYPE numeric(10, 4);
+ALTER TABLE "core_recipe" ALTER COLUMN "name" TYPE varchar;
+ALTER TABLE "core_recipe" ALTER COLUMN "name" TYPE varchar(5);
+        "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::ChangingColumnType,
+                RuleViolationKind::ChangingColumnType,
+                RuleViolationKind::ChangingColumnType,
+                RuleViolationKind::ChangingColumnType,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collation_change() {
+        let bad_sql = r#"
+CREATE TABLE "core_recipe" ("id" bigint, "name" text);
+ALTER TABLE "core_recipe" ALTER COLUMN "name" TYPE text COLLATE "C";
+        "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::ChangingColumnType]
+        );
+        assert_eq!(
+            res[0].messages[0],
+            ViolationMessage::Note(r#"Changing the collation to "C" rebuilds the indexes on the column while holding an ACCESS EXCLUSIVE lock."#.into())
+        );
+
+        // removing the COLLATE clause resets the collation to the default.
+        let bad_sql = r#"
+CREATE TABLE "core_recipe" ("id" bigint, "name" text COLLATE "C");
+ALTER TABLE "core_recipe" ALTER COLUMN "name" TYPE text;
+        "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![RuleViolationKind::ChangingColumnType]
+        );
+
+        let ok_sql = r#"
+CREATE TABLE "core_recipe" ("id" bigint, "name" varchar(10) COLLATE "C");
+ALTER TABLE "core_recipe" ALTER COLUMN "name" TYPE text COLLATE "C";
+        "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
 }
@@ -0,0 +1,85 @@
+use crate::{
+    config::RuleOptions,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind},
+};
+
+use squawk_parser::ast::{RawStmt, Stmt};
+
+/// `ALTER ... SET SCHEMA` for objects that clients query by name.
+const OBJECT_TYPES: [&str; 4] = [
+    "OBJECT_TABLE",
+    "OBJECT_SEQUENCE",
+    "OBJECT_VIEW",
+    "OBJECT_MATVIEW",
+];
+
+#[must_use]
+pub fn changing_object_schema(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            Stmt::AlterObjectSchemaStmt(stmt)
+                if stmt["objectType"]
+                    .as_str()
+                    .map_or(false, |object_type| OBJECT_TYPES.contains(&object_type)) =>
+            {
+                errs.push(RuleViolation::new(
+                    RuleViolationKind::ChangingObjectSchema,
+                    raw_stmt,
+                    None,
+                ));
+            }
+            _ => continue,
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::ChangingObjectSchema, None, false).unwrap()
+    }
+
+    #[test]
+    fn test_set_schema() {
+        let bad_sql = r#"
+ALTER TABLE "core_recipe" SET SCHEMA "archive";
+ALTER TABLE IF EXISTS "core_recipe" SET SCHEMA "archive";
+ALTER SEQUENCE "core_recipe_id_seq" SET SCHEMA "archive";
+ALTER VIEW "core_recipe_view" SET SCHEMA "archive";
+ALTER MATERIALIZED VIEW "core_recipe_stats" SET SCHEMA "archive";
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::ChangingObjectSchema,
+                RuleViolationKind::ChangingObjectSchema,
+                RuleViolationKind::ChangingObjectSchema,
+                RuleViolationKind::ChangingObjectSchema,
+                RuleViolationKind::ChangingObjectSchema,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_other_objects() {
+        let ok_sql = r#"
+ALTER EXTENSION "hstore" SET SCHEMA "extensions";
+ALTER TABLE "core_recipe" RENAME TO "core_dish";
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
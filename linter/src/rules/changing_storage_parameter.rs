@@ -0,0 +1,149 @@
+use crate::{
+    config::RuleOptions,
+    rules::utils::tables_created_in_transaction,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+};
+
+use squawk_parser::ast::{AlterTableCmds, AlterTableDef, AlterTableType, RawStmt, Stmt};
+
+/// How changing a table's storage parameter affects the rows already in it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Effect {
+    /// Only used for newly written rows, so the table has to be rewritten for
+    /// the existing rows to pick it up.
+    NeedsRewrite,
+    /// Only changes how the table is vacuumed or planned.
+    Harmless,
+}
+
+/// Storage parameters from
+/// <https://www.postgresql.org/docs/current/sql-createtable.html#SQL-CREATETABLE-STORAGE-PARAMETERS>.
+/// The `autovacuum_*` parameters are all harmless, so they're matched by
+/// prefix instead.
+const STORAGE_PARAMETERS: &[(&str, Effect)] = &[
+    ("fillfactor", Effect::NeedsRewrite),
+    ("toast_tuple_target", Effect::NeedsRewrite),
+    ("log_autovacuum_min_duration", Effect::Harmless),
+    ("parallel_workers", Effect::Harmless),
+    ("vacuum_index_cleanup", Effect::Harmless),
+    ("vacuum_truncate", Effect::Harmless),
+];
+
+fn effect(name: &str) -> Option<Effect> {
+    if name.starts_with("autovacuum_") {
+        return Some(Effect::Harmless);
+    }
+    STORAGE_PARAMETERS
+        .iter()
+        .find(|(param, _)| *param == name)
+        .map(|(_, effect)| *effect)
+}
+
+/// `ALTER TABLE ... SET (...)` with storage parameters that only apply to the
+/// existing rows once the table is rewritten, e.g. `fillfactor`. Unknown
+/// parameters aren't reported.
+#[must_use]
+pub fn changing_storage_parameter(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let tables_created = tables_created_in_transaction(tree, assume_in_transaction);
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            Stmt::AlterTableStmt(stmt) if !tables_created.contains(&stmt.relation.relname) => {
+                for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
+                    let (AlterTableType::SetRelOptions, Some(AlterTableDef::List(options))) =
+                        (&cmd.subtype, &cmd.def)
+                    else {
+                        continue;
+                    };
+                    let Some(options) = options["items"].as_array() else {
+                        continue;
+                    };
+                    for option in options {
+                        let Some(name) = option["DefElem"]["defname"].as_str() else {
+                            continue;
+                        };
+                        if effect(name) != Some(Effect::NeedsRewrite) {
+                            continue;
+                        }
+                        errs.push(RuleViolation::new(
+                            RuleViolationKind::ChangingStorageParameter,
+                            raw_stmt,
+                            Some(vec![
+                                ViolationMessage::Note(format!("Changing {name} only applies to newly written rows, the existing rows keep the old setting until the table is rewritten, e.g. by VACUUM FULL or CLUSTER, which hold an ACCESS EXCLUSIVE lock.")),
+                                ViolationMessage::Help("Rewrite the table without blocking reads and writes, e.g. with pg_repack, or leave the existing rows as they are.".into()),
+                            ]),
+                        ));
+                    }
+                }
+            }
+            _ => continue,
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(
+            sql,
+            &RuleViolationKind::ChangingStorageParameter,
+            None,
+            false,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_rewriting_parameter() {
+        let bad_sql = r#"
+ALTER TABLE "core_recipe" SET (fillfactor = 70);
+ALTER TABLE "core_recipe" SET (autovacuum_enabled = false, toast_tuple_target = 256);
+  "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![
+                RuleViolationKind::ChangingStorageParameter,
+                RuleViolationKind::ChangingStorageParameter,
+            ]
+        );
+        assert_eq!(
+            res[1].messages[0],
+            ViolationMessage::Note("Changing toast_tuple_target only applies to newly written rows, the existing rows keep the old setting until the table is rewritten, e.g. by VACUUM FULL or CLUSTER, which hold an ACCESS EXCLUSIVE lock.".into())
+        );
+    }
+
+    #[test]
+    fn test_harmless_parameter() {
+        let ok_sql = r#"
+ALTER TABLE "core_recipe" SET (autovacuum_enabled = false);
+ALTER TABLE "core_recipe" SET (autovacuum_vacuum_scale_factor = 0.01, parallel_workers = 4);
+ALTER TABLE "core_recipe" SET (toast.autovacuum_enabled = false);
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_new_table() {
+        let ok_sql = r#"
+BEGIN;
+CREATE TABLE "core_recipe" ("id" bigint PRIMARY KEY);
+ALTER TABLE "core_recipe" SET (fillfactor = 70);
+COMMIT;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
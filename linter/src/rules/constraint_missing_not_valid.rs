@@ -1,35 +1,58 @@
 use std::collections::HashSet;
 
+use crate::config::RuleOptions;
+use crate::rules::utils::{tables_created_in_transaction, TransactionChange, TransactionTracker};
 use crate::versions::Version;
 use crate::violations::{RuleViolation, RuleViolationKind, ViolationMessage};
-use crate::{rules::utils::tables_created_in_transaction, violations::Span};
 use squawk_parser::ast::{
-    AlterTableCmds, AlterTableDef, AlterTableType, RawStmt, Stmt, TransactionStmtKind,
+    AlterTableCmds, AlterTableDef, AlterTableType, ColumnDefConstraint, ConstrType, Constraint,
+    RawStmt, Stmt,
 };
 
-/// Return list of spans for offending transactions. From the start of BEGIN to
+/// Whether the column is defined with an inline `CHECK` constraint, which
+/// can't be added as `NOT VALID`.
+fn has_check_constraint(constraints: &[ColumnDefConstraint]) -> bool {
+    constraints
+        .iter()
+        .any(|ColumnDefConstraint::Constraint(constraint)| constraint.contype == ConstrType::Check)
+}
+
+/// `CHECK ("col" IS NOT NULL)`, which some tools add before `SET NOT NULL` so
+/// Postgres 12+ can skip scanning the table. It only helps when the check is
+/// added `NOT VALID` and validated separately.
+fn is_not_null_check(constraint: &Constraint) -> bool {
+    constraint.contype == ConstrType::Check
+        && constraint.raw_expr.as_ref().map_or(false, |expr| {
+            expr["NullTest"]["nulltesttype"].as_str() == Some("IS_NOT_NULL")
+        })
+}
+
+/// Return list of statements for offending transactions. From the start of BEGIN to
 /// the end of COMMIT.
-fn not_valid_validate_in_transaction(tree: &[RawStmt], assume_in_transaction: bool) -> Vec<Span> {
+fn not_valid_validate_in_transaction(
+    tree: &[RawStmt],
+    assume_in_transaction: bool,
+) -> Vec<&RawStmt> {
     let mut not_valid_names = HashSet::new();
-    let mut in_transaction = assume_in_transaction;
-    let mut bad_spans = vec![];
+    let mut transaction = TransactionTracker::new(assume_in_transaction);
+    let mut bad_stmts = vec![];
     for raw_stmt in tree {
-        match &raw_stmt.stmt {
-            Stmt::TransactionStmt(stmt) => {
-                if stmt.kind == TransactionStmtKind::Begin && !in_transaction {
-                    in_transaction = true;
-                    not_valid_names.clear();
-                }
-                if stmt.kind == TransactionStmtKind::Commit {
-                    in_transaction = false;
-                }
+        match transaction.update(&raw_stmt.stmt) {
+            // the constraints added in a rolled back transaction don't exist
+            // anymore.
+            Some(TransactionChange::Begin | TransactionChange::Rollback) => {
+                not_valid_names.clear();
             }
+            Some(TransactionChange::Commit) | None => {}
+        }
+        let in_transaction = transaction.inside_transaction();
+        match &raw_stmt.stmt {
             Stmt::AlterTableStmt(stmt) => {
                 for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
                     if cmd.subtype == AlterTableType::ValidateConstraint {
                         if let Some(constraint_name) = &cmd.name {
                             if in_transaction && not_valid_names.get(constraint_name).is_some() {
-                                bad_spans.push(raw_stmt.into());
+                                bad_stmts.push(raw_stmt);
                             }
                         }
                     }
@@ -48,7 +71,7 @@ fn not_valid_validate_in_transaction(tree: &[RawStmt], assume_in_transaction: bo
             _ => continue,
         }
     }
-    bad_spans
+    bad_stmts
 }
 
 #[must_use]
@@ -56,13 +79,14 @@ pub fn constraint_missing_not_valid(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
     let mut errs = vec![];
     let tables_created = tables_created_in_transaction(tree, assume_in_transaction);
-    for span in not_valid_validate_in_transaction(tree, assume_in_transaction) {
+    for raw_stmt in not_valid_validate_in_transaction(tree, assume_in_transaction) {
         errs.push(RuleViolation::new(
                 RuleViolationKind::ConstraintMissingNotValid,
-                span,
+                raw_stmt,
                 Some(vec![
                     ViolationMessage::Note("Using NOT VALID and VALIDATE CONSTRAINT in the same transaction will block all reads while the constraint is validated.".into()), ViolationMessage::Help("Add constraint as NOT VALID in one transaction and VALIDATE CONSTRAINT in a separate transaction.".into())
                 ]),
@@ -76,14 +100,38 @@ pub fn constraint_missing_not_valid(
                 for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
                     match &cmd.def {
                         Some(AlterTableDef::Constraint(constraint)) => {
-                            if !tables_created.contains(tbl_name) && constraint.initially_valid {
+                            // foreign keys are reported by adding-foreign-key-constraint.
+                            if !tables_created.contains(tbl_name)
+                                && constraint.initially_valid
+                                && constraint.contype != ConstrType::Foreign
+                            {
+                                let messages = is_not_null_check(constraint).then(|| vec![
+                                    ViolationMessage::Note("Adding a CHECK (... IS NOT NULL) constraint without NOT VALID scans the table while holding an ACCESS EXCLUSIVE lock, the same as SET NOT NULL.".into()),
+                                    ViolationMessage::Help("Add the constraint with NOT VALID, VALIDATE it in a separate transaction, and then SET NOT NULL, which uses the valid constraint to skip the scan in Postgres 12+.".into()),
+                                ]);
                                 errs.push(RuleViolation::new(
                                     RuleViolationKind::ConstraintMissingNotValid,
-                                    raw_stmt.into(),
-                                    None,
+                                    raw_stmt,
+                                    messages,
                                 ));
                             }
                         }
+                        // only the CHECK is reported here, NOT NULL and
+                        // DEFAULT are covered by the other adding-* rules.
+                        Some(AlterTableDef::ColumnDef(column_def))
+                            if cmd.subtype == AlterTableType::AddColumn
+                                && !tables_created.contains(tbl_name)
+                                && has_check_constraint(&column_def.constraints) =>
+                        {
+                            errs.push(RuleViolation::new(
+                                RuleViolationKind::ConstraintMissingNotValid,
+                                raw_stmt,
+                                Some(vec![
+                                    ViolationMessage::Note("Adding a column with a CHECK constraint requires a table scan to verify the constraint and an ACCESS EXCLUSIVE lock which blocks reads.".into()),
+                                    ViolationMessage::Help("Add the column without the CHECK, then add the constraint with NOT VALID in one transaction and VALIDATE the constraint in a separate transaction.".into()),
+                                ]),
+                            ));
+                        }
                         _ => continue,
                     }
                 }
@@ -99,8 +147,10 @@ mod test_rules {
     use insta::assert_debug_snapshot;
 
     use crate::{
-        check_sql_with_rule,
-        violations::{RuleViolation, RuleViolationKind},
+        check_sql, check_sql_with_rule,
+        config::CheckSqlConfig,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind, ViolationMessage},
     };
 
     fn lint_sql(sql: &str) -> Vec<RuleViolation> {
@@ -212,6 +262,59 @@ COMMIT;
         assert_debug_snapshot!(res[0].messages);
     }
 
+    /// Validating a `CHECK` right after adding it `NOT VALID` holds the
+    /// `ACCESS EXCLUSIVE` lock from the `ADD` while the table is scanned.
+    #[test]
+    fn not_valid_check_validate_in_transaction() {
+        let bad_sql = r#"
+BEGIN;
+ALTER TABLE "accounts" ADD CONSTRAINT "positive_balance" CHECK ("balance" >= 0) NOT VALID;
+ALTER TABLE "accounts" VALIDATE CONSTRAINT "positive_balance";
+COMMIT;
+"#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::ConstraintMissingNotValid]
+        );
+        assert_eq!(
+            res[0].messages[0],
+            ViolationMessage::Note("Using NOT VALID and VALIDATE CONSTRAINT in the same transaction will block all reads while the constraint is validated.".into())
+        );
+
+        let bad_sql = r#"
+ALTER TABLE "accounts" ADD CONSTRAINT "positive_balance" CHECK ("balance" >= 0) NOT VALID;
+ALTER TABLE "accounts" VALIDATE CONSTRAINT "positive_balance";
+"#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql_assuming_in_transaction(bad_sql)),
+            vec![RuleViolationKind::ConstraintMissingNotValid]
+        );
+        // without a transaction each statement commits on its own.
+        assert_eq!(lint_sql(bad_sql), vec![]);
+    }
+
+    #[test]
+    fn not_valid_validate_in_separate_transactions() {
+        let ok_sql = r#"
+BEGIN;
+ALTER TABLE "accounts" ADD CONSTRAINT "positive_balance" CHECK ("balance" >= 0) NOT VALID;
+COMMIT;
+BEGIN;
+ALTER TABLE "accounts" VALIDATE CONSTRAINT "positive_balance";
+COMMIT;
+"#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+
+        let ok_sql = r#"
+BEGIN;
+ALTER TABLE "accounts" ADD CONSTRAINT "positive_balance" CHECK ("balance" >= 0) NOT VALID;
+ROLLBACK;
+ALTER TABLE "accounts" VALIDATE CONSTRAINT "positive_balance";
+"#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
     /// ```sql
     /// -- instead of
     /// ALTER TABLE distributors ADD CONSTRAINT distfk FOREIGN KEY (address) REFERENCES addresses (address);
@@ -226,6 +329,7 @@ COMMIT;
 ALTER TABLE distributors ADD CONSTRAINT distfk FOREIGN KEY (address) REFERENCES addresses (address);
    "#;
 
+        // reported by adding-foreign-key-constraint instead.
         assert_debug_snapshot!(lint_sql(bad_sql));
 
         let ok_sql = r#"
@@ -262,4 +366,93 @@ ALTER TABLE accounts VALIDATE CONSTRAINT positive_balance;
 
         assert_debug_snapshot!(lint_sql(ok_sql));
     }
+
+    #[test]
+    fn test_adding_column_with_check_constraint() {
+        let bad_sql = r#"
+ALTER TABLE "accounts" ADD COLUMN "balance" integer CHECK ("balance" >= 0);
+   "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::ConstraintMissingNotValid]
+        );
+        // the constraint can't be NOT VALID inline, so the help is different.
+        assert_debug_snapshot!(res[0].messages);
+
+        let ok_sql = r#"
+ALTER TABLE "accounts" ADD COLUMN "balance" integer;
+ALTER TABLE "accounts" ADD CONSTRAINT "positive_balance" CHECK ("balance" >= 0) NOT VALID;
+   "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_adding_not_null_check_constraint() {
+        let bad_sql = r#"
+ALTER TABLE "core_recipe" ADD CONSTRAINT "foo_not_null" CHECK ("foo" IS NOT NULL);
+   "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::ConstraintMissingNotValid]
+        );
+        assert_eq!(
+            res[0].messages[1],
+            ViolationMessage::Help("Add the constraint with NOT VALID, VALIDATE it in a separate transaction, and then SET NOT NULL, which uses the valid constraint to skip the scan in Postgres 12+.".into())
+        );
+
+        let ok_sql = r#"
+ALTER TABLE "core_recipe" ADD CONSTRAINT "foo_not_null" CHECK ("foo" IS NOT NULL) NOT VALID;
+   "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    /// NOT NULL and DEFAULT are handled by the other adding-* rules.
+    #[test]
+    fn test_adding_column_with_other_constraints() {
+        let ok_sql = r#"
+ALTER TABLE "accounts" ADD COLUMN "balance" integer NOT NULL DEFAULT 0;
+ALTER TABLE "accounts" ADD COLUMN "note" text UNIQUE;
+   "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_adding_column_with_check_constraint_to_new_table() {
+        let ok_sql = r#"
+BEGIN;
+CREATE TABLE "accounts" ("id" bigint);
+ALTER TABLE "accounts" ADD COLUMN "balance" integer CHECK ("balance" >= 0);
+COMMIT;
+   "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    /// Foreign keys are only reported by adding-foreign-key-constraint.
+    #[test]
+    fn test_foreign_key_and_check_constraint() {
+        let sql = r#"
+ALTER TABLE "app_email" ADD CONSTRAINT "fk_user" FOREIGN KEY (user_id) REFERENCES "app_user" (id);
+ALTER TABLE "accounts" ADD CONSTRAINT "positive_balance" CHECK ("balance" >= 0);
+   "#;
+        let res: Vec<RuleViolation> = check_sql(sql, &CheckSqlConfig::default())
+            .unwrap()
+            .into_iter()
+            .filter(|v| {
+                matches!(
+                    v.kind,
+                    RuleViolationKind::AddingForeignKeyConstraint
+                        | RuleViolationKind::ConstraintMissingNotValid
+                )
+            })
+            .collect();
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![
+                RuleViolationKind::AddingForeignKeyConstraint,
+                RuleViolationKind::ConstraintMissingNotValid,
+            ]
+        );
+    }
 }
@@ -0,0 +1,95 @@
+use crate::config::RuleOptions;
+use crate::rules::utils::tables_created_in_transaction;
+use crate::versions::Version;
+use crate::violations::{RuleViolation, RuleViolationKind};
+
+use squawk_parser::ast::{
+    AlterTableCmds, AlterTableDef, AlterTableType, ConstrType, RawStmt, Stmt,
+};
+
+/// Like `UNIQUE` constraints, `EXCLUDE` constraints build an index while
+/// holding an `ACCESS EXCLUSIVE` lock, but they can't be added `USING INDEX`,
+/// so there's no concurrent alternative for existing tables.
+#[must_use]
+pub fn disallow_exclusion_constraint(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let tables_created = tables_created_in_transaction(tree, assume_in_transaction);
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            Stmt::AlterTableStmt(stmt) if !tables_created.contains(&stmt.relation.relname) => {
+                for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
+                    if let (
+                        Some(AlterTableDef::Constraint(constraint)),
+                        AlterTableType::AddConstraint,
+                    ) = (&cmd.def, &cmd.subtype)
+                    {
+                        if constraint.contype == ConstrType::Exclusion {
+                            errs.push(RuleViolation::new(
+                                RuleViolationKind::DisallowedExclusionConstraint,
+                                raw_stmt,
+                                None,
+                            ));
+                        }
+                    }
+                }
+            }
+            _ => continue,
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(
+            sql,
+            &RuleViolationKind::DisallowedExclusionConstraint,
+            None,
+            false,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_add_exclusion_constraint() {
+        let bad_sql = r#"
+ALTER TABLE "reservation" ADD CONSTRAINT "reservation_no_overlap" EXCLUDE USING gist ("room_id" WITH =, "during" WITH &&);
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![RuleViolationKind::DisallowedExclusionConstraint]
+        );
+    }
+
+    #[test]
+    fn test_new_table() {
+        let ok_sql = r#"
+CREATE TABLE "reservation" (
+    "room_id" bigint NOT NULL,
+    "during" tstzrange NOT NULL,
+    CONSTRAINT "reservation_no_overlap" EXCLUDE USING gist ("room_id" WITH =, "during" WITH &&)
+);
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+
+        let ok_sql = r#"
+BEGIN;
+CREATE TABLE "reservation" ("room_id" bigint NOT NULL, "during" tstzrange NOT NULL);
+ALTER TABLE "reservation" ADD CONSTRAINT "reservation_no_overlap" EXCLUDE USING gist ("room_id" WITH =, "during" WITH &&);
+COMMIT;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
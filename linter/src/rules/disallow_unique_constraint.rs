@@ -1,3 +1,4 @@
+use crate::config::RuleOptions;
 use crate::rules::utils::tables_created_in_transaction;
 use crate::versions::Version;
 use crate::violations::{RuleViolation, RuleViolationKind};
@@ -11,6 +12,7 @@ pub fn disallow_unique_constraint(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
     let tables_created = tables_created_in_transaction(tree, assume_in_transaction);
     let mut errs = vec![];
@@ -31,7 +33,7 @@ pub fn disallow_unique_constraint(
                             {
                                 errs.push(RuleViolation::new(
                                     RuleViolationKind::DisallowedUniqueConstraint,
-                                    raw_stmt.into(),
+                                    raw_stmt,
                                     None,
                                 ));
                             }
@@ -41,7 +43,7 @@ pub fn disallow_unique_constraint(
                                 if constraint.contype == ConstrType::Unique {
                                     errs.push(RuleViolation::new(
                                         RuleViolationKind::DisallowedUniqueConstraint,
-                                        raw_stmt.into(),
+                                        raw_stmt,
                                         None,
                                     ));
                                 }
@@ -60,7 +62,9 @@ pub fn disallow_unique_constraint(
 #[cfg(test)]
 mod test_rules {
     use crate::{
-        check_sql_with_rule,
+        check_sql, check_sql_with_rule,
+        config::CheckSqlConfig,
+        rules::test_utils::violations_to_kinds,
         violations::{RuleViolation, RuleViolationKind},
     };
     use insta::assert_debug_snapshot;
@@ -128,6 +132,47 @@ ALTER TABLE "legacy_questiongrouppg" ADD CONSTRAINT "legacy_questiongrouppg_mong
         assert_eq!(lint_sql(sql), vec![]);
     }
 
+    /// The index can be created in an earlier migration, and `USING INDEX`
+    /// works with other statements in the file.
+    #[test]
+    fn test_unique_constraint_using_index() {
+        let sql = r#"
+BEGIN;
+SET lock_timeout = '1s';
+ALTER TABLE "core_recipe" ADD CONSTRAINT "core_recipe_slug_uniq" UNIQUE USING INDEX "core_recipe_slug_idx";
+ALTER TABLE "core_recipe" ADD CONSTRAINT "core_recipe_name_uniq" UNIQUE USING INDEX "core_recipe_name_idx", ADD COLUMN "foo" text;
+COMMIT;
+        "#;
+        assert_eq!(lint_sql(sql), vec![]);
+    }
+
+    /// Building the index without `CONCURRENTLY` is reported by
+    /// require-concurrent-index-creation, so the constraint isn't reported
+    /// twice.
+    #[test]
+    fn test_unique_constraint_using_non_concurrent_index() {
+        let sql = r#"
+CREATE UNIQUE INDEX "core_recipe_slug_idx" ON "core_recipe" ("slug");
+ALTER TABLE "core_recipe" ADD CONSTRAINT "core_recipe_slug_uniq" UNIQUE USING INDEX "core_recipe_slug_idx";
+        "#;
+        assert_eq!(lint_sql(sql), vec![]);
+        let res: Vec<RuleViolation> = check_sql(sql, &CheckSqlConfig::default())
+            .unwrap()
+            .into_iter()
+            .filter(|v| {
+                matches!(
+                    v.kind,
+                    RuleViolationKind::DisallowedUniqueConstraint
+                        | RuleViolationKind::RequireConcurrentIndexCreation
+                )
+            })
+            .collect();
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::RequireConcurrentIndexCreation]
+        );
+    }
+
     /// Creating a UNIQUE constraint in the same transaction as the table is create is OK.
     #[test]
     fn test_unique_constraint_after_create_table() {
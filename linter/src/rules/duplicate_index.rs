@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use crate::{
+    config::RuleOptions,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+};
+
+use serde_json::{json, Value};
+use squawk_parser::ast::{IndexStmt, ObjectType, RawStmt, Stmt};
+
+fn strip_locations(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.remove("location");
+            map.values_mut().for_each(strip_locations);
+        }
+        Value::Array(values) => values.iter_mut().for_each(strip_locations),
+        _ => {}
+    }
+}
+
+/// The parts of an index that decide what it stores: the table, access
+/// method, uniqueness, ordered key list, included columns, storage
+/// parameters, and predicate. The name and `IF NOT EXISTS` don't change the
+/// index that's built.
+fn index_definition(stmt: &IndexStmt) -> String {
+    let mut definition = json!({
+        "schema": stmt.relation.schemaname,
+        "table": stmt.relation.relname,
+        "access_method": stmt.access_method,
+        "unique": stmt.unique,
+        "nulls_not_distinct": stmt.nulls_not_distinct,
+        "params": serde_json::to_value(&stmt.index_params).unwrap_or(Value::Null),
+        "include": serde_json::to_value(&stmt.index_including_params).unwrap_or(Value::Null),
+        "options": stmt.options,
+        "where": stmt.where_clause,
+    });
+    strip_locations(&mut definition);
+    definition.to_string()
+}
+
+/// The name of an index in `DROP INDEX`, without the schema.
+fn dropped_index_name(object: &Value) -> Option<&str> {
+    object["List"]["items"].as_array()?.last()?["String"]["sval"].as_str()
+}
+
+#[must_use]
+pub fn duplicate_index(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let mut indexes: HashMap<String, Option<&str>> = HashMap::new();
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        let stmt = match &raw_stmt.stmt {
+            Stmt::IndexStmt(stmt) => stmt,
+            // recreating a dropped index, e.g. to change its name, isn't a
+            // duplicate.
+            Stmt::DropStmt(stmt) if stmt.remove_type == ObjectType::Index => {
+                for name in stmt.objects.iter().filter_map(dropped_index_name) {
+                    indexes.retain(|_, index_name| *index_name != Some(name));
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        let name = stmt.idxname.as_deref();
+        let definition = index_definition(stmt);
+        match indexes.get(&definition).copied() {
+            Some(first) => {
+                let messages = match (name, first) {
+                    (Some(name), Some(first)) => Some(vec![
+                        ViolationMessage::Note(format!(
+                            r#"Index "{name}" has the same definition as "{first}", created earlier in the file."#
+                        )),
+                        ViolationMessage::Help("Remove the duplicate CREATE INDEX.".into()),
+                    ]),
+                    _ => None,
+                };
+                errs.push(RuleViolation::new(
+                    RuleViolationKind::DuplicateIndex,
+                    raw_stmt,
+                    messages,
+                ));
+            }
+            None => {
+                indexes.insert(definition, name);
+            }
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind, Severity, ViolationMessage},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::DuplicateIndex, None, false).unwrap()
+    }
+
+    #[test]
+    fn test_identical_indexes() {
+        let bad_sql = r#"
+CREATE INDEX CONCURRENTLY "core_recipe_foo_idx" ON "core_recipe" ("foo");
+CREATE INDEX CONCURRENTLY "core_recipe_foo_idx2" ON "core_recipe" ("foo");
+  "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::DuplicateIndex]
+        );
+        assert_eq!(res[0].span.start, 74);
+        assert_eq!(res[0].severity, Severity::Info);
+        assert_eq!(
+            res[0].messages[0],
+            ViolationMessage::Note(
+                r#"Index "core_recipe_foo_idx2" has the same definition as "core_recipe_foo_idx", created earlier in the file."#.into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_if_not_exists() {
+        let bad_sql = r#"
+CREATE INDEX "core_recipe_foo_idx" ON "core_recipe" ("foo") WHERE "foo" IS NOT NULL;
+CREATE INDEX IF NOT EXISTS "core_recipe_foo_idx" ON "core_recipe" ("foo") WHERE "foo" IS NOT NULL;
+CREATE INDEX IF NOT EXISTS ON "core_recipe" (lower("bar"));
+CREATE INDEX ON "core_recipe" (lower("bar"));
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::DuplicateIndex,
+                RuleViolationKind::DuplicateIndex,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_different_indexes() {
+        let ok_sql = r#"
+CREATE INDEX "core_recipe_foo_idx" ON "core_recipe" ("foo");
+CREATE INDEX "core_recipe_foo_bar_idx" ON "core_recipe" ("foo", "bar");
+CREATE INDEX "core_recipe_bar_foo_idx" ON "core_recipe" ("bar", "foo");
+CREATE INDEX "core_recipe_foo_desc_idx" ON "core_recipe" ("foo" DESC);
+CREATE UNIQUE INDEX "core_recipe_foo_key" ON "core_recipe" ("foo");
+CREATE INDEX "core_recipe_foo_partial_idx" ON "core_recipe" ("foo") WHERE "foo" > 0;
+CREATE INDEX "core_recipe_foo_gin_idx" ON "core_recipe" USING gin ("foo");
+CREATE INDEX "core_dish_foo_idx" ON "core_dish" ("foo");
+CREATE INDEX "other_recipe_foo_idx" ON "other"."core_recipe" ("foo");
+CREATE INDEX "core_recipe_foo_include_idx" ON "core_recipe" ("foo") INCLUDE ("bar");
+CREATE INDEX "core_recipe_foo_fillfactor_idx" ON "core_recipe" ("foo") WITH (fillfactor = 70);
+CREATE UNIQUE INDEX "core_recipe_foo_nulls_key" ON "core_recipe" ("foo") NULLS NOT DISTINCT;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_recreate_dropped_index() {
+        let ok_sql = r#"
+CREATE INDEX "core_recipe_foo_idx" ON "core_recipe" ("foo");
+DROP INDEX "core_recipe_foo_idx";
+CREATE INDEX "core_recipe_foo_idx" ON "core_recipe" ("foo");
+DROP INDEX IF EXISTS "public"."core_recipe_foo_idx";
+CREATE INDEX "core_recipe_foo_idx2" ON "core_recipe" ("foo");
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+
+        let bad_sql = r#"
+CREATE INDEX "core_recipe_foo_idx" ON "core_recipe" ("foo");
+DROP INDEX "core_recipe_bar_idx";
+CREATE INDEX "core_recipe_foo_idx2" ON "core_recipe" ("foo");
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![RuleViolationKind::DuplicateIndex]
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{
+    config::RuleOptions,
+    rules::utils::{strong_lock_level, tables_created_in_transaction},
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind},
+};
+
+use squawk_parser::ast::{RawStmt, Stmt};
+
+/// The statements that modify data: `INSERT`, `UPDATE`, `DELETE`, and
+/// `MERGE`.
+fn is_dml(stmt: &Stmt) -> bool {
+    matches!(
+        stmt,
+        Stmt::InsertStmt(_) | Stmt::UpdateStmt(_) | Stmt::DeleteStmt(_) | Stmt::MergeStmt(_)
+    )
+}
+
+/// Migrations usually run in a single transaction, so a backfill in the same
+/// file as a schema change holds the schema change's lock until the backfill
+/// finishes. Reported once per file, on the first data modifying statement.
+#[must_use]
+pub fn mixing_ddl_and_dml(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let tables_created = tables_created_in_transaction(tree, assume_in_transaction);
+    let changes_schema = tree
+        .iter()
+        .any(|raw_stmt| strong_lock_level(&raw_stmt.stmt, &tables_created).is_some());
+    if !changes_schema {
+        return vec![];
+    }
+    tree.iter()
+        .find(|raw_stmt| is_dml(&raw_stmt.stmt))
+        .map(|raw_stmt| RuleViolation::new(RuleViolationKind::MixingDdlAndDml, raw_stmt, None))
+        .into_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::MixingDdlAndDml, None, false).unwrap()
+    }
+
+    #[test]
+    fn test_mixed_file() {
+        let bad_sql = r#"
+ALTER TABLE "core_recipe" ADD COLUMN "status" text;
+UPDATE "core_recipe" SET "status" = 'draft';
+DELETE FROM "core_recipe" WHERE "status" IS NULL;
+  "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::MixingDdlAndDml]
+        );
+        assert_eq!(res[0].span.start, 52);
+    }
+
+    #[test]
+    fn test_dml_kinds() {
+        for dml in [
+            r#"INSERT INTO "core_recipe" ("id") VALUES (1);"#,
+            r#"UPDATE "core_recipe" SET "status" = 'draft';"#,
+            r#"DELETE FROM "core_recipe";"#,
+            r#"MERGE INTO "core_recipe" r USING "core_draft" d ON r."id" = d."id" WHEN MATCHED THEN UPDATE SET "status" = d."status";"#,
+        ] {
+            let sql = format!(
+                r#"CREATE INDEX "core_recipe_status_idx" ON "core_recipe" ("status"); {dml}"#
+            );
+            assert_eq!(
+                violations_to_kinds(&lint_sql(&sql)),
+                vec![RuleViolationKind::MixingDdlAndDml],
+                "{dml}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_only_ddl_or_dml() {
+        let ok_sql = r#"
+ALTER TABLE "core_recipe" ADD COLUMN "status" text;
+CREATE INDEX CONCURRENTLY "core_recipe_status_idx" ON "core_recipe" ("status");
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+
+        let ok_sql = r#"
+UPDATE "core_recipe" SET "status" = 'draft' WHERE "id" BETWEEN 1 AND 1000;
+SELECT 1;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    /// `VALIDATE CONSTRAINT` doesn't block the writes, so backfilling in the
+    /// same file is fine.
+    #[test]
+    fn test_validate_constraint() {
+        let ok_sql = r#"
+UPDATE "core_recipe" SET "status" = 'draft' WHERE "status" IS NULL;
+ALTER TABLE "core_recipe" VALIDATE CONSTRAINT "core_recipe_status_not_null";
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    /// Seeding a table created in the same transaction doesn't hold a lock
+    /// other queries are waiting on.
+    #[test]
+    fn test_new_table() {
+        let ok_sql = r#"
+BEGIN;
+CREATE TABLE "core_status" ("id" bigint, "name" text);
+ALTER TABLE "core_status" ADD CONSTRAINT "core_status_pk" PRIMARY KEY ("id");
+INSERT INTO "core_status" ("id", "name") VALUES (1, 'draft');
+COMMIT;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
@@ -52,3 +52,73 @@ pub mod adding_required_field;
 pub use adding_required_field::*;
 pub mod ban_concurrent_index_creation_in_transaction;
 pub use ban_concurrent_index_creation_in_transaction::*;
+pub mod adding_serial_column;
+pub use adding_serial_column::*;
+pub mod require_lock_timeout;
+pub use require_lock_timeout::*;
+pub mod prefer_jsonb;
+pub use prefer_jsonb::*;
+pub mod setting_not_null_on_existing_column;
+pub use setting_not_null_on_existing_column::*;
+pub mod ban_vacuum_full;
+pub use ban_vacuum_full::*;
+pub mod ban_cluster;
+pub use ban_cluster::*;
+pub mod adding_enum_value_in_transaction;
+pub use adding_enum_value_in_transaction::*;
+pub mod require_if_not_exists;
+pub use require_if_not_exists::*;
+pub mod renaming_constraint;
+pub use renaming_constraint::*;
+pub mod too_many_locking_statements;
+pub use too_many_locking_statements::*;
+pub mod ban_truncate;
+pub use ban_truncate::*;
+pub mod ban_disabling_statement_timeout;
+pub use ban_disabling_statement_timeout::*;
+pub mod prefer_primary_key;
+pub use prefer_primary_key::*;
+pub mod ban_money_type;
+pub use ban_money_type::*;
+pub mod adding_stored_generated_column;
+pub use adding_stored_generated_column::*;
+pub mod require_validate_constraint;
+pub use require_validate_constraint::*;
+pub mod ban_set_unlogged;
+pub use ban_set_unlogged::*;
+pub mod mixing_ddl_and_dml;
+pub use mixing_ddl_and_dml::*;
+pub mod require_concurrent_reindex;
+pub use require_concurrent_reindex::*;
+pub mod changing_object_schema;
+pub use changing_object_schema::*;
+pub mod duplicate_index;
+pub use duplicate_index::*;
+pub mod ban_create_table_as;
+pub use ban_create_table_as::*;
+pub mod partition_lock_hazard;
+pub use partition_lock_hazard::*;
+pub mod ban_grant_in_migration;
+pub use ban_grant_in_migration::*;
+pub mod naming_convention;
+pub use naming_convention::*;
+pub mod adding_column_with_domain_type;
+pub use adding_column_with_domain_type::*;
+pub mod non_immutable_check_constraint;
+pub use non_immutable_check_constraint::*;
+pub mod ban_deprecated_type;
+pub use ban_deprecated_type::*;
+pub mod require_replica_identity;
+pub use require_replica_identity::*;
+pub mod unbounded_data_modification;
+pub use unbounded_data_modification::*;
+pub mod ban_alter_owner;
+pub use ban_alter_owner::*;
+pub mod setting_search_path;
+pub use setting_search_path::*;
+pub mod disallow_exclusion_constraint;
+pub use disallow_exclusion_constraint::*;
+pub mod adding_identity_column;
+pub use adding_identity_column::*;
+pub mod changing_storage_parameter;
+pub use changing_storage_parameter::*;
@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+
+use crate::{
+    config::RuleOptions,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+};
+
+use regex::Regex;
+use serde_json::Value;
+use squawk_parser::ast::{
+    AlterTableCmds, AlterTableDef, ColumnDefConstraint, ConstrType, Constraint, RawStmt, Stmt,
+    TableElt,
+};
+
+const INDEX: &str = "index";
+/// Fallback for constraint kinds without their own pattern.
+const CONSTRAINT: &str = "constraint";
+
+/// The option for the constraint's pattern and a description for the
+/// message, `None` for constraints that can't be named, e.g. `NOT NULL`.
+fn constraint_kind(contype: &ConstrType) -> Option<(&'static str, &'static str)> {
+    match contype {
+        ConstrType::Primary => Some(("primary_key", "Primary key")),
+        ConstrType::Unique => Some(("unique", "Unique constraint")),
+        ConstrType::Foreign => Some(("foreign_key", "Foreign key")),
+        ConstrType::Check => Some(("check", "Check constraint")),
+        ConstrType::Exclusion => Some(("exclusion", "Exclusion constraint")),
+        _ => None,
+    }
+}
+
+type Patterns = HashMap<&'static str, (String, Regex)>;
+
+/// Compile each configured pattern once per file. Patterns match the whole
+/// name. Invalid patterns are returned as messages with the regex error, so a
+/// typo in the config doesn't turn the check off silently.
+fn compile_patterns(rule_options: &RuleOptions) -> (Patterns, Vec<String>) {
+    let mut patterns = Patterns::new();
+    let mut invalid = vec![];
+    for key in [
+        INDEX,
+        CONSTRAINT,
+        "primary_key",
+        "unique",
+        "foreign_key",
+        "check",
+        "exclusion",
+    ] {
+        let Some(pattern) = rule_options.get(key).and_then(Value::as_str) else {
+            continue;
+        };
+        match Regex::new(&format!("^(?:{pattern})$")) {
+            Ok(regex) => {
+                patterns.insert(key, (pattern.to_string(), regex));
+            }
+            Err(err) => invalid.push(format!(
+                r#"The "{key}" pattern `{pattern}` isn't a valid regular expression: {err}"#
+            )),
+        }
+    }
+    (patterns, invalid)
+}
+
+fn naming_violation(
+    raw_stmt: &RawStmt,
+    description: &str,
+    name: &str,
+    pattern: &str,
+) -> RuleViolation {
+    RuleViolation::new(
+        RuleViolationKind::NamingConvention,
+        raw_stmt,
+        Some(vec![
+            ViolationMessage::Note(format!(
+                r#"{description} name "{name}" doesn't match the pattern `{pattern}`."#
+            )),
+            ViolationMessage::Help("Rename it to match the configured naming convention.".into()),
+        ]),
+    )
+}
+
+fn check_constraint(
+    raw_stmt: &RawStmt,
+    constraint: &Constraint,
+    patterns: &Patterns,
+) -> Option<RuleViolation> {
+    let name = constraint.conname.as_ref()?;
+    let (key, description) = constraint_kind(&constraint.contype)?;
+    let (pattern, regex) = patterns.get(key).or_else(|| patterns.get(CONSTRAINT))?;
+    if regex.is_match(name) {
+        return None;
+    }
+    Some(naming_violation(raw_stmt, description, name, pattern))
+}
+
+/// Index and constraint names that don't match the patterns from the rule's
+/// options. Unnamed indexes and constraints are skipped since Postgres
+/// generates their names.
+#[must_use]
+pub fn naming_convention(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let (patterns, invalid) = compile_patterns(rule_options);
+    let mut errs = vec![];
+    // the options apply to the whole file, so report them on its first
+    // statement.
+    if let Some(raw_stmt) = tree.first() {
+        errs.extend(invalid.into_iter().map(|note| {
+            RuleViolation::new(
+                RuleViolationKind::NamingConvention,
+                raw_stmt,
+                Some(vec![
+                    ViolationMessage::Note(note),
+                    ViolationMessage::Help(
+                        "Fix the pattern in the naming-convention rule options.".into(),
+                    ),
+                ]),
+            )
+        }));
+    }
+    if patterns.is_empty() {
+        return errs;
+    }
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            Stmt::IndexStmt(stmt) => {
+                if let (Some(name), Some((pattern, regex))) = (&stmt.idxname, patterns.get(INDEX)) {
+                    if !regex.is_match(name) {
+                        errs.push(naming_violation(raw_stmt, "Index", name, pattern));
+                    }
+                }
+            }
+            Stmt::AlterTableStmt(stmt) => {
+                for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
+                    if let Some(AlterTableDef::Constraint(constraint)) = &cmd.def {
+                        errs.extend(check_constraint(raw_stmt, constraint, &patterns));
+                    }
+                }
+            }
+            Stmt::CreateStmt(stmt) => {
+                for elt in &stmt.table_elts {
+                    match elt {
+                        TableElt::Constraint(constraint) => {
+                            errs.extend(check_constraint(raw_stmt, constraint, &patterns));
+                        }
+                        TableElt::ColumnDef(column) => {
+                            for ColumnDefConstraint::Constraint(constraint) in &column.constraints {
+                                errs.extend(check_constraint(raw_stmt, constraint, &patterns));
+                            }
+                        }
+                        TableElt::TableLikeClause(_) => {}
+                    }
+                }
+            }
+            _ => continue,
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql,
+        config::{CheckSqlConfig, RuleOptions},
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+    };
+    use serde_json::json;
+
+    fn lint_sql_with_options(sql: &str, options: serde_json::Value) -> Vec<RuleViolation> {
+        let options: RuleOptions = serde_json::from_value(options).unwrap();
+        let mut config = CheckSqlConfig::default();
+        config
+            .rules
+            .insert(RuleViolationKind::NamingConvention, options);
+        check_sql(sql, &config)
+            .unwrap()
+            .into_iter()
+            .filter(|v| v.kind == RuleViolationKind::NamingConvention)
+            .collect()
+    }
+
+    #[test]
+    fn test_index_name() {
+        let options = json!({ "index": "idx_[a-z0-9_]+" });
+        let ok_sql = r#"
+CREATE INDEX CONCURRENTLY "idx_core_recipe_name" ON "core_recipe" ("name");
+CREATE INDEX CONCURRENTLY ON "core_recipe" ("created_at");
+  "#;
+        assert_eq!(lint_sql_with_options(ok_sql, options.clone()), vec![]);
+
+        let bad_sql = r#"
+CREATE INDEX CONCURRENTLY "core_recipe_name_idx" ON "core_recipe" ("name");
+  "#;
+        let res = lint_sql_with_options(bad_sql, options);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::NamingConvention]
+        );
+        assert_eq!(
+            res[0].messages[0],
+            ViolationMessage::Note(
+                r#"Index name "core_recipe_name_idx" doesn't match the pattern `idx_[a-z0-9_]+`."#
+                    .into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let options = json!({ "index": "idx_[a-z", "unique": "[a-z_]+_key" });
+        let sql = r#"
+CREATE INDEX CONCURRENTLY "core_recipe_name_idx" ON "core_recipe" ("name");
+ALTER TABLE "core_recipe" ADD CONSTRAINT "unique_name" UNIQUE ("name");
+  "#;
+        let res = lint_sql_with_options(sql, options);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![
+                RuleViolationKind::NamingConvention,
+                RuleViolationKind::NamingConvention,
+            ]
+        );
+        assert_eq!(res[0].span.start, 0);
+        assert!(matches!(
+            &res[0].messages[0],
+            ViolationMessage::Note(note)
+                if note.starts_with(r#"The "index" pattern `idx_[a-z` isn't a valid regular expression: "#)
+        ));
+        assert_eq!(
+            res[1].messages[0],
+            ViolationMessage::Note(
+                r#"Unique constraint name "unique_name" doesn't match the pattern `[a-z_]+_key`."#
+                    .into()
+            )
+        );
+    }
+
+    /// Constraint kinds without their own pattern use the `constraint`
+    /// pattern.
+    #[test]
+    fn test_constraint_names() {
+        let options = json!({
+            "foreign_key": "[a-z0-9_]+_fkey",
+            "constraint": "[a-z0-9_]+_(pkey|key|check)",
+        });
+        let ok_sql = r#"
+CREATE TABLE "core_recipe" (
+    "id" bigint CONSTRAINT "core_recipe_pkey" PRIMARY KEY,
+    "author_id" bigint NOT NULL
+);
+ALTER TABLE "core_recipe" ADD CONSTRAINT "core_recipe_author_id_fkey" FOREIGN KEY ("author_id") REFERENCES "core_author" ("id") NOT VALID;
+  "#;
+        assert_eq!(lint_sql_with_options(ok_sql, options.clone()), vec![]);
+
+        let bad_sql = r#"
+CREATE TABLE "core_recipe" (
+    "id" bigint PRIMARY KEY,
+    "author_id" bigint,
+    CONSTRAINT "positive_id" CHECK ("id" > 0)
+);
+ALTER TABLE "core_recipe" ADD CONSTRAINT "fk_author" FOREIGN KEY ("author_id") REFERENCES "core_author" ("id") NOT VALID;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql_with_options(bad_sql, options)),
+            vec![
+                RuleViolationKind::NamingConvention,
+                RuleViolationKind::NamingConvention,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_opt_in() {
+        let sql = r#"
+CREATE INDEX CONCURRENTLY "core_recipe_name_idx" ON "core_recipe" ("name");
+  "#;
+        let res: Vec<RuleViolation> = check_sql(sql, &CheckSqlConfig::default())
+            .unwrap()
+            .into_iter()
+            .filter(|v| v.kind == RuleViolationKind::NamingConvention)
+            .collect();
+        assert_eq!(res, vec![]);
+
+        assert_eq!(lint_sql_with_options(sql, json!({})), vec![]);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{
+    config::RuleOptions,
+    rules::utils::non_immutable_functions,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+};
+
+use squawk_parser::ast::{
+    AlterTableCmds, AlterTableDef, ColumnDefConstraint, ConstrType, Constraint, RawStmt, Stmt,
+    TableElt,
+};
+
+fn check_constraint(raw_stmt: &RawStmt, constraint: &Constraint) -> Option<RuleViolation> {
+    if constraint.contype != ConstrType::Check {
+        return None;
+    }
+    let mut funcs = non_immutable_functions(constraint.raw_expr.as_ref()?);
+    if funcs.is_empty() {
+        return None;
+    }
+    funcs.sort();
+    funcs.dedup();
+    Some(RuleViolation::new(
+        RuleViolationKind::NonImmutableCheckConstraint,
+        raw_stmt,
+        Some(vec![
+            ViolationMessage::Note(format!(
+                "The CHECK constraint calls {}, which isn't IMMUTABLE. Postgres only checks rows when they're inserted or updated, so existing rows can stop satisfying the constraint, and restoring a dump can fail.",
+                funcs.join(", ")
+            )),
+            ViolationMessage::Help(
+                "Compare against a fixed value, or use a trigger to check values against the current time or settings.".into(),
+            ),
+        ]),
+    ))
+}
+
+/// Postgres assumes `CHECK` constraints are immutable, so the result for a
+/// row never changes, but doesn't enforce it.
+#[must_use]
+pub fn non_immutable_check_constraint(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            Stmt::CreateStmt(stmt) => {
+                for elt in &stmt.table_elts {
+                    match elt {
+                        TableElt::Constraint(constraint) => {
+                            errs.extend(check_constraint(raw_stmt, constraint));
+                        }
+                        TableElt::ColumnDef(column_def) => {
+                            for ColumnDefConstraint::Constraint(constraint) in
+                                &column_def.constraints
+                            {
+                                errs.extend(check_constraint(raw_stmt, constraint));
+                            }
+                        }
+                        TableElt::TableLikeClause(_) => {}
+                    }
+                }
+            }
+            Stmt::AlterTableStmt(stmt) => {
+                for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
+                    match &cmd.def {
+                        Some(AlterTableDef::Constraint(constraint)) => {
+                            errs.extend(check_constraint(raw_stmt, constraint));
+                        }
+                        Some(AlterTableDef::ColumnDef(column_def)) => {
+                            for ColumnDefConstraint::Constraint(constraint) in
+                                &column_def.constraints
+                            {
+                                errs.extend(check_constraint(raw_stmt, constraint));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => continue,
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(
+            sql,
+            &RuleViolationKind::NonImmutableCheckConstraint,
+            None,
+            false,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_check_calls_now() {
+        let bad_sql = r#"
+ALTER TABLE "core_event" ADD CONSTRAINT "starts_in_past" CHECK ("starts_at" < now()) NOT VALID;
+  "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::NonImmutableCheckConstraint]
+        );
+        assert_eq!(
+            res[0].messages[0],
+            ViolationMessage::Note("The CHECK constraint calls now(), which isn't IMMUTABLE. Postgres only checks rows when they're inserted or updated, so existing rows can stop satisfying the constraint, and restoring a dump can fail.".into())
+        );
+    }
+
+    #[test]
+    fn test_non_immutable_functions() {
+        let bad_sql = r#"
+CREATE TABLE "core_event" (
+    "id" bigint,
+    "starts_at" timestamptz CHECK ("starts_at" > CURRENT_TIMESTAMP),
+    CONSTRAINT "random_id" CHECK ("id" < random() * 100)
+);
+ALTER TABLE "core_event" ADD COLUMN "ends_at" timestamptz CHECK ("ends_at" > pg_catalog.now() - interval '1 day');
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::NonImmutableCheckConstraint,
+                RuleViolationKind::NonImmutableCheckConstraint,
+                RuleViolationKind::NonImmutableCheckConstraint,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_immutable_check() {
+        let ok_sql = r#"
+ALTER TABLE "core_event" ADD CONSTRAINT "positive_id" CHECK ("id" > 0) NOT VALID;
+ALTER TABLE "core_event" ADD CONSTRAINT "ends_after_start" CHECK ("ends_at" > "starts_at" AND lower("name") <> '') NOT VALID;
+ALTER TABLE "core_event" ALTER COLUMN "created_at" SET DEFAULT now();
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashSet;
+
+use crate::{
+    config::RuleOptions,
+    rules::utils::tables_created_in_transaction,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+};
+
+use squawk_parser::ast::{
+    AlterTableCmds, AlterTableDef, AlterTableType, ConstrType, RawStmt, Stmt,
+};
+
+fn detach_messages(pg_version: Option<Version>) -> Vec<ViolationMessage> {
+    let help = match pg_version {
+        Some(pg_version) if pg_version < Version::new(14, None, None) => "DETACH PARTITION CONCURRENTLY requires Postgres 14+. Set a lock_timeout so the detach doesn't block other queries while it waits for the lock.",
+        _ => "Use DETACH PARTITION CONCURRENTLY, outside of a transaction.",
+    };
+    vec![
+        ViolationMessage::Note(
+            "Detaching a partition takes an ACCESS EXCLUSIVE lock on the partitioned table, blocking reads and writes to every partition.".into(),
+        ),
+        ViolationMessage::Help(help.into()),
+    ]
+}
+
+fn attach_messages() -> Vec<ViolationMessage> {
+    vec![
+        ViolationMessage::Note(
+            "Attaching a partition scans it to check the partition bound while holding an ACCESS EXCLUSIVE lock on the partition.".into(),
+        ),
+        ViolationMessage::Help(
+            "Add and VALIDATE a CHECK constraint matching the partition bound before attaching, so the scan is skipped.".into(),
+        ),
+    ]
+}
+
+#[must_use]
+pub fn partition_lock_hazard(
+    tree: &[RawStmt],
+    pg_version: Option<Version>,
+    assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let tables_created = tables_created_in_transaction(tree, assume_in_transaction);
+    // tables with a validated constraint, which `ATTACH PARTITION` can use
+    // instead of scanning when it implies the partition bound.
+    let mut tables_checked = HashSet::new();
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        let Stmt::AlterTableStmt(stmt) = &raw_stmt.stmt else {
+            continue;
+        };
+        for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
+            match (&cmd.subtype, &cmd.def) {
+                (AlterTableType::AddConstraint, Some(AlterTableDef::Constraint(constraint)))
+                    if constraint.contype == ConstrType::Check && constraint.initially_valid =>
+                {
+                    tables_checked.insert(stmt.relation.relname.clone());
+                }
+                (AlterTableType::ValidateConstraint, _) => {
+                    tables_checked.insert(stmt.relation.relname.clone());
+                }
+                (AlterTableType::DetachPartition, Some(AlterTableDef::PartitionCmd(partition)))
+                    if partition["concurrent"].as_bool() != Some(true)
+                        && !tables_created.contains(&stmt.relation.relname) =>
+                {
+                    errs.push(RuleViolation::new(
+                        RuleViolationKind::PartitionLockHazard,
+                        raw_stmt,
+                        Some(detach_messages(pg_version)),
+                    ));
+                }
+                (AlterTableType::AttachPartition, Some(AlterTableDef::PartitionCmd(partition))) => {
+                    let Some(partition_name) = partition["name"]["relname"].as_str() else {
+                        continue;
+                    };
+                    if tables_created.contains(partition_name)
+                        || tables_checked.contains(partition_name)
+                    {
+                        continue;
+                    }
+                    errs.push(RuleViolation::new(
+                        RuleViolationKind::PartitionLockHazard,
+                        raw_stmt,
+                        Some(attach_messages()),
+                    ));
+                }
+                _ => {}
+            }
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use std::str::FromStr;
+
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        versions::Version,
+        violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+    };
+
+    fn lint_sql(sql: &str, pg_version: Option<Version>) -> Vec<RuleViolation> {
+        check_sql_with_rule(
+            sql,
+            &RuleViolationKind::PartitionLockHazard,
+            pg_version,
+            false,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_detach_partition() {
+        let bad_sql = r#"
+ALTER TABLE "measurement" DETACH PARTITION "measurement_y2022";
+  "#;
+        let res = lint_sql(bad_sql, None);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::PartitionLockHazard]
+        );
+        assert_eq!(
+            res[0].messages[1],
+            ViolationMessage::Help(
+                "Use DETACH PARTITION CONCURRENTLY, outside of a transaction.".into()
+            )
+        );
+
+        let res = lint_sql(bad_sql, Some(Version::from_str("13").unwrap()));
+        assert_eq!(
+            res[0].messages[1],
+            ViolationMessage::Help("DETACH PARTITION CONCURRENTLY requires Postgres 14+. Set a lock_timeout so the detach doesn't block other queries while it waits for the lock.".into())
+        );
+
+        let ok_sql = r#"
+ALTER TABLE "measurement" DETACH PARTITION "measurement_y2022" CONCURRENTLY;
+ALTER TABLE "measurement" DETACH PARTITION "measurement_y2022" FINALIZE;
+  "#;
+        assert_eq!(lint_sql(ok_sql, None), vec![]);
+    }
+
+    #[test]
+    fn test_attach_partition() {
+        let bad_sql = r#"
+ALTER TABLE "measurement" ATTACH PARTITION "measurement_y2023" FOR VALUES FROM ('2023-01-01') TO ('2024-01-01');
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql, None)),
+            vec![RuleViolationKind::PartitionLockHazard]
+        );
+
+        // a NOT VALID constraint can't be used to skip the scan.
+        let bad_sql = r#"
+ALTER TABLE "measurement_y2023" ADD CONSTRAINT "y2023_bound" CHECK ("logdate" >= '2023-01-01' AND "logdate" < '2024-01-01') NOT VALID;
+ALTER TABLE "measurement" ATTACH PARTITION "measurement_y2023" FOR VALUES FROM ('2023-01-01') TO ('2024-01-01');
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql, None)),
+            vec![RuleViolationKind::PartitionLockHazard]
+        );
+    }
+
+    #[test]
+    fn test_attach_partition_with_check() {
+        let ok_sql = r#"
+ALTER TABLE "measurement_y2023" ADD CONSTRAINT "y2023_bound" CHECK ("logdate" >= '2023-01-01' AND "logdate" < '2024-01-01') NOT VALID;
+ALTER TABLE "measurement_y2023" VALIDATE CONSTRAINT "y2023_bound";
+ALTER TABLE "measurement" ATTACH PARTITION "measurement_y2023" FOR VALUES FROM ('2023-01-01') TO ('2024-01-01');
+  "#;
+        assert_eq!(lint_sql(ok_sql, None), vec![]);
+    }
+
+    #[test]
+    fn test_new_partition() {
+        let ok_sql = r#"
+BEGIN;
+CREATE TABLE "measurement_y2023" (LIKE "measurement" INCLUDING DEFAULTS);
+ALTER TABLE "measurement" ATTACH PARTITION "measurement_y2023" FOR VALUES FROM ('2023-01-01') TO ('2024-01-01');
+COMMIT;
+  "#;
+        assert_eq!(lint_sql(ok_sql, None), vec![]);
+    }
+}
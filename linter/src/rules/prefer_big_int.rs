@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
 use crate::{
+    config::RuleOptions,
     versions::Version,
     violations::{RuleViolation, RuleViolationKind},
 };
@@ -14,6 +15,7 @@ pub fn prefer_big_int(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
     let mut errs = vec![];
     for raw_stmt in tree {
@@ -42,7 +44,7 @@ fn check_column_def(errs: &mut Vec<RuleViolation>, raw_stmt: &RawStmt, column_de
         if SMALL_INT_TYPES.contains(column_name.string.sval.as_str()) {
             errs.push(RuleViolation::new(
                 RuleViolationKind::PreferBigInt,
-                raw_stmt.into(),
+                raw_stmt,
                 None,
             ));
         }
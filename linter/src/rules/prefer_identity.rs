@@ -1,19 +1,19 @@
-use std::collections::HashSet;
-
 use crate::{
+    config::RuleOptions,
     versions::Version,
     violations::{RuleViolation, RuleViolationKind},
 };
 
 use squawk_parser::ast::{ColumnDef, RawStmt};
 
-use super::utils::columns_create_or_modified;
+use super::utils::{columns_create_or_modified, is_serial_column};
 
 #[must_use]
 pub fn prefer_identity(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
     let mut errs = vec![];
     for raw_stmt in tree {
@@ -24,26 +24,13 @@ pub fn prefer_identity(
     errs
 }
 
-lazy_static! {
-    static ref SERIAL_TYPES: HashSet<&'static str> = HashSet::from([
-        "serial",
-        "serial2",
-        "serial4",
-        "serial8",
-        "smallserial",
-        "bigserial",
-    ]);
-}
-
 fn check_column_def(errs: &mut Vec<RuleViolation>, raw_stmt: &RawStmt, column_def: &ColumnDef) {
-    if let Some(column_name) = column_def.type_name.names.last() {
-        if SERIAL_TYPES.contains(column_name.string.sval.as_str()) {
-            errs.push(RuleViolation::new(
-                RuleViolationKind::PreferIdentity,
-                raw_stmt.into(),
-                None,
-            ));
-        }
+    if is_serial_column(column_def) {
+        errs.push(RuleViolation::new(
+            RuleViolationKind::PreferIdentity,
+            raw_stmt,
+            None,
+        ));
     }
 }
 
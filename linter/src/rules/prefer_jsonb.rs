@@ -0,0 +1,121 @@
+use crate::{
+    config::RuleOptions,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind},
+};
+
+use squawk_parser::ast::{ColumnDef, RawStmt};
+
+use crate::rules::utils::{builtin_type_name, columns_create_or_modified};
+
+#[must_use]
+pub fn prefer_jsonb(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        for column in columns_create_or_modified(&raw_stmt.stmt) {
+            check_column_def(&mut errs, raw_stmt, column);
+        }
+    }
+    errs
+}
+
+fn check_column_def(errs: &mut Vec<RuleViolation>, raw_stmt: &RawStmt, column_def: &ColumnDef) {
+    if builtin_type_name(column_def) == Some("json") {
+        errs.push(RuleViolation::new(
+            RuleViolationKind::PreferJsonb,
+            raw_stmt,
+            None,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::check_sql_with_rule;
+    use crate::rules::test_utils::violations_to_kinds;
+    use crate::violations::{RuleViolation, RuleViolationKind};
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::PreferJsonb, None, false).unwrap()
+    }
+
+    #[test]
+    fn test_create_table_with_json() {
+        let bad_sql = r#"
+create table app.users
+(
+    data json
+);
+create table app.accounts
+(
+    tags json[]
+);
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::PreferJsonb,
+                RuleViolationKind::PreferJsonb
+            ]
+        );
+
+        let ok_sql = r#"
+create table app.users
+(
+    data jsonb
+);
+create table app.accounts
+(
+    tags jsonb[]
+);
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_add_column_with_json() {
+        let bad_sql = r#"
+alter table app.users add column data json;
+alter table app.users add column tags json[] not null default '{}';
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::PreferJsonb,
+                RuleViolationKind::PreferJsonb
+            ]
+        );
+
+        let ok_sql = r#"
+alter table app.users add column data jsonb;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    /// Only the built-in `json` type is reported, not a type with the same
+    /// name in another schema.
+    #[test]
+    fn test_schema_qualified_json() {
+        let bad_sql = r#"
+alter table app.users add column data pg_catalog.json;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![RuleViolationKind::PreferJsonb]
+        );
+
+        let ok_sql = r#"
+alter table app.users add column data app.json;
+create table app.accounts
+(
+    data "app"."json"
+);
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{
+    config::RuleOptions,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind},
+};
+
+use squawk_parser::ast::{RawStmt, Stmt};
+
+use super::utils::{has_primary_key, may_inherit_primary_key, TEMPORARY};
+
+/// Tables without a primary key can't be replicated with logical
+/// replication, which needs a replica identity for updates and deletes.
+#[must_use]
+pub fn prefer_primary_key(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            Stmt::CreateStmt(stmt)
+                if stmt.relation.relpersistence != TEMPORARY
+                    && !may_inherit_primary_key(stmt)
+                    && !has_primary_key(stmt) =>
+            {
+                errs.push(RuleViolation::new(
+                    RuleViolationKind::PreferPrimaryKey,
+                    raw_stmt,
+                    None,
+                ));
+            }
+            _ => continue,
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::PreferPrimaryKey, None, false).unwrap()
+    }
+
+    #[test]
+    fn test_missing_primary_key() {
+        let bad_sql = r#"
+CREATE TABLE "core_foo" ("id" bigint NOT NULL, "name" text);
+CREATE UNLOGGED TABLE "core_bar" ("id" bigint UNIQUE);
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::PreferPrimaryKey,
+                RuleViolationKind::PreferPrimaryKey,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_primary_key() {
+        let ok_sql = r#"
+CREATE TABLE "core_foo" ("id" bigint GENERATED ALWAYS AS IDENTITY PRIMARY KEY);
+CREATE TABLE "core_bar" (
+    "foo_id" bigint NOT NULL,
+    "baz_id" bigint NOT NULL,
+    PRIMARY KEY ("foo_id", "baz_id")
+);
+CREATE TABLE "core_qux" (
+    "id" bigint NOT NULL,
+    CONSTRAINT "core_qux_pkey" PRIMARY KEY ("id")
+);
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    /// Temporary tables aren't replicated and are dropped at the end of the
+    /// session.
+    #[test]
+    fn test_temporary_table() {
+        let ok_sql = r#"
+CREATE TEMPORARY TABLE "tmp_foo" ("id" bigint);
+CREATE TEMP TABLE "tmp_bar" ("id" bigint);
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    /// Partitions use the primary key of the partitioned table.
+    #[test]
+    fn test_partition() {
+        let ok_sql = r#"
+CREATE TABLE "measurement_y2024" PARTITION OF "measurement"
+    FOR VALUES FROM ('2024-01-01') TO ('2025-01-01');
+CREATE TABLE "measurement_default" PARTITION OF "measurement" DEFAULT;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_like() {
+        let ok_sql = r#"
+CREATE TABLE "core_foo_copy" (LIKE "core_foo" INCLUDING ALL);
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
@@ -1,18 +1,30 @@
 use std::collections::HashMap;
 
 use crate::{
+    config::RuleOptions,
+    rules::utils::TransactionTracker,
     versions::Version,
     violations::{RuleViolation, RuleViolationKind, ViolationMessage},
 };
-use squawk_parser::ast::{
-    AlterTableCmds, AlterTableDef, AlterTableType, RawStmt, Stmt, TransactionStmtKind,
-};
+use squawk_parser::ast::{AlterTableCmds, AlterTableDef, AlterTableType, RawStmt, Stmt};
 #[derive(PartialEq)]
 enum Constraint {
     Dropped,
     Added,
 }
 
+/// When the migration runner wraps the file in a transaction, suggesting a
+/// transaction is noise, so we only suggest the guards.
+fn violation_messages(assume_in_transaction: bool) -> Option<Vec<ViolationMessage>> {
+    if assume_in_transaction {
+        Some(vec![ViolationMessage::Help(
+            "Consider adding a IF NOT EXISTS clause if the statement supports it.".into(),
+        )])
+    } else {
+        None
+    }
+}
+
 /// If a migration is running in a transaction, then we skip the statements
 /// because if it fails part way through, it will revert.
 /// For the cases where statements aren't running in a transaction, for instance,
@@ -24,9 +36,10 @@ pub fn prefer_robust_stmts(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
     let mut errs = vec![];
-    let mut inside_transaction = assume_in_transaction;
+    let mut transaction = TransactionTracker::new(assume_in_transaction);
     let mut constraint_names: HashMap<String, Constraint> = HashMap::new();
     // if we only have one statement in our file, Postgres will run that
     // statement in an implicit transaction, so we don't need to worry about
@@ -34,13 +47,11 @@ pub fn prefer_robust_stmts(
     if tree.len() == 1 {
         return errs;
     }
+    let messages = violation_messages(assume_in_transaction);
     for raw_stmt in tree {
+        transaction.update(&raw_stmt.stmt);
+        let inside_transaction = transaction.inside_transaction();
         match &raw_stmt.stmt {
-            Stmt::TransactionStmt(stmt) => match stmt.kind {
-                TransactionStmtKind::Begin => inside_transaction = true,
-                TransactionStmtKind::Commit => inside_transaction = false,
-                _ => continue,
-            },
             Stmt::AlterTableStmt(stmt) => {
                 for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
                     if let Some(constraint_name) = &cmd.name {
@@ -70,8 +81,8 @@ pub fn prefer_robust_stmts(
                     }
                     errs.push(RuleViolation::new(
                         RuleViolationKind::PreferRobustStmts,
-                        raw_stmt.into(),
-                        None,
+                        raw_stmt,
+                        messages.clone(),
                     ));
                 }
             }
@@ -80,7 +91,7 @@ pub fn prefer_robust_stmts(
             Stmt::IndexStmt(stmt) if stmt.concurrent && stmt.idxname.is_none() => {
                 errs.push(RuleViolation::new(
                     RuleViolationKind::PreferRobustStmts,
-                    raw_stmt.into(),
+                    raw_stmt,
                     Some(vec![ViolationMessage::Help(
                         "Use an explicit name for a concurrently created index".into(),
                     )]),
@@ -91,22 +102,22 @@ pub fn prefer_robust_stmts(
             {
                 errs.push(RuleViolation::new(
                     RuleViolationKind::PreferRobustStmts,
-                    raw_stmt.into(),
-                    None,
+                    raw_stmt,
+                    messages.clone(),
                 ));
             }
             Stmt::CreateStmt(stmt) if !stmt.if_not_exists && !inside_transaction => {
                 errs.push(RuleViolation::new(
                     RuleViolationKind::PreferRobustStmts,
-                    raw_stmt.into(),
-                    None,
+                    raw_stmt,
+                    messages.clone(),
                 ));
             }
             Stmt::DropStmt(stmt) if !stmt.missing_ok && !inside_transaction => {
                 errs.push(RuleViolation::new(
                     RuleViolationKind::PreferRobustStmts,
-                    raw_stmt.into(),
-                    None,
+                    raw_stmt,
+                    messages.clone(),
                 ));
             }
             _ => continue,
@@ -119,8 +130,9 @@ pub fn prefer_robust_stmts(
 mod test_rules {
     use crate::{
         check_sql_with_rule,
+        config::RuleOptions,
         errors::CheckSqlError,
-        violations::{RuleViolation, RuleViolationKind},
+        violations::{RuleViolation, RuleViolationKind, ViolationMessage},
     };
     use insta::assert_debug_snapshot;
     use squawk_parser::parse::parse_sql_query;
@@ -132,7 +144,7 @@ mod test_rules {
         // our check ignores single statement queries, so we add an extra statement to ensure we check that case
         sql.push_str(";\nSELECT 1;");
         let tree = parse_sql_query(&sql)?;
-        Ok(prefer_robust_stmts(&tree, None, false))
+        Ok(prefer_robust_stmts(&tree, None, false, &RuleOptions::new()))
     }
 
     fn lint_sql_assuming_in_transaction(sql: &str) -> Result<Vec<RuleViolation>, CheckSqlError> {
@@ -140,7 +152,7 @@ mod test_rules {
         // our check ignores single statement queries, so we add an extra statement to ensure we check that case
         sql.push_str(";\nSELECT 1;");
         let tree = parse_sql_query(&sql)?;
-        Ok(prefer_robust_stmts(&tree, None, true))
+        Ok(prefer_robust_stmts(&tree, None, true, &RuleOptions::new()))
     }
 
     #[test]
@@ -284,6 +296,34 @@ DROP TYPE foo;
         assert_eq!(lint_sql_assuming_in_transaction(sql), Ok(vec![]));
     }
 
+    /// When the runner wraps the migration in a transaction, we only suggest
+    /// adding a guard.
+    #[test]
+    fn test_assume_in_transaction_messages() {
+        let sql = r#"
+COMMIT;
+CREATE INDEX CONCURRENTLY "core_foo_idx" ON "core_foo" ("bar");
+BEGIN;
+"#;
+        let res = lint_sql_assuming_in_transaction(sql).unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(
+            res[0].messages,
+            vec![ViolationMessage::Help(
+                "Consider adding a IF NOT EXISTS clause if the statement supports it.".into()
+            )]
+        );
+
+        let res = lint_sql(sql).unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(
+            res[0].messages,
+            vec![ViolationMessage::Help(
+                "Consider wrapping in a transaction or adding a IF NOT EXISTS clause if the statement supports it.".into()
+            )]
+        );
+    }
+
     #[test]
     fn test_create_index_concurrently_unnamed() {
         let bad_sql = r#"
@@ -340,17 +380,21 @@ ALTER TABLE "core_foo" ADD COLUMN "answer_id" integer NULL;
             [
                 RuleViolation {
                     kind: PreferRobustStmts,
+                    severity: Warning,
+                    lock_level: None,
                     span: Span {
                         start: 0,
                         len: Some(
                             59,
                         ),
                     },
+                    statement_kind: AlterTable,
                     messages: [
                         Help(
                             "Consider wrapping in a transaction or adding a IF NOT EXISTS clause if the statement supports it.",
                         ),
                     ],
+                    snippet: None,
                 },
             ],
         )
@@ -364,17 +408,21 @@ CREATE INDEX CONCURRENTLY "core_foo_idx" ON "core_foo" ("answer_id");
             [
                 RuleViolation {
                     kind: PreferRobustStmts,
+                    severity: Warning,
+                    lock_level: None,
                     span: Span {
                         start: 0,
                         len: Some(
                             69,
                         ),
                     },
+                    statement_kind: CreateIndex,
                     messages: [
                         Help(
                             "Consider wrapping in a transaction or adding a IF NOT EXISTS clause if the statement supports it.",
                         ),
                     ],
+                    snippet: None,
                 },
             ],
         )
@@ -388,17 +436,21 @@ CREATE TABLE "core_bar" ( "id" serial NOT NULL PRIMARY KEY, "bravo" text NOT NUL
             [
                 RuleViolation {
                     kind: PreferRobustStmts,
+                    severity: Warning,
+                    lock_level: None,
                     span: Span {
                         start: 0,
                         len: Some(
                             83,
                         ),
                     },
+                    statement_kind: CreateTable,
                     messages: [
                         Help(
                             "Consider wrapping in a transaction or adding a IF NOT EXISTS clause if the statement supports it.",
                         ),
                     ],
+                    snippet: None,
                 },
             ],
         )
@@ -412,17 +464,21 @@ ALTER TABLE "core_foo" DROP CONSTRAINT "core_foo_idx";
             [
                 RuleViolation {
                     kind: PreferRobustStmts,
+                    severity: Warning,
+                    lock_level: None,
                     span: Span {
                         start: 0,
                         len: Some(
                             54,
                         ),
                     },
+                    statement_kind: AlterTable,
                     messages: [
                         Help(
                             "Consider wrapping in a transaction or adding a IF NOT EXISTS clause if the statement supports it.",
                         ),
                     ],
+                    snippet: None,
                 },
             ],
         )
@@ -1,11 +1,13 @@
 use crate::{
+    config::RuleOptions,
     versions::Version,
     violations::{RuleViolation, RuleViolationKind},
 };
 
-use squawk_parser::ast::{ColumnDef, RawStmt};
-
-use crate::rules::utils::columns_create_or_modified;
+use serde_json::Value;
+use squawk_parser::ast::{
+    AlterTableCmds, AlterTableDef, AlterTableType, ColumnDef, RawStmt, Stmt, TableElt, TypeName,
+};
 
 /// It's easier to update the check constraint on a text field than a varchar()
 /// size since the check constraint can use NOT VALID with a separate VALIDATE
@@ -15,23 +17,69 @@ pub fn prefer_text_field(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     _assume_in_transaction: bool,
+    rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
+    // varchars up to this length are allowed, e.g. `varchar(32)` for short codes.
+    let max_allowed_length = rule_options
+        .get("max_allowed_length")
+        .and_then(Value::as_i64);
     let mut errs = vec![];
     for raw_stmt in tree {
-        for column in columns_create_or_modified(&raw_stmt.stmt) {
-            check_column_def(&mut errs, raw_stmt, column);
+        match &raw_stmt.stmt {
+            Stmt::CreateStmt(stmt) => {
+                for elt in &stmt.table_elts {
+                    if let TableElt::ColumnDef(column_def) = elt {
+                        check_column_def(&mut errs, raw_stmt, column_def, max_allowed_length);
+                    }
+                }
+            }
+            Stmt::AlterTableStmt(stmt) => {
+                for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
+                    let Some(AlterTableDef::ColumnDef(column_def)) = &cmd.def else {
+                        continue;
+                    };
+                    match cmd.subtype {
+                        AlterTableType::AddColumn => {
+                            check_column_def(&mut errs, raw_stmt, column_def, max_allowed_length);
+                        }
+                        // resizing a varchar is reported regardless of the
+                        // length since the type change needs a lock.
+                        AlterTableType::AlterColumnType => {
+                            check_column_def(&mut errs, raw_stmt, column_def, None);
+                        }
+                        _ => continue,
+                    }
+                }
+            }
+            _ => continue,
         }
     }
     errs
 }
 
-fn check_column_def(errs: &mut Vec<RuleViolation>, raw_stmt: &RawStmt, column_def: &ColumnDef) {
+fn varchar_length(type_name: &TypeName) -> Option<i64> {
+    type_name.typmods.first()?["A_Const"]["ival"]["ival"].as_i64()
+}
+
+fn check_column_def(
+    errs: &mut Vec<RuleViolation>,
+    raw_stmt: &RawStmt,
+    column_def: &ColumnDef,
+    max_allowed_length: Option<i64>,
+) {
     let type_name = &column_def.type_name;
     for field_type_name in &type_name.names {
         if field_type_name.string.sval == "varchar" && !type_name.typmods.is_empty() {
+            if let (Some(max_allowed_length), Some(length)) =
+                (max_allowed_length, varchar_length(type_name))
+            {
+                if length <= max_allowed_length {
+                    continue;
+                }
+            }
             errs.push(RuleViolation::new(
                 RuleViolationKind::PreferTextField,
-                raw_stmt.into(),
+                raw_stmt,
                 None,
             ));
         }
@@ -44,13 +92,30 @@ mod test_rules {
     use insta::assert_debug_snapshot;
 
     use crate::{
-        check_sql_with_rule,
+        check_sql, check_sql_with_rule,
+        config::{CheckSqlConfig, RuleOptions},
         violations::{RuleViolation, RuleViolationKind},
     };
+    use serde_json::json;
+
     fn lint_sql(sql: &str) -> Vec<RuleViolation> {
         check_sql_with_rule(sql, &RuleViolationKind::PreferTextField, None, false).unwrap()
     }
 
+    fn lint_sql_with_max_length(sql: &str, max_allowed_length: i64) -> Vec<RuleViolation> {
+        let mut options = RuleOptions::new();
+        options.insert("max_allowed_length".into(), json!(max_allowed_length));
+        let mut config = CheckSqlConfig::default();
+        config
+            .rules
+            .insert(RuleViolationKind::PreferTextField, options);
+        check_sql(sql, &config)
+            .unwrap()
+            .into_iter()
+            .filter(|v| v.kind == RuleViolationKind::PreferTextField)
+            .collect()
+    }
+
     /// Changing a column of varchar(255) to varchar(1000) requires an ACCESS
     /// EXCLUSIVE lock
     #[test]
@@ -67,12 +132,17 @@ COMMIT;
         [
             RuleViolation {
                 kind: PreferTextField,
+                severity: Warning,
+                lock_level: Some(
+                    AccessExclusive,
+                ),
                 span: Span {
                     start: 7,
                     len: Some(
                         123,
                     ),
                 },
+                statement_kind: AlterTable,
                 messages: [
                     Note(
                         "Changing the size of a varchar field requires an ACCESS EXCLUSIVE lock.",
@@ -80,7 +150,11 @@ COMMIT;
                     Help(
                         "Use a text field with a check constraint.",
                     ),
+                    Url(
+                        "https://www.postgresql.org/docs/current/sql-altertable.html",
+                    ),
                 ],
+                snippet: None,
             },
         ]
         "###);
@@ -103,12 +177,17 @@ COMMIT;
         [
             RuleViolation {
                 kind: PreferTextField,
+                severity: Warning,
+                lock_level: Some(
+                    AccessExclusive,
+                ),
                 span: Span {
                     start: 7,
                     len: Some(
                         127,
                     ),
                 },
+                statement_kind: CreateTable,
                 messages: [
                     Note(
                         "Changing the size of a varchar field requires an ACCESS EXCLUSIVE lock.",
@@ -116,7 +195,11 @@ COMMIT;
                     Help(
                         "Use a text field with a check constraint.",
                     ),
+                    Url(
+                        "https://www.postgresql.org/docs/current/sql-altertable.html",
+                    ),
                 ],
+                snippet: None,
             },
         ]
         "###);
@@ -158,4 +241,44 @@ COMMIT;
         let res = lint_sql(ok_sql);
         assert_eq!(res, vec![]);
     }
+
+    #[test]
+    fn test_max_allowed_length() {
+        let sql = r#"
+CREATE TABLE IF NOT EXISTS "core_bar" ("code" varchar(32) NOT NULL);
+ALTER TABLE "core_bar" ADD COLUMN IF NOT EXISTS "name" varchar(256) NULL;
+"#;
+        assert_eq!(lint_sql(sql).len(), 2);
+
+        let res = lint_sql_with_max_length(sql, 32);
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].span.start, 69);
+
+        assert_eq!(lint_sql_with_max_length(sql, 256), vec![]);
+    }
+
+    #[test]
+    fn test_max_allowed_length_below_and_above() {
+        let ok_sql = r#"
+ALTER TABLE "core_bar" ADD COLUMN "code" varchar(16);
+"#;
+        assert_eq!(lint_sql_with_max_length(ok_sql, 32), vec![]);
+
+        let bad_sql = r#"
+ALTER TABLE "core_bar" ADD COLUMN "code" varchar(33);
+"#;
+        assert_eq!(lint_sql_with_max_length(bad_sql, 32).len(), 1);
+    }
+
+    /// Changing the size of a varchar is reported even within the allowed
+    /// length.
+    #[test]
+    fn test_max_allowed_length_resize() {
+        let sql = r#"
+ALTER TABLE "core_bar" ALTER COLUMN "code" TYPE varchar(16);
+"#;
+        let res = lint_sql_with_max_length(sql, 32);
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].kind, RuleViolationKind::PreferTextField);
+    }
 }
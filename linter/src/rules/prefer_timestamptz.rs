@@ -1,4 +1,5 @@
 use crate::{
+    config::RuleOptions,
     versions::Version,
     violations::{RuleViolation, RuleViolationKind},
 };
@@ -12,6 +13,7 @@ pub fn prefer_timestamptz(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
     let mut errs = vec![];
     for raw_stmt in tree {
@@ -27,7 +29,7 @@ fn check_column_def(errs: &mut Vec<RuleViolation>, raw_stmt: &RawStmt, column_de
         if type_name.string.sval == "timestamp" {
             errs.push(RuleViolation::new(
                 RuleViolationKind::PreferTimestampTz,
-                raw_stmt.into(),
+                raw_stmt,
                 None,
             ));
         }
@@ -108,4 +110,25 @@ alter table app.accounts
   "#;
         assert_eq!(lint_sql(ok_sql), vec![]);
     }
+
+    #[test]
+    fn test_add_column_with_precision() {
+        let bad_sql = r#"
+alter table app.users add column created_ts timestamp(6);
+alter table app.users add column updated_ts timestamp(3) without time zone;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::PreferTimestampTz,
+                RuleViolationKind::PreferTimestampTz
+            ]
+        );
+
+        let ok_sql = r#"
+alter table app.users add column created_ts timestamptz(6);
+alter table app.users add column updated_ts timestamp(3) with time zone;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
 }
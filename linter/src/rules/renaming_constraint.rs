@@ -0,0 +1,70 @@
+use crate::{
+    config::RuleOptions,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind},
+};
+
+use squawk_parser::ast::{ObjectType, RawStmt, Stmt};
+
+#[must_use]
+pub fn renaming_constraint(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            // `RENAME COLUMN` uses the same statement with a different rename
+            // type.
+            Stmt::RenameStmt(stmt) => match stmt.rename_type {
+                ObjectType::Tabconstraint => {
+                    errs.push(RuleViolation::new(
+                        RuleViolationKind::RenamingConstraint,
+                        raw_stmt,
+                        None,
+                    ));
+                }
+                _ => continue,
+            },
+            _ => continue,
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::RenamingConstraint, None, false).unwrap()
+    }
+
+    #[test]
+    fn test_renaming_constraint() {
+        let sql = r#"
+ALTER TABLE "table_name" RENAME CONSTRAINT "constraint_name" TO "new_constraint_name";
+        "#;
+        let res = lint_sql(sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::RenamingConstraint]
+        );
+        assert_eq!(res[0].span.start, 0);
+    }
+
+    #[test]
+    fn test_renaming_column() {
+        let sql = r#"
+ALTER TABLE "table_name" RENAME COLUMN "column_name" TO "new_column_name";
+ALTER TABLE "table_name" RENAME TO "new_table_name";
+        "#;
+        assert_eq!(lint_sql(sql), vec![]);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{
+    config::RuleOptions,
     versions::Version,
     violations::{RuleViolation, RuleViolationKind},
 };
@@ -10,6 +11,7 @@ pub fn renaming_table(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
     let mut errs = vec![];
     for raw_stmt in tree {
@@ -18,7 +20,7 @@ pub fn renaming_table(
                 ObjectType::Table => {
                     errs.push(RuleViolation::new(
                         RuleViolationKind::RenamingTable,
-                        raw_stmt.into(),
+                        raw_stmt,
                         None,
                     ));
                 }
@@ -1,3 +1,4 @@
+use crate::config::RuleOptions;
 use crate::rules::utils::tables_created_in_transaction;
 use crate::versions::Version;
 use crate::violations::{RuleViolation, RuleViolationKind};
@@ -9,6 +10,7 @@ pub fn require_concurrent_index_creation(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
     let tables_created = tables_created_in_transaction(tree, assume_in_transaction);
     let mut errs = vec![];
@@ -20,7 +22,7 @@ pub fn require_concurrent_index_creation(
                 if !stmt.concurrent && !tables_created.contains(tbl_name) {
                     errs.push(RuleViolation::new(
                         RuleViolationKind::RequireConcurrentIndexCreation,
-                        raw_stmt.into(),
+                        raw_stmt,
                         None,
                     ));
                 }
@@ -37,6 +39,7 @@ mod test_rules {
 
     use crate::{
         check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
         violations::{RuleViolation, RuleViolationKind},
     };
 
@@ -109,4 +112,32 @@ CREATE INDEX "core_foo_tenant_id_4d397ef9" ON "core_foo" ("tenant_id");
   "#;
         assert_debug_snapshot!(lint_sql(ok_sql));
     }
+
+    /// The index key doesn't change the lock, so expression, partial and
+    /// multicolumn indexes are reported like plain ones.
+    #[test]
+    fn test_index_key_expressions() {
+        let bad_sql = r#"
+CREATE INDEX "core_user_email_idx" ON "core_user" (lower("email"));
+CREATE INDEX "core_user_active_idx" ON "core_user" ("email") WHERE "deleted_at" IS NULL;
+CREATE UNIQUE INDEX "core_user_tenant_email_idx" ON "core_user" ("tenant_id", lower("email") DESC);
+CREATE INDEX "core_user_tags_idx" ON "core_user" USING gin ("tags") WHERE "tags" <> '{}';
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::RequireConcurrentIndexCreation,
+                RuleViolationKind::RequireConcurrentIndexCreation,
+                RuleViolationKind::RequireConcurrentIndexCreation,
+                RuleViolationKind::RequireConcurrentIndexCreation,
+            ]
+        );
+
+        let ok_sql = r#"
+CREATE INDEX CONCURRENTLY "core_user_email_idx" ON "core_user" (lower("email"));
+CREATE INDEX CONCURRENTLY "core_user_active_idx" ON "core_user" ("email") WHERE "deleted_at" IS NULL;
+CREATE UNIQUE INDEX CONCURRENTLY "core_user_tenant_email_idx" ON "core_user" ("tenant_id", lower("email") DESC);
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
 }
@@ -1,4 +1,5 @@
 use crate::{
+    config::RuleOptions,
     versions::Version,
     violations::{RuleViolation, RuleViolationKind},
 };
@@ -10,6 +11,7 @@ pub fn require_concurrent_index_deletion(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
     let mut errs = vec![];
     for raw_stmt in tree {
@@ -17,7 +19,7 @@ pub fn require_concurrent_index_deletion(
             Stmt::DropStmt(stmt) if !stmt.concurrent && stmt.remove_type == ObjectType::Index => {
                 errs.push(RuleViolation::new(
                     RuleViolationKind::RequireConcurrentIndexDeletion,
-                    raw_stmt.into(),
+                    raw_stmt,
                     None,
                 ));
             }
@@ -31,6 +33,7 @@ pub fn require_concurrent_index_deletion(
 mod test_rules {
     use crate::{
         check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
         violations::{RuleViolation, RuleViolationKind},
     };
     fn lint_sql(sql: &str) -> Vec<RuleViolation> {
@@ -62,6 +65,39 @@ mod test_rules {
         assert_eq!(lint_sql(ok_sql), vec![]);
     }
 
+    /// `DROP INDEX CONCURRENTLY` can't run in a transaction, which is reported
+    /// by ban-concurrent-index-creation-in-transaction rather than this rule.
+    #[test]
+    fn test_drop_index_in_transaction() {
+        let bad_sql = r#"
+BEGIN;
+DROP INDEX "field_name_idx";
+COMMIT;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![RuleViolationKind::RequireConcurrentIndexDeletion]
+        );
+
+        let sql = r#"
+BEGIN;
+DROP INDEX CONCURRENTLY "field_name_idx";
+COMMIT;
+  "#;
+        assert_eq!(lint_sql(sql), vec![]);
+        let res = check_sql_with_rule(
+            sql,
+            &RuleViolationKind::BanConcurrentIndexCreationInTransaction,
+            None,
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::BanConcurrentIndexCreationInTransaction]
+        );
+    }
+
     #[test]
     fn regression_false_positive_drop_type() {
         let sql = r#"
@@ -0,0 +1,111 @@
+use crate::config::RuleOptions;
+use crate::rules::utils::is_concurrent_reindex;
+use crate::versions::Version;
+use crate::violations::{RuleViolation, RuleViolationKind, ViolationMessage};
+
+use squawk_parser::ast::{RawStmt, Stmt};
+
+/// `REINDEX CONCURRENTLY` was added in Postgres 12, so for older versions we
+/// suggest building a replacement index instead.
+fn messages(pg_version: Option<Version>) -> Option<Vec<ViolationMessage>> {
+    let pg_version = pg_version?;
+    if pg_version >= Version::new(12, None, None) {
+        return None;
+    }
+    Some(vec![
+        ViolationMessage::Note(
+            "REINDEX without CONCURRENTLY blocks writes to the table, and reads that use the index, while the index is rebuilt.".into(),
+        ),
+        ViolationMessage::Help(
+            "REINDEX CONCURRENTLY requires Postgres 12+. Create a new index CONCURRENTLY, then drop the old index CONCURRENTLY and rename the new one.".into(),
+        ),
+    ])
+}
+
+#[must_use]
+pub fn require_concurrent_reindex(
+    tree: &[RawStmt],
+    pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            Stmt::ReindexStmt(stmt) if !is_concurrent_reindex(stmt) => {
+                errs.push(RuleViolation::new(
+                    RuleViolationKind::RequireConcurrentReindex,
+                    raw_stmt,
+                    messages(pg_version),
+                ));
+            }
+            _ => continue,
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use std::str::FromStr;
+
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        versions::Version,
+        violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+    };
+
+    fn lint_sql(sql: &str, pg_version: Option<Version>) -> Vec<RuleViolation> {
+        check_sql_with_rule(
+            sql,
+            &RuleViolationKind::RequireConcurrentReindex,
+            pg_version,
+            false,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_reindex() {
+        let bad_sql = r#"
+REINDEX INDEX "core_recipe_foo_idx";
+REINDEX TABLE "core_recipe";
+REINDEX DATABASE "app";
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql, None)),
+            vec![
+                RuleViolationKind::RequireConcurrentReindex,
+                RuleViolationKind::RequireConcurrentReindex,
+                RuleViolationKind::RequireConcurrentReindex,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reindex_concurrently() {
+        let ok_sql = r#"
+REINDEX INDEX CONCURRENTLY "core_recipe_foo_idx";
+REINDEX TABLE CONCURRENTLY "core_recipe";
+REINDEX (VERBOSE) DATABASE CONCURRENTLY "app";
+  "#;
+        assert_eq!(lint_sql(ok_sql, None), vec![]);
+    }
+
+    #[test]
+    fn test_pg_version() {
+        let sql = r#"REINDEX INDEX "core_recipe_foo_idx";"#;
+        let pg_version_12 = Some(Version::from_str("12").unwrap());
+        assert_eq!(
+            lint_sql(sql, pg_version_12)[0].messages[1],
+            ViolationMessage::Help("Use REINDEX CONCURRENTLY, which requires Postgres 12+.".into())
+        );
+
+        let pg_version_11 = Some(Version::from_str("11").unwrap());
+        assert_eq!(
+            lint_sql(sql, pg_version_11)[0].messages[1],
+            ViolationMessage::Help("REINDEX CONCURRENTLY requires Postgres 12+. Create a new index CONCURRENTLY, then drop the old index CONCURRENTLY and rename the new one.".into())
+        );
+    }
+}
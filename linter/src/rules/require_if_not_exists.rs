@@ -0,0 +1,286 @@
+use std::collections::HashSet;
+
+use crate::{
+    config::RuleOptions,
+    rules::utils::TransactionTracker,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+};
+
+use serde_json::Value;
+use squawk_parser::ast::{RawStmt, Stmt};
+
+const CREATE_TABLE: &str = "create-table";
+const CREATE_INDEX: &str = "create-index";
+const CREATE_EXTENSION: &str = "create-extension";
+
+/// The statement kinds to check, from the `statements` option, e.g.
+/// `["create-table"]`. Defaults to every kind.
+fn checked_statements(rule_options: &RuleOptions) -> HashSet<&str> {
+    match rule_options.get("statements").and_then(Value::as_array) {
+        Some(statements) => statements.iter().filter_map(Value::as_str).collect(),
+        None => HashSet::from([CREATE_TABLE, CREATE_INDEX, CREATE_EXTENSION]),
+    }
+}
+
+/// If a migration fails part way through outside of a transaction, the
+/// statements that ran aren't reverted, so rerunning the migration fails on
+/// the objects that already exist.
+///
+/// Extensions are reported even in a transaction, since they're shared by the
+/// whole database and often created outside of migrations.
+#[must_use]
+pub fn require_if_not_exists(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    assume_in_transaction: bool,
+    rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let mut errs = vec![];
+    // a single statement can't be partially applied.
+    let single_statement = tree.len() == 1;
+    let statements = checked_statements(rule_options);
+    let mut transaction = TransactionTracker::new(assume_in_transaction);
+    for raw_stmt in tree {
+        transaction.update(&raw_stmt.stmt);
+        if let Stmt::CreateExtensionStmt(stmt) = &raw_stmt.stmt {
+            if statements.contains(CREATE_EXTENSION) && stmt["if_not_exists"] != true {
+                errs.push(RuleViolation::new(
+                    RuleViolationKind::RequireIfNotExists,
+                    raw_stmt,
+                    Some(vec![
+                        ViolationMessage::Note(format!(
+                            r#"Extension "{}" may already exist, e.g. if it was installed by another application or by a superuser ahead of the migration. Creating an extension usually requires superuser privileges, and some extensions can't be created in a transaction."#,
+                            stmt["extname"].as_str().unwrap_or_default()
+                        )),
+                        ViolationMessage::Help(
+                            "Use CREATE EXTENSION IF NOT EXISTS so the migration can be rerun.".into(),
+                        ),
+                    ]),
+                ));
+            }
+            continue;
+        }
+        if single_statement {
+            continue;
+        }
+        let missing_if_not_exists = match &raw_stmt.stmt {
+            Stmt::CreateStmt(stmt) if statements.contains(CREATE_TABLE) => {
+                !stmt.if_not_exists && !transaction.inside_transaction()
+            }
+            // CREATE INDEX CONCURRENTLY can't run in a transaction.
+            Stmt::IndexStmt(stmt) if statements.contains(CREATE_INDEX) => {
+                !stmt.if_not_exists && (stmt.concurrent || !transaction.inside_transaction())
+            }
+            _ => false,
+        };
+        if missing_if_not_exists {
+            errs.push(RuleViolation::new(
+                RuleViolationKind::RequireIfNotExists,
+                raw_stmt,
+                None,
+            ));
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql, check_sql_with_rule,
+        config::{CheckSqlConfig, RuleOptions},
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+    };
+    use serde_json::json;
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::RequireIfNotExists, None, false).unwrap()
+    }
+
+    fn lint_sql_assuming_in_transaction(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::RequireIfNotExists, None, true).unwrap()
+    }
+
+    fn lint_sql_with_statements(sql: &str, statements: &[&str]) -> Vec<RuleViolation> {
+        let mut options = RuleOptions::new();
+        options.insert("statements".into(), json!(statements));
+        let mut config = CheckSqlConfig::default();
+        config
+            .rules
+            .insert(RuleViolationKind::RequireIfNotExists, options);
+        check_sql(sql, &config)
+            .unwrap()
+            .into_iter()
+            .filter(|v| v.kind == RuleViolationKind::RequireIfNotExists)
+            .collect()
+    }
+
+    #[test]
+    fn test_create_table_without_if_not_exists() {
+        let bad_sql = r#"
+CREATE TABLE "core_foo" ("id" bigint);
+CREATE TABLE "core_bar" ("id" bigint);
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::RequireIfNotExists,
+                RuleViolationKind::RequireIfNotExists,
+            ]
+        );
+    }
+
+    /// The rule is opt-in, since `prefer-robust-stmts` already reports a
+    /// missing IF NOT EXISTS with the default rules.
+    #[test]
+    fn test_opt_in() {
+        let bad_sql = r#"
+CREATE TABLE "core_foo" ("id" bigint PRIMARY KEY);
+CREATE INDEX CONCURRENTLY "core_foo_id_idx" ON "core_foo" ("id");
+  "#;
+        let lint_kinds = |config: &CheckSqlConfig| -> Vec<(RuleViolationKind, i32)> {
+            check_sql(bad_sql, config)
+                .unwrap()
+                .into_iter()
+                .filter(|v| {
+                    matches!(
+                        v.kind,
+                        RuleViolationKind::RequireIfNotExists
+                            | RuleViolationKind::PreferRobustStmts
+                    )
+                })
+                .map(|v| (v.kind, v.span.start))
+                .collect()
+        };
+        assert_eq!(
+            lint_kinds(&CheckSqlConfig::default()),
+            vec![
+                (RuleViolationKind::PreferRobustStmts, 0),
+                (RuleViolationKind::PreferRobustStmts, 51),
+            ]
+        );
+
+        let mut config = CheckSqlConfig::default();
+        config
+            .rules
+            .insert(RuleViolationKind::RequireIfNotExists, RuleOptions::new());
+        assert_eq!(
+            lint_kinds(&config),
+            vec![
+                (RuleViolationKind::PreferRobustStmts, 0),
+                (RuleViolationKind::RequireIfNotExists, 0),
+                (RuleViolationKind::PreferRobustStmts, 51),
+                (RuleViolationKind::RequireIfNotExists, 51),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_create_table_with_if_not_exists() {
+        let ok_sql = r#"
+CREATE TABLE IF NOT EXISTS "core_foo" ("id" bigint);
+CREATE TABLE IF NOT EXISTS "core_bar" ("id" bigint);
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_create_index() {
+        let bad_sql = r#"
+CREATE INDEX "foo_idx" ON "core_foo" ("bar");
+CREATE INDEX IF NOT EXISTS "bar_idx" ON "core_foo" ("bar");
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![RuleViolationKind::RequireIfNotExists]
+        );
+    }
+
+    #[test]
+    fn test_in_transaction() {
+        let ok_sql = r#"
+BEGIN;
+CREATE TABLE "core_foo" ("id" bigint);
+CREATE INDEX "foo_idx" ON "core_foo" ("id");
+COMMIT;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+
+        let ok_sql = r#"
+CREATE TABLE "core_foo" ("id" bigint);
+CREATE INDEX "foo_idx" ON "core_foo" ("id");
+  "#;
+        assert_eq!(lint_sql_assuming_in_transaction(ok_sql), vec![]);
+
+        // the concurrent index has to run outside of the transaction.
+        let bad_sql = r#"
+CREATE TABLE "core_foo" ("id" bigint);
+CREATE INDEX CONCURRENTLY "foo_idx" ON "core_foo" ("id");
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql_assuming_in_transaction(bad_sql)),
+            vec![RuleViolationKind::RequireIfNotExists]
+        );
+    }
+
+    #[test]
+    fn test_single_statement() {
+        let ok_sql = r#"
+CREATE TABLE "core_foo" ("id" bigint);
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_create_extension() {
+        let bad_sql = r#"
+CREATE EXTENSION "pg_trgm";
+  "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::RequireIfNotExists]
+        );
+        assert_eq!(
+            res[0].messages[1],
+            ViolationMessage::Help(
+                "Use CREATE EXTENSION IF NOT EXISTS so the migration can be rerun.".into()
+            )
+        );
+
+        // extensions are shared by the database, so a transaction doesn't help.
+        let bad_sql = r#"
+BEGIN;
+CREATE EXTENSION "pg_trgm";
+COMMIT;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![RuleViolationKind::RequireIfNotExists]
+        );
+
+        let ok_sql = r#"
+CREATE EXTENSION IF NOT EXISTS "pg_trgm";
+CREATE EXTENSION IF NOT EXISTS "citext" WITH SCHEMA "public";
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_statements_option() {
+        let sql = r#"
+CREATE TABLE "core_foo" ("id" bigint);
+CREATE INDEX "foo_idx" ON "core_foo" ("id");
+  "#;
+        let res = lint_sql_with_statements(sql, &["create-index"]);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::RequireIfNotExists]
+        );
+        assert_eq!(res[0].span.start, 39);
+
+        assert_eq!(lint_sql_with_statements(sql, &[]), vec![]);
+    }
+}
@@ -0,0 +1,194 @@
+use crate::config::RuleOptions;
+use crate::rules::utils::{
+    is_zero_timeout, strong_lock_level, tables_created_in_transaction, TransactionChange,
+    TransactionTracker,
+};
+use crate::versions::Version;
+use crate::violations::{RuleViolation, RuleViolationKind};
+
+use serde_json::Value;
+use squawk_parser::ast::{RawStmt, Stmt};
+
+/// How a `SET` statement changes `lock_timeout`, `None` if it's for a
+/// different setting.
+fn lock_timeout_change(stmt: &Value) -> Option<LockTimeout> {
+    if stmt["name"].as_str() != Some("lock_timeout") {
+        return None;
+    }
+    if stmt["kind"].as_str() != Some("VAR_SET_VALUE") {
+        return Some(LockTimeout::Unset);
+    }
+    match stmt["args"].get(0) {
+        Some(arg) if !is_zero_timeout(arg) => {
+            if stmt["is_local"].as_bool().unwrap_or(false) {
+                Some(LockTimeout::Local)
+            } else {
+                Some(LockTimeout::Session)
+            }
+        }
+        _ => Some(LockTimeout::Unset),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LockTimeout {
+    Unset,
+    /// `SET LOCAL`, which only lasts until the end of the transaction.
+    Local,
+    Session,
+}
+
+#[must_use]
+pub fn require_lock_timeout(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let tables_created = tables_created_in_transaction(tree, assume_in_transaction);
+    let mut lock_timeout = LockTimeout::Unset;
+    let mut transaction = TransactionTracker::new(assume_in_transaction);
+    for raw_stmt in tree {
+        let change = transaction.update(&raw_stmt.stmt);
+        match &raw_stmt.stmt {
+            Stmt::VariableSetStmt(stmt) => {
+                if let Some(change) = lock_timeout_change(stmt) {
+                    lock_timeout = change;
+                }
+            }
+            Stmt::TransactionStmt(_) => {
+                if matches!(
+                    change,
+                    Some(TransactionChange::Commit | TransactionChange::Rollback)
+                ) && lock_timeout == LockTimeout::Local
+                {
+                    lock_timeout = LockTimeout::Unset;
+                }
+            }
+            stmt => {
+                // only report the first statement, setting the timeout at the
+                // top of the file fixes every statement.
+                if lock_timeout == LockTimeout::Unset
+                    && strong_lock_level(stmt, &tables_created).is_some()
+                {
+                    return vec![RuleViolation::new(
+                        RuleViolationKind::RequireLockTimeout,
+                        raw_stmt,
+                        None,
+                    )];
+                }
+            }
+        }
+    }
+    vec![]
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::RequireLockTimeout, None, false).unwrap()
+    }
+
+    #[test]
+    fn test_missing_lock_timeout() {
+        let bad_sql = r#"
+ALTER TABLE "core_foo" ADD COLUMN "bar" integer;
+CREATE INDEX "bar_idx" ON "core_foo" ("bar");
+  "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::RequireLockTimeout]
+        );
+        assert_eq!(res[0].span.start, 0);
+    }
+
+    #[test]
+    fn test_lock_timeout_after_ddl() {
+        let bad_sql = r#"
+CREATE INDEX "bar_idx" ON "core_foo" ("bar");
+SET lock_timeout = '2s';
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![RuleViolationKind::RequireLockTimeout]
+        );
+    }
+
+    #[test]
+    fn test_lock_timeout_set() {
+        let ok_sql = r#"
+SET lock_timeout = '2s';
+ALTER TABLE "core_foo" ADD COLUMN "bar" integer;
+DROP INDEX "bar_idx";
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+
+        let ok_sql = r#"
+BEGIN;
+SET LOCAL lock_timeout = 2000;
+ALTER TABLE "core_foo" ADD COLUMN "bar" integer;
+COMMIT;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_lock_timeout_disabled() {
+        let bad_sql = r#"
+SET lock_timeout = '2s';
+SET lock_timeout = 0;
+ALTER TABLE "core_foo" ADD COLUMN "bar" integer;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![RuleViolationKind::RequireLockTimeout]
+        );
+
+        let bad_sql = r#"
+SET lock_timeout = '2s';
+RESET lock_timeout;
+ALTER TABLE "core_foo" ADD COLUMN "bar" integer;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![RuleViolationKind::RequireLockTimeout]
+        );
+    }
+
+    /// `SET LOCAL` only applies until the end of the transaction.
+    #[test]
+    fn test_local_lock_timeout_after_commit() {
+        let bad_sql = r#"
+BEGIN;
+SET LOCAL lock_timeout = '2s';
+COMMIT;
+ALTER TABLE "core_foo" ADD COLUMN "bar" integer;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![RuleViolationKind::RequireLockTimeout]
+        );
+    }
+
+    #[test]
+    fn test_safe_statements() {
+        let ok_sql = r#"
+CREATE INDEX CONCURRENTLY "bar_idx" ON "core_foo" ("bar");
+DROP INDEX CONCURRENTLY "bar_idx";
+BEGIN;
+CREATE TABLE "core_bar" ("id" bigint);
+ALTER TABLE "core_bar" ADD COLUMN "foo" integer;
+CREATE INDEX "foo_idx" ON "core_bar" ("foo");
+COMMIT;
+SELECT 1;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::HashSet;
+
+use crate::{
+    config::RuleOptions,
+    rules::utils::{has_primary_key, may_inherit_primary_key, TEMPORARY},
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+};
+
+use serde_json::Value;
+use squawk_parser::ast::{
+    AlterTableCmds, AlterTableDef, AlterTableType, ConstrType, RawStmt, Stmt,
+};
+
+/// `relpersistence` for `CREATE UNLOGGED TABLE`, which can't be published.
+const UNLOGGED: &str = "u";
+
+/// The `identity_type` of `REPLICA IDENTITY NOTHING`.
+const IDENTITY_NOTHING: &str = "n";
+/// `REPLICA IDENTITY FULL` and `REPLICA IDENTITY USING INDEX`, which don't
+/// need a primary key.
+const IDENTITY_FULL: &str = "f";
+const IDENTITY_INDEX: &str = "i";
+
+/// Tables given a primary key or a replica identity that doesn't need one
+/// later in the file.
+fn tables_with_identity(tree: &[RawStmt]) -> HashSet<&str> {
+    let mut tables = HashSet::new();
+    for raw_stmt in tree {
+        if let Stmt::AlterTableStmt(stmt) = &raw_stmt.stmt {
+            for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
+                let has_identity = match &cmd.def {
+                    Some(AlterTableDef::Constraint(constraint)) => {
+                        cmd.subtype == AlterTableType::AddConstraint
+                            && constraint.contype == ConstrType::Primary
+                    }
+                    Some(AlterTableDef::ReplicaIdentityStmt(identity)) => matches!(
+                        identity["identity_type"].as_str(),
+                        Some(IDENTITY_FULL | IDENTITY_INDEX)
+                    ),
+                    _ => false,
+                };
+                if has_identity {
+                    tables.insert(stmt.relation.relname.as_str());
+                }
+            }
+        }
+    }
+    tables
+}
+
+/// Logical replication needs a replica identity to replicate updates and
+/// deletes, so they fail on published tables without one.
+///
+/// `REPLICA IDENTITY NOTHING` is always reported. With the
+/// `logical_replication` option, new tables without a primary key are too.
+#[must_use]
+pub fn require_replica_identity(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let logical_replication = rule_options
+        .get("logical_replication")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let tables_with_identity = tables_with_identity(tree);
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            Stmt::AlterTableStmt(stmt) => {
+                for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
+                    if let Some(AlterTableDef::ReplicaIdentityStmt(identity)) = &cmd.def {
+                        if identity["identity_type"].as_str() == Some(IDENTITY_NOTHING) {
+                            errs.push(RuleViolation::new(
+                                RuleViolationKind::RequireReplicaIdentity,
+                                raw_stmt,
+                                None,
+                            ));
+                        }
+                    }
+                }
+            }
+            Stmt::CreateStmt(stmt)
+                if logical_replication
+                    && stmt.relation.relpersistence != TEMPORARY
+                    && stmt.relation.relpersistence != UNLOGGED
+                    && !may_inherit_primary_key(stmt)
+                    && !has_primary_key(stmt)
+                    && !tables_with_identity.contains(stmt.relation.relname.as_str()) =>
+            {
+                errs.push(RuleViolation::new(
+                    RuleViolationKind::RequireReplicaIdentity,
+                    raw_stmt,
+                    Some(vec![
+                        ViolationMessage::Note(format!(
+                            r#"Table "{}" doesn't have a primary key, so updates and deletes fail once it's published for logical replication."#,
+                            stmt.relation.relname
+                        )),
+                        ViolationMessage::Help(
+                            "Add a primary key, or set REPLICA IDENTITY USING INDEX with a unique index on NOT NULL columns.".into(),
+                        ),
+                    ]),
+                ));
+            }
+            _ => continue,
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql, check_sql_with_rule,
+        config::{CheckSqlConfig, RuleOptions},
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind},
+    };
+    use serde_json::json;
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::RequireReplicaIdentity, None, false).unwrap()
+    }
+
+    fn lint_sql_with_logical_replication(sql: &str) -> Vec<RuleViolation> {
+        let mut options = RuleOptions::new();
+        options.insert("logical_replication".into(), json!(true));
+        let mut config = CheckSqlConfig::default();
+        config
+            .rules
+            .insert(RuleViolationKind::RequireReplicaIdentity, options);
+        check_sql(sql, &config)
+            .unwrap()
+            .into_iter()
+            .filter(|v| v.kind == RuleViolationKind::RequireReplicaIdentity)
+            .collect()
+    }
+
+    #[test]
+    fn test_replica_identity_nothing() {
+        let bad_sql = r#"
+ALTER TABLE "core_recipe" REPLICA IDENTITY NOTHING;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![RuleViolationKind::RequireReplicaIdentity]
+        );
+
+        let ok_sql = r#"
+ALTER TABLE "core_recipe" REPLICA IDENTITY DEFAULT;
+ALTER TABLE "core_recipe" REPLICA IDENTITY FULL;
+ALTER TABLE "core_recipe" REPLICA IDENTITY USING INDEX "core_recipe_uuid_key";
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_table_without_primary_key() {
+        let sql = r#"
+CREATE TABLE "core_event" ("id" bigint, "name" text);
+  "#;
+        // tables are only checked when the database uses logical replication.
+        assert_eq!(lint_sql(sql), vec![]);
+        assert_eq!(
+            violations_to_kinds(&lint_sql_with_logical_replication(sql)),
+            vec![RuleViolationKind::RequireReplicaIdentity]
+        );
+
+        let ok_sql = r#"
+CREATE TABLE "core_recipe" ("id" bigint PRIMARY KEY);
+CREATE TABLE "core_event" ("id" bigint NOT NULL, "name" text);
+CREATE UNIQUE INDEX "core_event_id_key" ON "core_event" ("id");
+ALTER TABLE "core_event" REPLICA IDENTITY USING INDEX "core_event_id_key";
+CREATE TABLE "core_log" ("message" text);
+ALTER TABLE "core_log" ADD PRIMARY KEY ("message");
+CREATE TEMPORARY TABLE "import" ("name" text);
+CREATE UNLOGGED TABLE "cache" ("key" text);
+  "#;
+        assert_eq!(lint_sql_with_logical_replication(ok_sql), vec![]);
+    }
+}
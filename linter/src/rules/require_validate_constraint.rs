@@ -0,0 +1,142 @@
+use std::collections::HashSet;
+
+use crate::{
+    config::RuleOptions,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind},
+};
+
+use squawk_parser::ast::{AlterTableCmds, AlterTableDef, AlterTableType, RawStmt, Stmt};
+
+/// The `(table, constraint)` pairs validated anywhere in the file.
+fn validated_constraints(tree: &[RawStmt]) -> HashSet<(&str, &str)> {
+    let mut validated = HashSet::new();
+    for raw_stmt in tree {
+        if let Stmt::AlterTableStmt(stmt) = &raw_stmt.stmt {
+            for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
+                if cmd.subtype == AlterTableType::ValidateConstraint {
+                    if let Some(name) = &cmd.name {
+                        validated.insert((stmt.relation.relname.as_str(), name.as_str()));
+                    }
+                }
+            }
+        }
+    }
+    validated
+}
+
+/// A `NOT VALID` constraint is only enforced for new rows until it's
+/// validated, which often happens in a later migration, so this rule is
+/// opt-in and informational.
+#[must_use]
+pub fn require_validate_constraint(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let validated = validated_constraints(tree);
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        if let Stmt::AlterTableStmt(stmt) = &raw_stmt.stmt {
+            for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
+                let Some(AlterTableDef::Constraint(constraint)) = &cmd.def else {
+                    continue;
+                };
+                if cmd.subtype != AlterTableType::AddConstraint || constraint.initially_valid {
+                    continue;
+                }
+                // unnamed constraints can't be matched to a VALIDATE.
+                let is_validated = constraint.conname.as_ref().map_or(false, |name| {
+                    validated.contains(&(stmt.relation.relname.as_str(), name.as_str()))
+                });
+                if !is_validated {
+                    errs.push(RuleViolation::new(
+                        RuleViolationKind::RequireValidateConstraint,
+                        raw_stmt,
+                        None,
+                    ));
+                }
+            }
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql, check_sql_with_rule,
+        config::{CheckSqlConfig, RuleOptions},
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind, Severity},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(
+            sql,
+            &RuleViolationKind::RequireValidateConstraint,
+            None,
+            false,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_missing_validate() {
+        let bad_sql = r#"
+ALTER TABLE "core_foo" ADD CONSTRAINT "positive_bar" CHECK ("bar" > 0) NOT VALID;
+ALTER TABLE "core_foo" ADD CONSTRAINT "fk_baz" FOREIGN KEY ("baz_id") REFERENCES "core_baz" ("id") NOT VALID;
+ALTER TABLE "core_foo" VALIDATE CONSTRAINT "positive_bar";
+-- the same constraint name on a different table.
+ALTER TABLE "core_qux" ADD CONSTRAINT "positive_bar" CHECK ("bar" > 0) NOT VALID;
+  "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![
+                RuleViolationKind::RequireValidateConstraint,
+                RuleViolationKind::RequireValidateConstraint,
+            ]
+        );
+        assert_eq!(res[0].severity, Severity::Info);
+    }
+
+    #[test]
+    fn test_validate_present() {
+        let ok_sql = r#"
+ALTER TABLE "core_foo" ADD CONSTRAINT "positive_bar" CHECK ("bar" > 0) NOT VALID;
+SELECT 1;
+ALTER TABLE "core_foo" VALIDATE CONSTRAINT "positive_bar";
+ALTER TABLE "core_foo" ADD CONSTRAINT "positive_baz" CHECK ("baz" > 0);
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    /// The rule only runs when it's configured.
+    #[test]
+    fn test_opt_in() {
+        let sql = r#"
+ALTER TABLE "core_foo" ADD CONSTRAINT "positive_bar" CHECK ("bar" > 0) NOT VALID;
+  "#;
+        let kinds = |config: &CheckSqlConfig| -> Vec<RuleViolationKind> {
+            let res: Vec<RuleViolation> = check_sql(sql, config)
+                .unwrap()
+                .into_iter()
+                .filter(|v| v.kind == RuleViolationKind::RequireValidateConstraint)
+                .collect();
+            violations_to_kinds(&res)
+        };
+        let mut config = CheckSqlConfig::default();
+        assert_eq!(kinds(&config), vec![]);
+
+        config.rules.insert(
+            RuleViolationKind::RequireValidateConstraint,
+            RuleOptions::new(),
+        );
+        assert_eq!(
+            kinds(&config),
+            vec![RuleViolationKind::RequireValidateConstraint]
+        );
+    }
+}
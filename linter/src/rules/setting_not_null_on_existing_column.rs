@@ -0,0 +1,268 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::config::RuleOptions;
+use crate::rules::utils::{tables_created_in_transaction, TransactionTracker};
+use crate::versions::Version;
+use crate::violations::{RuleViolation, RuleViolationKind};
+
+use squawk_parser::ast::{
+    AlterTableCmds, AlterTableDef, AlterTableType, ConstrType, Constraint, RawStmt, Stmt,
+};
+
+/// The column of a `CHECK ("col" IS NOT NULL)` constraint.
+fn not_null_check_column(constraint: &Constraint) -> Option<&str> {
+    if constraint.contype != ConstrType::Check {
+        return None;
+    }
+    let null_test = &constraint.raw_expr.as_ref()?["NullTest"];
+    if null_test["nulltesttype"].as_str() != Some("IS_NOT_NULL") {
+        return None;
+    }
+    null_test["arg"]["ColumnRef"]["fields"].as_array()?.last()?["String"]["sval"].as_str()
+}
+
+/// Columns added by `ALTER TABLE ... ADD COLUMN` inside a transaction, keyed
+/// by table and column name.
+fn columns_added_in_transaction(
+    tree: &[RawStmt],
+    assume_in_transaction: bool,
+) -> HashSet<(String, String)> {
+    let mut added_columns = HashSet::new();
+    let mut transaction = TransactionTracker::new(assume_in_transaction);
+    for raw_stmt in tree {
+        transaction.update(&raw_stmt.stmt);
+        match &raw_stmt.stmt {
+            Stmt::AlterTableStmt(stmt) if transaction.inside_transaction() => {
+                for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
+                    if cmd.subtype != AlterTableType::AddColumn {
+                        continue;
+                    }
+                    if let Some(AlterTableDef::ColumnDef(column_def)) = &cmd.def {
+                        if let Some(colname) = &column_def.colname {
+                            added_columns.insert((stmt.relation.relname.clone(), colname.clone()));
+                        }
+                    }
+                }
+            }
+            _ => continue,
+        }
+    }
+    added_columns
+}
+
+/// `SET NOT NULL` scans the table to check for nulls, unless the table or
+/// column is new, in which case there is nothing to scan. Postgres 12+ also
+/// skips the scan when the column has a valid `CHECK ("col" IS NOT NULL)`
+/// constraint.
+#[must_use]
+pub fn setting_not_null_on_existing_column(
+    tree: &[RawStmt],
+    pg_version: Option<Version>,
+    assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let tables_created = tables_created_in_transaction(tree, assume_in_transaction);
+    let columns_added = columns_added_in_transaction(tree, assume_in_transaction);
+    let uses_check_constraints = pg_version.map_or(true, |pg_version| {
+        pg_version >= Version::new(12, None, None)
+    });
+    // `NOT VALID` checks keyed by table and constraint name, waiting for a
+    // `VALIDATE CONSTRAINT`.
+    let mut not_valid_checks: HashMap<(String, String), String> = HashMap::new();
+    let mut checked_columns: HashSet<(String, String)> = HashSet::new();
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        match &raw_stmt.stmt {
+            Stmt::AlterTableStmt(stmt) => {
+                let table_name = &stmt.relation.relname;
+                if tables_created.contains(table_name) {
+                    continue;
+                }
+                for AlterTableCmds::AlterTableCmd(cmd) in &stmt.cmds {
+                    match (&cmd.subtype, &cmd.def, &cmd.name) {
+                        (
+                            AlterTableType::AddConstraint,
+                            Some(AlterTableDef::Constraint(constraint)),
+                            _,
+                        ) => {
+                            if let Some(colname) = not_null_check_column(constraint) {
+                                let column = (table_name.clone(), colname.to_string());
+                                if constraint.initially_valid {
+                                    checked_columns.insert(column);
+                                } else if let Some(conname) = &constraint.conname {
+                                    not_valid_checks
+                                        .insert((table_name.clone(), conname.clone()), column.1);
+                                }
+                            }
+                            continue;
+                        }
+                        (AlterTableType::ValidateConstraint, _, Some(conname)) => {
+                            if let Some(colname) =
+                                not_valid_checks.remove(&(table_name.clone(), conname.clone()))
+                            {
+                                checked_columns.insert((table_name.clone(), colname));
+                            }
+                            continue;
+                        }
+                        (AlterTableType::SetNotNull, _, _) => {}
+                        _ => continue,
+                    }
+                    let is_new_column = cmd.name.as_ref().map_or(false, |colname| {
+                        columns_added.contains(&(table_name.clone(), colname.clone()))
+                    });
+                    let is_checked_column = uses_check_constraints
+                        && cmd.name.as_ref().map_or(false, |colname| {
+                            checked_columns.contains(&(table_name.clone(), colname.clone()))
+                        });
+                    if !is_new_column && !is_checked_column {
+                        errs.push(RuleViolation::new(
+                            RuleViolationKind::SettingNotNullOnExistingColumn,
+                            raw_stmt,
+                            None,
+                        ));
+                    }
+                }
+            }
+            _ => continue,
+        }
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use std::str::FromStr;
+
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        versions::Version,
+        violations::{RuleViolation, RuleViolationKind},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(
+            sql,
+            &RuleViolationKind::SettingNotNullOnExistingColumn,
+            None,
+            false,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_set_not_null() {
+        let bad_sql = r#"
+ALTER TABLE "core_recipe" ALTER COLUMN "foo" SET NOT NULL;
+  "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::SettingNotNullOnExistingColumn]
+        );
+        assert_eq!(res[0].span.start, 0);
+    }
+
+    #[test]
+    fn test_set_not_null_multiple_columns() {
+        let bad_sql = r#"
+ALTER TABLE "core_recipe" ALTER COLUMN "foo" SET NOT NULL, ALTER COLUMN "bar" SET NOT NULL;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::SettingNotNullOnExistingColumn,
+                RuleViolationKind::SettingNotNullOnExistingColumn,
+            ]
+        );
+    }
+
+    /// The validated constraint lets Postgres 12+ skip the scan.
+    #[test]
+    fn test_set_not_null_after_check_constraint() {
+        let ok_sql = r#"
+ALTER TABLE "core_recipe" ADD CONSTRAINT "foo_not_null" CHECK ("foo" IS NOT NULL) NOT VALID;
+ALTER TABLE "core_recipe" VALIDATE CONSTRAINT "foo_not_null";
+ALTER TABLE "core_recipe" ALTER COLUMN "foo" SET NOT NULL;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+
+        let bad_sql = r#"
+ALTER TABLE "core_recipe" ADD CONSTRAINT "foo_not_null" CHECK ("foo" IS NOT NULL) NOT VALID;
+ALTER TABLE "core_recipe" ALTER COLUMN "foo" SET NOT NULL;
+ALTER TABLE "core_recipe" ADD CONSTRAINT "bar_not_null" CHECK ("bar" IS NOT NULL) NOT VALID;
+ALTER TABLE "core_recipe" VALIDATE CONSTRAINT "bar_not_null";
+ALTER TABLE "core_recipe" ALTER COLUMN "foo" SET NOT NULL;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::SettingNotNullOnExistingColumn,
+                RuleViolationKind::SettingNotNullOnExistingColumn,
+            ]
+        );
+    }
+
+    /// Before Postgres 12, `SET NOT NULL` scans the table whatever the
+    /// constraints are.
+    #[test]
+    fn test_set_not_null_after_check_constraint_before_pg_12() {
+        let bad_sql = r#"
+ALTER TABLE "core_recipe" ADD CONSTRAINT "foo_not_null" CHECK ("foo" IS NOT NULL) NOT VALID;
+ALTER TABLE "core_recipe" VALIDATE CONSTRAINT "foo_not_null";
+ALTER TABLE "core_recipe" ALTER COLUMN "foo" SET NOT NULL;
+  "#;
+        let res = check_sql_with_rule(
+            bad_sql,
+            &RuleViolationKind::SettingNotNullOnExistingColumn,
+            Some(Version::from_str("11.0.0").unwrap()),
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::SettingNotNullOnExistingColumn]
+        );
+    }
+
+    #[test]
+    fn test_set_not_null_on_new_column() {
+        let ok_sql = r#"
+BEGIN;
+ALTER TABLE "core_recipe" ADD COLUMN "foo" integer;
+ALTER TABLE "core_recipe" ALTER COLUMN "foo" SET NOT NULL;
+COMMIT;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+
+        let ok_sql = r#"
+BEGIN;
+CREATE TABLE "core_bar" ("id" bigint, "foo" integer);
+ALTER TABLE "core_bar" ALTER COLUMN "foo" SET NOT NULL;
+COMMIT;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+
+        let ok_sql = r#"
+START TRANSACTION;
+CREATE TABLE "core_bar" ("id" bigint, "foo" integer);
+ALTER TABLE "core_bar" ALTER COLUMN "foo" SET NOT NULL;
+COMMIT;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    /// Once the transaction is committed other rows can be written, so the
+    /// column has to be scanned.
+    #[test]
+    fn test_set_not_null_on_column_added_outside_transaction() {
+        let bad_sql = r#"
+ALTER TABLE "core_recipe" ADD COLUMN "foo" integer;
+ALTER TABLE "core_recipe" ALTER COLUMN "foo" SET NOT NULL;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![RuleViolationKind::SettingNotNullOnExistingColumn]
+        );
+    }
+}
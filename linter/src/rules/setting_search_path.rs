@@ -0,0 +1,127 @@
+use crate::{
+    config::RuleOptions,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+};
+
+use squawk_parser::ast::{RangeVar, RawStmt, Stmt};
+
+/// The table changed by the statement when it isn't schema qualified.
+fn unqualified_relation(stmt: &Stmt) -> Option<&str> {
+    let relation: &RangeVar = match stmt {
+        Stmt::CreateStmt(stmt) => &stmt.relation,
+        Stmt::AlterTableStmt(stmt) => &stmt.relation,
+        Stmt::IndexStmt(stmt) => &stmt.relation,
+        _ => return None,
+    };
+    if relation.schemaname.is_some() {
+        return None;
+    }
+    Some(&relation.relname)
+}
+
+/// Changing `search_path` partway through a migration changes which schema
+/// the unqualified names after it resolve to, which is easy to miss when
+/// reading the statements. Reported on the `SET` or `RESET`, when a later
+/// statement changes a table by an unqualified name.
+#[must_use]
+pub fn setting_search_path(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    _assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let mut errs = vec![];
+    for (idx, raw_stmt) in tree.iter().enumerate() {
+        let Stmt::VariableSetStmt(stmt) = &raw_stmt.stmt else {
+            continue;
+        };
+        if stmt["name"].as_str() != Some("search_path") {
+            continue;
+        }
+        let Some(name) = tree[idx + 1..]
+            .iter()
+            .find_map(|raw_stmt| unqualified_relation(&raw_stmt.stmt))
+        else {
+            continue;
+        };
+        errs.push(RuleViolation::new(
+            RuleViolationKind::SettingSearchPath,
+            raw_stmt,
+            Some(vec![
+                ViolationMessage::Note(format!(
+                    r#"search_path is changed before "{name}" is used without a schema, so it may resolve to a different schema than expected."#
+                )),
+                ViolationMessage::Help(
+                    "Qualify the names with their schema, or set search_path outside of the migration.".into(),
+                ),
+            ]),
+        ));
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(sql, &RuleViolationKind::SettingSearchPath, None, false).unwrap()
+    }
+
+    #[test]
+    fn test_set_search_path_then_create_table() {
+        let bad_sql = r#"
+SET search_path = "app", "public";
+CREATE TABLE "core_recipe" ("id" bigint PRIMARY KEY);
+  "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::SettingSearchPath]
+        );
+        assert_eq!(res[0].span.start, 0);
+        assert_eq!(
+            res[0].messages[0],
+            ViolationMessage::Note(r#"search_path is changed before "core_recipe" is used without a schema, so it may resolve to a different schema than expected."#.into())
+        );
+
+        let bad_sql = r#"
+BEGIN;
+SET LOCAL search_path TO "app";
+ALTER TABLE "core_recipe" ADD COLUMN "name" text;
+RESET search_path;
+CREATE INDEX "core_recipe_name_idx" ON "core_recipe" ("name");
+COMMIT;
+  "#;
+        assert_eq!(
+            violations_to_kinds(&lint_sql(bad_sql)),
+            vec![
+                RuleViolationKind::SettingSearchPath,
+                RuleViolationKind::SettingSearchPath,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_qualified_names() {
+        let ok_sql = r#"
+SET search_path = "app";
+CREATE TABLE "app"."core_recipe" ("id" bigint PRIMARY KEY);
+ALTER TABLE "app"."core_recipe" ADD COLUMN "name" text;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+
+        // unqualified names before the change resolve with the original path.
+        let ok_sql = r#"
+CREATE TABLE "core_recipe" ("id" bigint PRIMARY KEY);
+SET search_path = "app";
+SET statement_timeout = '5s';
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{
+    config::RuleOptions,
+    rules::utils::{strong_lock_level, tables_created_in_transaction},
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+};
+
+use serde_json::Value;
+use squawk_parser::ast::{ObjectType, RawStmt, Stmt};
+
+const DEFAULT_MAX_LOCKING_STATEMENTS: usize = 5;
+
+/// Short description of the statement for the summary, e.g.
+/// `ALTER TABLE "core_foo"`.
+fn describe_stmt(stmt: &Stmt) -> String {
+    match stmt {
+        Stmt::AlterTableStmt(stmt) => format!(r#"ALTER TABLE "{}""#, stmt.relation.relname),
+        Stmt::IndexStmt(stmt) => format!(r#"CREATE INDEX ON "{}""#, stmt.relation.relname),
+        Stmt::RenameStmt(stmt) => match &stmt.relation {
+            Some(relation) => format!(r#"ALTER TABLE "{}" RENAME"#, relation.relname),
+            None => "RENAME".into(),
+        },
+        Stmt::DropStmt(stmt) if stmt.remove_type == ObjectType::Index => "DROP INDEX".into(),
+        Stmt::DropStmt(_) => "DROP TABLE".into(),
+        _ => "statement".into(),
+    }
+}
+
+/// Each statement that takes a strong lock can block reads and writes, and a
+/// migration with many of them is easy to underestimate in review. Reported
+/// once per file, on the statement that goes over the limit.
+#[must_use]
+pub fn too_many_locking_statements(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    assume_in_transaction: bool,
+    rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let max_locking_statements = rule_options
+        .get("max_locking_statements")
+        .and_then(Value::as_u64)
+        .map_or(DEFAULT_MAX_LOCKING_STATEMENTS, |max| {
+            usize::try_from(max).unwrap_or(usize::MAX)
+        });
+    let tables_created = tables_created_in_transaction(tree, assume_in_transaction);
+    let locking_stmts: Vec<_> = tree
+        .iter()
+        .filter_map(|raw_stmt| {
+            strong_lock_level(&raw_stmt.stmt, &tables_created).map(|level| (raw_stmt, level))
+        })
+        .collect();
+    let Some((over_limit, _)) = locking_stmts.get(max_locking_statements) else {
+        return vec![];
+    };
+    let summary = locking_stmts
+        .iter()
+        .map(|(raw_stmt, _)| describe_stmt(&raw_stmt.stmt))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut violation = RuleViolation::new(
+        RuleViolationKind::TooManyLockingStatements,
+        over_limit,
+        Some(vec![
+            ViolationMessage::Note(format!(
+                "Found {} statements that take strong locks, more than the limit of {}: {}.",
+                locking_stmts.len(),
+                max_locking_statements,
+                summary
+            )),
+            ViolationMessage::Help(
+                "Split the migration into smaller migrations so fewer locks are held at once."
+                    .into(),
+            ),
+        ]),
+    );
+    violation.lock_level = locking_stmts.iter().map(|(_, level)| *level).max();
+    vec![violation]
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql, check_sql_with_rule,
+        config::{CheckSqlConfig, RuleOptions},
+        rules::test_utils::violations_to_kinds,
+        violations::{LockLevel, RuleViolation, RuleViolationKind, ViolationMessage},
+    };
+    use serde_json::json;
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(
+            sql,
+            &RuleViolationKind::TooManyLockingStatements,
+            None,
+            false,
+        )
+        .unwrap()
+    }
+
+    fn lint_sql_with_max(sql: &str, max_locking_statements: u64) -> Vec<RuleViolation> {
+        let mut options = RuleOptions::new();
+        options.insert(
+            "max_locking_statements".into(),
+            json!(max_locking_statements),
+        );
+        let mut config = CheckSqlConfig::default();
+        config
+            .rules
+            .insert(RuleViolationKind::TooManyLockingStatements, options);
+        check_sql(sql, &config)
+            .unwrap()
+            .into_iter()
+            .filter(|v| v.kind == RuleViolationKind::TooManyLockingStatements)
+            .collect()
+    }
+
+    #[test]
+    fn test_over_default_limit() {
+        let bad_sql = r#"
+ALTER TABLE "core_a" ADD COLUMN "foo" integer;
+ALTER TABLE "core_b" ADD COLUMN "foo" integer;
+CREATE INDEX "core_c_foo_idx" ON "core_c" ("foo");
+ALTER TABLE "core_d" RENAME COLUMN "foo" TO "bar";
+DROP INDEX "core_e_foo_idx";
+ALTER TABLE "core_f" ADD COLUMN "foo" integer;
+  "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::TooManyLockingStatements]
+        );
+        assert_eq!(res[0].span.start, 225);
+        assert_eq!(res[0].lock_level, Some(LockLevel::AccessExclusive));
+        assert_eq!(
+            res[0].messages[0],
+            ViolationMessage::Note(
+                r#"Found 6 statements that take strong locks, more than the limit of 5: ALTER TABLE "core_a", ALTER TABLE "core_b", CREATE INDEX ON "core_c", ALTER TABLE "core_d" RENAME, DROP INDEX, ALTER TABLE "core_f"."#.into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_within_limit() {
+        let ok_sql = r#"
+ALTER TABLE "core_a" ADD COLUMN "foo" integer;
+CREATE INDEX CONCURRENTLY "core_a_foo_idx" ON "core_a" ("foo");
+SELECT 1;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_max_locking_statements_option() {
+        let sql = r#"
+ALTER TABLE "core_a" ADD COLUMN "foo" integer;
+CREATE INDEX "core_b_foo_idx" ON "core_b" ("foo");
+  "#;
+        assert_eq!(lint_sql_with_max(sql, 2), vec![]);
+
+        let res = lint_sql_with_max(sql, 1);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::TooManyLockingStatements]
+        );
+        assert_eq!(res[0].span.start, 47);
+    }
+
+    /// `VALIDATE CONSTRAINT` doesn't block reads or writes, so adding a
+    /// constraint as `NOT VALID` and validating it counts once.
+    #[test]
+    fn test_not_valid_and_validate_constraint() {
+        let sql = r#"
+ALTER TABLE "core_a" ADD CONSTRAINT "core_a_b_fk" FOREIGN KEY ("b_id") REFERENCES "core_b" ("id") NOT VALID;
+ALTER TABLE "core_a" VALIDATE CONSTRAINT "core_a_b_fk";
+  "#;
+        assert_eq!(lint_sql_with_max(sql, 1), vec![]);
+
+        let res = lint_sql_with_max(sql, 0);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![RuleViolationKind::TooManyLockingStatements]
+        );
+        assert_eq!(res[0].lock_level, Some(LockLevel::ShareRowExclusive));
+    }
+
+    /// Tables created in the same transaction aren't visible to other
+    /// queries, so their locks don't block anything.
+    #[test]
+    fn test_tables_created_in_transaction() {
+        let ok_sql = r#"
+BEGIN;
+CREATE TABLE "core_a" ("id" bigint);
+ALTER TABLE "core_a" ADD COLUMN "foo" integer;
+CREATE INDEX "core_a_foo_idx" ON "core_a" ("foo");
+ALTER TABLE "core_b" ADD COLUMN "foo" integer;
+COMMIT;
+  "#;
+        assert_eq!(lint_sql_with_max(ok_sql, 1), vec![]);
+    }
+}
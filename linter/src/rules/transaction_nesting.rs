@@ -1,3 +1,4 @@
+use crate::config::RuleOptions;
 use crate::versions::Version;
 use crate::violations::{RuleViolation, RuleViolationKind};
 use crate::ViolationMessage;
@@ -9,6 +10,7 @@ pub fn transaction_nesting(
     tree: &[RawStmt],
     _pg_version: Option<Version>,
     assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
 ) -> Vec<RuleViolation> {
     let mut errs = vec![];
     let mut in_explicit_transaction = false;
@@ -25,7 +27,7 @@ pub fn transaction_nesting(
                     if assume_in_transaction {
                         errs.push(RuleViolation::new(
                                 RuleViolationKind::TransactionNesting,
-                                raw_stmt.into(),
+                                raw_stmt,
                                 Some(vec![
                                     ViolationMessage::Note(
                                         "There is an existing transaction already in progress, managed by your migration tool.".into()
@@ -36,7 +38,7 @@ pub fn transaction_nesting(
                     } else if in_explicit_transaction {
                         errs.push(RuleViolation::new(
                             RuleViolationKind::TransactionNesting,
-                            raw_stmt.into(),
+                            raw_stmt,
                             None,
                         ));
                     }
@@ -46,7 +48,7 @@ pub fn transaction_nesting(
                     if assume_in_transaction {
                         errs.push(RuleViolation::new(
                                 RuleViolationKind::TransactionNesting,
-                                raw_stmt.into(),
+                                raw_stmt,
                                 Some(vec![
                                     ViolationMessage::Note(
                                         "Attempting to end the transaction that is managed by your migration tool.".into()
@@ -57,7 +59,7 @@ pub fn transaction_nesting(
                     } else if !in_explicit_transaction {
                         errs.push(RuleViolation::new(
                                 RuleViolationKind::TransactionNesting,
-                                raw_stmt.into(),
+                                raw_stmt,
                                 Some(vec![
                                     ViolationMessage::Note(
                                         "There is no transaction to COMMIT or ROLLBACK.".into()
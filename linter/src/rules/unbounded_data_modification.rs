@@ -0,0 +1,115 @@
+use crate::{
+    config::RuleOptions,
+    rules::utils::tables_created_in_transaction,
+    versions::Version,
+    violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+};
+
+use squawk_parser::ast::{RawStmt, Stmt};
+
+/// `UPDATE` and `DELETE` statements without a `WHERE` clause modify every row
+/// of the table in one transaction, locking the rows until it commits and
+/// leaving the old versions as dead rows for vacuum.
+///
+/// Whether a `WHERE` clause limits the statement to a small batch can't be
+/// told without the data, so only statements without one are reported.
+/// Tables created in the same transaction are skipped since they start empty.
+#[must_use]
+pub fn unbounded_data_modification(
+    tree: &[RawStmt],
+    _pg_version: Option<Version>,
+    assume_in_transaction: bool,
+    _rule_options: &RuleOptions,
+) -> Vec<RuleViolation> {
+    let tables_created = tables_created_in_transaction(tree, assume_in_transaction);
+    let mut errs = vec![];
+    for raw_stmt in tree {
+        let (stmt, statement) = match &raw_stmt.stmt {
+            Stmt::UpdateStmt(stmt) => (stmt, "UPDATE"),
+            Stmt::DeleteStmt(stmt) => (stmt, "DELETE"),
+            _ => continue,
+        };
+        if !stmt["whereClause"].is_null() {
+            continue;
+        }
+        let Some(table) = stmt["relation"]["relname"].as_str() else {
+            continue;
+        };
+        if tables_created.contains(table) {
+            continue;
+        }
+        errs.push(RuleViolation::new(
+            RuleViolationKind::UnboundedDataModification,
+            raw_stmt,
+            Some(vec![
+                ViolationMessage::Note(format!(
+                    r#"{statement} without a WHERE clause modifies every row of "{table}" in one transaction, locking the rows until it commits and leaving dead rows behind."#
+                )),
+                ViolationMessage::Help(
+                    "Modify the rows in batches, e.g. with a WHERE clause on a range of ids, committing between batches.".into(),
+                ),
+            ]),
+        ));
+    }
+    errs
+}
+
+#[cfg(test)]
+mod test_rules {
+    use crate::{
+        check_sql_with_rule,
+        rules::test_utils::violations_to_kinds,
+        violations::{RuleViolation, RuleViolationKind, ViolationMessage},
+    };
+
+    fn lint_sql(sql: &str) -> Vec<RuleViolation> {
+        check_sql_with_rule(
+            sql,
+            &RuleViolationKind::UnboundedDataModification,
+            None,
+            false,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_without_where_clause() {
+        let bad_sql = r#"
+UPDATE "core_recipe" SET "status" = 'draft';
+DELETE FROM "core_recipe_draft";
+  "#;
+        let res = lint_sql(bad_sql);
+        assert_eq!(
+            violations_to_kinds(&res),
+            vec![
+                RuleViolationKind::UnboundedDataModification,
+                RuleViolationKind::UnboundedDataModification,
+            ]
+        );
+        assert_eq!(
+            res[0].messages[0],
+            ViolationMessage::Note(r#"UPDATE without a WHERE clause modifies every row of "core_recipe" in one transaction, locking the rows until it commits and leaving dead rows behind."#.into())
+        );
+    }
+
+    #[test]
+    fn test_with_where_clause() {
+        let ok_sql = r#"
+UPDATE "core_recipe" SET "status" = 'draft' WHERE "id" BETWEEN 1 AND 10000;
+UPDATE "core_recipe" SET "status" = 'draft' WHERE "id" IN (SELECT "id" FROM "core_recipe" WHERE "status" IS NULL LIMIT 1000);
+DELETE FROM "core_recipe_draft" WHERE "created_at" < '2020-01-01';
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+
+    #[test]
+    fn test_table_created_in_transaction() {
+        let ok_sql = r#"
+BEGIN;
+CREATE TABLE "core_recipe" ("id" bigint, "status" text);
+UPDATE "core_recipe" SET "status" = 'draft';
+COMMIT;
+  "#;
+        assert_eq!(lint_sql(ok_sql), vec![]);
+    }
+}
@@ -1,23 +1,163 @@
+use crate::violations::LockLevel;
+use serde_json::Value;
 use squawk_parser::ast::{
-    AlterTableCmds, AlterTableDef, AlterTableType, ColumnDef, RawStmt, Stmt, TableElt,
-    TransactionStmtKind,
+    AlterTableCmd, AlterTableCmds, AlterTableDef, AlterTableType, ColumnDef, ColumnDefConstraint,
+    ConstrType, CreateStmt, ObjectType, RawStmt, Stmt, TableElt, TransactionStmtKind,
 };
 use std::collections::HashSet;
 
+/// Functions that return a different value for every row, so existing rows
+/// have to be rewritten, including common extension functions that aren't
+/// built in.
+pub const VOLATILE_FUNCTIONS: &[&str] = &[
+    "clock_timestamp",
+    "gen_random_uuid",
+    "nextval",
+    "random",
+    "timeofday",
+    "uuid_generate_v1",
+    "uuid_generate_v1mc",
+    "uuid_generate_v4",
+];
+
+/// Functions that return the same value within a statement, but can change
+/// between statements, e.g. with the time or the session's settings.
+pub const STABLE_FUNCTIONS: &[&str] = &[
+    "current_database",
+    "current_schema",
+    "current_schemas",
+    "current_setting",
+    "inet_client_addr",
+    "now",
+    "pg_backend_pid",
+    "statement_timestamp",
+    "transaction_timestamp",
+];
+
+/// The known volatile and stable functions called anywhere in an
+/// expression, e.g. `now()` in `CHECK ("expires_at" > now())`. SQL value
+/// functions like `CURRENT_TIMESTAMP` are stable too, and are named without
+/// parentheses.
+pub fn non_immutable_functions(expr: &Value) -> Vec<String> {
+    let mut names = vec![];
+    match expr {
+        Value::Object(object) => {
+            if let Some(func_name) = object
+                .get("FuncCall")
+                .and_then(|func_call| func_call["funcname"].as_array()?.last())
+                .and_then(|name| name["String"]["sval"].as_str())
+            {
+                let func_name = func_name.to_lowercase();
+                if VOLATILE_FUNCTIONS.contains(&func_name.as_str())
+                    || STABLE_FUNCTIONS.contains(&func_name.as_str())
+                {
+                    names.push(format!("{func_name}()"));
+                }
+            }
+            // e.g. `SVFOP_CURRENT_TIMESTAMP` or `SVFOP_CURRENT_TIME_N` with a
+            // precision.
+            if let Some(op) = object
+                .get("SQLValueFunction")
+                .and_then(|func| func["op"].as_str())
+            {
+                let op = op.trim_start_matches("SVFOP_");
+                names.push(op.strip_suffix("_N").unwrap_or(op).to_string());
+            }
+            for value in object.values() {
+                names.extend(non_immutable_functions(value));
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                names.extend(non_immutable_functions(value));
+            }
+        }
+        _ => {}
+    }
+    names
+}
+
+/// Whether a `REINDEX` statement uses `CONCURRENTLY`, which pg15 parses as
+/// a `concurrently` option.
+pub fn is_concurrent_reindex(stmt: &Value) -> bool {
+    stmt["params"].as_array().map_or(false, |params| {
+        params
+            .iter()
+            .any(|param| param["DefElem"]["defname"].as_str() == Some("concurrently"))
+    })
+}
+
+/// Whether the value passed to `SET` for a timeout, like `lock_timeout`,
+/// disables the timeout, e.g. `0`, `'0'`, or `'0s'`.
+pub fn is_zero_timeout(arg: &Value) -> bool {
+    let constant = &arg["A_Const"];
+    if let Some(ival) = constant.get("ival") {
+        // libpg_query omits zero values from the JSON output.
+        return ival["ival"].as_i64().unwrap_or(0) == 0;
+    }
+    if let Some(sval) = constant["sval"]["sval"].as_str() {
+        let number = sval.trim().trim_end_matches(char::is_alphabetic).trim();
+        return !number.is_empty() && number.chars().all(|c| c == '0' || c == '.');
+    }
+    false
+}
+
+/// How a statement changed the transaction state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionChange {
+    Begin,
+    Commit,
+    Rollback,
+}
+
+/// Tracks whether statements run inside a transaction, from `BEGIN` or
+/// `START TRANSACTION` until `COMMIT` or `ROLLBACK`.
+pub struct TransactionTracker {
+    inside_transaction: bool,
+}
+
+impl TransactionTracker {
+    pub fn new(assume_in_transaction: bool) -> Self {
+        Self {
+            inside_transaction: assume_in_transaction,
+        }
+    }
+
+    pub fn inside_transaction(&self) -> bool {
+        self.inside_transaction
+    }
+
+    /// Update the state for `stmt`, returning the change when it starts or
+    /// ends a transaction. Postgres ignores a `BEGIN` inside a transaction,
+    /// and a `COMMIT` or `ROLLBACK` outside of one, so they don't change
+    /// anything.
+    pub fn update(&mut self, stmt: &Stmt) -> Option<TransactionChange> {
+        let Stmt::TransactionStmt(stmt) = stmt else {
+            return None;
+        };
+        let change = match stmt.kind {
+            TransactionStmtKind::Begin | TransactionStmtKind::Start if !self.inside_transaction => {
+                TransactionChange::Begin
+            }
+            TransactionStmtKind::Commit if self.inside_transaction => TransactionChange::Commit,
+            TransactionStmtKind::Rollback if self.inside_transaction => TransactionChange::Rollback,
+            _ => return None,
+        };
+        self.inside_transaction = change == TransactionChange::Begin;
+        Some(change)
+    }
+}
+
 pub fn tables_created_in_transaction(
     tree: &[RawStmt],
     assume_in_transaction: bool,
 ) -> HashSet<String> {
     let mut created_table_names = HashSet::new();
-    let mut inside_transaction = assume_in_transaction;
+    let mut transaction = TransactionTracker::new(assume_in_transaction);
     for raw_stmt in tree {
+        transaction.update(&raw_stmt.stmt);
         match &raw_stmt.stmt {
-            Stmt::TransactionStmt(stmt) => match stmt.kind {
-                TransactionStmtKind::Begin => inside_transaction = true,
-                TransactionStmtKind::Commit => inside_transaction = false,
-                _ => continue,
-            },
-            Stmt::CreateStmt(stmt) if inside_transaction => {
+            Stmt::CreateStmt(stmt) if transaction.inside_transaction() => {
                 let stmt = &stmt.relation;
                 let table_name = &stmt.relname;
                 created_table_names.insert(table_name.clone());
@@ -28,6 +168,73 @@ pub fn tables_created_in_transaction(
     created_table_names
 }
 
+/// The lock `ALTER TABLE` takes for a subcommand, following
+/// `AlterTableGetLockLevel` in Postgres. Subcommands not listed take an
+/// `ACCESS EXCLUSIVE` lock.
+fn alter_table_cmd_lock_level(cmd: &AlterTableCmd) -> LockLevel {
+    match cmd.subtype {
+        AlterTableType::ValidateConstraint
+        | AlterTableType::ValidateConstraintRecurse
+        | AlterTableType::SetStatistics
+        | AlterTableType::SetOptions
+        | AlterTableType::ResetOptions
+        | AlterTableType::ClusterOn
+        | AlterTableType::DropCluster => LockLevel::ShareUpdateExclusive,
+        AlterTableType::EnableTrig
+        | AlterTableType::EnableAlwaysTrig
+        | AlterTableType::EnableReplicaTrig
+        | AlterTableType::DisableTrig
+        | AlterTableType::EnableTrigAll
+        | AlterTableType::DisableTrigAll
+        | AlterTableType::EnableTrigUser
+        | AlterTableType::DisableTrigUser => LockLevel::ShareRowExclusive,
+        AlterTableType::AddConstraint | AlterTableType::AddConstraintRecurse => match &cmd.def {
+            Some(AlterTableDef::Constraint(constraint))
+                if constraint.contype == ConstrType::Foreign =>
+            {
+                LockLevel::ShareRowExclusive
+            }
+            _ => LockLevel::AccessExclusive,
+        },
+        _ => LockLevel::AccessExclusive,
+    }
+}
+
+/// The lock taken by statements that take a `SHARE` lock or stronger and
+/// will queue behind long running queries, `None` for other statements and
+/// for tables created in the same transaction. `ALTER TABLE` takes the
+/// strongest lock of its subcommands, e.g. `VALIDATE CONSTRAINT` only takes a
+/// `SHARE UPDATE EXCLUSIVE` lock, which doesn't block reads or writes.
+pub fn strong_lock_level(stmt: &Stmt, tables_created: &HashSet<String>) -> Option<LockLevel> {
+    match stmt {
+        Stmt::AlterTableStmt(stmt) if !tables_created.contains(&stmt.relation.relname) => stmt
+            .cmds
+            .iter()
+            .map(|AlterTableCmds::AlterTableCmd(cmd)| alter_table_cmd_lock_level(cmd))
+            .max()
+            .filter(|level| *level >= LockLevel::Share),
+        Stmt::IndexStmt(stmt)
+            if !stmt.concurrent && !tables_created.contains(&stmt.relation.relname) =>
+        {
+            Some(LockLevel::Share)
+        }
+        Stmt::RenameStmt(stmt)
+            if stmt.relation.as_ref().map_or(false, |relation| {
+                !tables_created.contains(&relation.relname)
+            }) =>
+        {
+            Some(LockLevel::AccessExclusive)
+        }
+        Stmt::DropStmt(stmt)
+            if !stmt.concurrent
+                && matches!(stmt.remove_type, ObjectType::Table | ObjectType::Index) =>
+        {
+            Some(LockLevel::AccessExclusive)
+        }
+        _ => None,
+    }
+}
+
 pub fn columns_create_or_modified(stmt: &Stmt) -> Vec<&ColumnDef> {
     let mut columns = vec![];
     match stmt {
@@ -53,3 +260,63 @@ pub fn columns_create_or_modified(stmt: &Stmt) -> Vec<&ColumnDef> {
     }
     columns
 }
+
+lazy_static! {
+    static ref SERIAL_TYPES: HashSet<&'static str> = HashSet::from([
+        "serial",
+        "serial2",
+        "serial4",
+        "serial8",
+        "smallserial",
+        "bigserial",
+    ]);
+}
+
+/// The name of the column's type when it's a built-in type, either unqualified
+/// or in `pg_catalog`. Types in other schemas can reuse the names.
+pub fn builtin_type_name(column_def: &ColumnDef) -> Option<&str> {
+    let (type_name, schema) = column_def.type_name.names.split_last()?;
+    if !schema.iter().all(|name| name.string.sval == "pg_catalog") {
+        return None;
+    }
+    Some(type_name.string.sval.as_str())
+}
+
+/// Whether the column uses one of the `serial` pseudo-types, which create a
+/// sequence and a `DEFAULT nextval(...)` for the column.
+pub fn is_serial_column(column_def: &ColumnDef) -> bool {
+    column_def.type_name.names.last().map_or(false, |name| {
+        SERIAL_TYPES.contains(name.string.sval.as_str())
+    })
+}
+
+/// `relpersistence` for `CREATE TEMPORARY TABLE`.
+pub const TEMPORARY: &str = "t";
+
+pub fn has_primary_key(stmt: &CreateStmt) -> bool {
+    stmt.constraints
+        .iter()
+        .any(|constraint| constraint.contype == ConstrType::Primary)
+        || stmt.table_elts.iter().any(|elt| match elt {
+            TableElt::Constraint(constraint) => constraint.contype == ConstrType::Primary,
+            TableElt::ColumnDef(column_def) => {
+                column_def
+                    .constraints
+                    .iter()
+                    .any(|ColumnDefConstraint::Constraint(constraint)| {
+                        constraint.contype == ConstrType::Primary
+                    })
+            }
+            TableElt::TableLikeClause(_) => false,
+        })
+}
+
+/// Whether the table's primary key can come from somewhere else: partitions
+/// use the primary key of the partitioned table and `LIKE` can copy one.
+pub fn may_inherit_primary_key(stmt: &CreateStmt) -> bool {
+    stmt.partbound.is_some()
+        || stmt
+            .table_elts
+            .iter()
+            .any(|elt| matches!(elt, TableElt::TableLikeClause(_)))
+}
@@ -1,10 +1,16 @@
 use std::str::FromStr;
 
-use crate::RULES;
-use serde::{Deserialize, Serialize};
+use crate::baseline::{fnv1a, normalize_statement, statement_start, statement_text};
+use crate::config::CheckSqlConfig;
+use crate::rule_by_kind;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 pub use squawk_parser::ast::Span;
+use squawk_parser::ast::{ObjectType, RawStmt, Stmt};
 
-#[derive(Debug, PartialEq, Clone, Serialize, Hash, Eq, Deserialize)]
+// `remote = "Self"` derives the (de)serializers as inherent functions, so the
+// `Serialize` impl below can serialize `Custom` as a plain name.
+#[derive(Debug, PartialEq, Clone, Serialize, Hash, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(remote = "Self")]
 pub enum RuleViolationKind {
     #[serde(rename = "require-concurrent-index-creation")]
     RequireConcurrentIndexCreation,
@@ -61,16 +67,129 @@ pub enum RuleViolationKind {
     AddingRequiredField,
     #[serde(rename = "ban-concurrent-index-creation-in-transaction")]
     BanConcurrentIndexCreationInTransaction,
+    #[serde(rename = "adding-serial-column")]
+    AddingSerialColumn,
+    #[serde(rename = "require-lock-timeout")]
+    RequireLockTimeout,
+    #[serde(rename = "prefer-jsonb")]
+    PreferJsonb,
+    #[serde(rename = "setting-not-null-on-existing-column")]
+    SettingNotNullOnExistingColumn,
+    #[serde(rename = "ban-vacuum-full")]
+    BanVacuumFull,
+    #[serde(rename = "ban-cluster")]
+    BanCluster,
+    #[serde(rename = "adding-enum-value-in-transaction")]
+    AddingEnumValueInTransaction,
+    #[serde(rename = "require-if-not-exists")]
+    RequireIfNotExists,
+    #[serde(rename = "renaming-constraint")]
+    RenamingConstraint,
+    #[serde(rename = "too-many-locking-statements")]
+    TooManyLockingStatements,
+    #[serde(rename = "ban-truncate")]
+    BanTruncate,
+    #[serde(rename = "ban-disabling-statement-timeout")]
+    BanDisablingStatementTimeout,
+    #[serde(rename = "prefer-primary-key")]
+    PreferPrimaryKey,
+    #[serde(rename = "ban-money-type")]
+    BanMoneyType,
+    #[serde(rename = "adding-stored-generated-column")]
+    AddingStoredGeneratedColumn,
+    #[serde(rename = "require-validate-constraint")]
+    RequireValidateConstraint,
+    #[serde(rename = "ban-set-unlogged")]
+    BanSetUnlogged,
+    #[serde(rename = "mixing-ddl-and-dml")]
+    MixingDdlAndDml,
+    #[serde(rename = "require-concurrent-reindex")]
+    RequireConcurrentReindex,
+    #[serde(rename = "changing-object-schema")]
+    ChangingObjectSchema,
+    #[serde(rename = "duplicate-index")]
+    DuplicateIndex,
+    #[serde(rename = "ban-create-table-as")]
+    BanCreateTableAs,
+    #[serde(rename = "partition-lock-hazard")]
+    PartitionLockHazard,
+    #[serde(rename = "ban-grant-in-migration")]
+    BanGrantInMigration,
+    #[serde(rename = "naming-convention")]
+    NamingConvention,
+    #[serde(rename = "adding-column-with-domain-type")]
+    AddingColumnWithDomainType,
+    #[serde(rename = "non-immutable-check-constraint")]
+    NonImmutableCheckConstraint,
+    #[serde(rename = "ban-deprecated-type")]
+    BanDeprecatedType,
+    #[serde(rename = "require-replica-identity")]
+    RequireReplicaIdentity,
+    #[serde(rename = "unbounded-data-modification")]
+    UnboundedDataModification,
+    #[serde(rename = "ban-alter-owner")]
+    BanAlterOwner,
+    #[serde(rename = "setting-search-path")]
+    SettingSearchPath,
+    #[serde(rename = "disallowed-exclusion-constraint")]
+    DisallowedExclusionConstraint,
+    #[serde(rename = "adding-identity-column")]
+    AddingIdentityColumn,
+    #[serde(rename = "changing-storage-parameter")]
+    ChangingStorageParameter,
     // generator::new-rule-above
+    /// A rule registered by the embedder with `check_sql_with_rules`,
+    /// identified by its name, e.g. `require-created-at`.
+    #[serde(skip)]
+    Custom(String),
+}
+
+impl Serialize for RuleViolationKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Custom(name) => serializer.serialize_str(name),
+            _ => Self::serialize(self, serializer),
+        }
+    }
+}
+
+/// Only built-in rules can be deserialized, so a typo in a rule name is an
+/// error rather than a custom rule.
+impl<'de> Deserialize<'de> for RuleViolationKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::deserialize(deserializer)
+    }
+}
+
+impl RuleViolationKind {
+    /// Stable identifier for the rule, e.g. `ban-drop-column`.
+    ///
+    /// This is the same name accepted by `from_str` and `try_from`, and won't
+    /// change between releases.
+    #[must_use]
+    pub fn id(&self) -> String {
+        // every built-in variant is a unit variant with a rename, so this
+        // never fails.
+        serde_plain::to_string(self).unwrap_or_default()
+    }
+
+    /// Longer explanation of the problem the rule reports and how to fix it,
+    /// in markdown, for rules that have one.
+    #[must_use]
+    pub fn explanation(&self) -> Option<&'static str> {
+        rule_by_kind(self).and_then(|rule| rule.explanation)
+    }
+
+    /// Link to the documentation for the rule on the Squawk website.
+    #[must_use]
+    pub fn doc_url(&self) -> String {
+        format!("https://squawkhq.com/docs/{}", self.id())
+    }
 }
 
 impl std::fmt::Display for RuleViolationKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            serde_plain::to_string(self).map_err(|_| std::fmt::Error)?
-        )
+        write!(f, "{}", self.id())
     }
 }
 
@@ -79,6 +198,14 @@ pub struct UnknownRuleName {
     val: String,
 }
 
+impl UnknownRuleName {
+    /// The name that didn't match a rule, e.g. a typo in a config file.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.val
+    }
+}
+
 impl std::fmt::Display for UnknownRuleName {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "invalid rule name {}", self.val)
@@ -99,36 +226,406 @@ impl std::convert::TryFrom<&str> for RuleViolationKind {
     }
 }
 
+/// How seriously a violation should be treated by callers, e.g. a CI wrapper
+/// can fail on errors while only printing warnings.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Reminders that don't indicate a problem on their own.
+    Info,
+    Warning,
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let val = match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        };
+        write!(f, "{val}")
+    }
+}
+
+/// The table lock a statement takes in Postgres, ordered from the weakest to
+/// the strongest lock.
+///
+/// See <https://www.postgresql.org/docs/current/explicit-locking.html#LOCKING-TABLES>
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockLevel {
+    AccessShare,
+    RowShare,
+    RowExclusive,
+    ShareUpdateExclusive,
+    Share,
+    ShareRowExclusive,
+    Exclusive,
+    AccessExclusive,
+}
+
+impl std::fmt::Display for LockLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let val = match self {
+            Self::AccessShare => "ACCESS SHARE",
+            Self::RowShare => "ROW SHARE",
+            Self::RowExclusive => "ROW EXCLUSIVE",
+            Self::ShareUpdateExclusive => "SHARE UPDATE EXCLUSIVE",
+            Self::Share => "SHARE",
+            Self::ShareRowExclusive => "SHARE ROW EXCLUSIVE",
+            Self::Exclusive => "EXCLUSIVE",
+            Self::AccessExclusive => "ACCESS EXCLUSIVE",
+        };
+        write!(f, "{val}")
+    }
+}
+
+/// The kind of statement a violation was reported for, so violations can be
+/// grouped by statement without parsing the SQL again.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementKind {
+    AlterTable,
+    AlterType,
+    Cluster,
+    CreateIndex,
+    CreateTable,
+    CreateTableAs,
+    Delete,
+    Drop,
+    DropDatabase,
+    DropIndex,
+    DropTable,
+    Grant,
+    Insert,
+    Reindex,
+    Rename,
+    Select,
+    Set,
+    Transaction,
+    Truncate,
+    Update,
+    Vacuum,
+    /// Statements without a kind of their own, and statements that couldn't
+    /// be parsed.
+    Other,
+}
+
+impl From<&Stmt> for StatementKind {
+    fn from(stmt: &Stmt) -> Self {
+        match stmt {
+            Stmt::AlterTableStmt(_) => Self::AlterTable,
+            Stmt::AlterEnumStmt(_) => Self::AlterType,
+            Stmt::ClusterStmt(_) => Self::Cluster,
+            Stmt::IndexStmt(_) => Self::CreateIndex,
+            Stmt::CreateStmt(_) => Self::CreateTable,
+            Stmt::CreateTableAsStmt(_) => Self::CreateTableAs,
+            Stmt::DeleteStmt(_) => Self::Delete,
+            Stmt::DropStmt(stmt) => match stmt.remove_type {
+                ObjectType::Table => Self::DropTable,
+                ObjectType::Index => Self::DropIndex,
+                _ => Self::Drop,
+            },
+            Stmt::DropdbStmt(_) => Self::DropDatabase,
+            Stmt::GrantStmt(_) | Stmt::GrantRoleStmt(_) => Self::Grant,
+            Stmt::InsertStmt(_) => Self::Insert,
+            Stmt::ReindexStmt(_) => Self::Reindex,
+            Stmt::RenameStmt(_) => Self::Rename,
+            Stmt::SelectStmt(_) => Self::Select,
+            Stmt::VariableSetStmt(_) => Self::Set,
+            Stmt::TransactionStmt(_) => Self::Transaction,
+            Stmt::TruncateStmt(_) => Self::Truncate,
+            Stmt::UpdateStmt(_) => Self::Update,
+            Stmt::VacuumStmt(_) => Self::Vacuum,
+            _ => Self::Other,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Serialize, Clone)]
 pub enum ViolationMessage {
     Note(String),
     Help(String),
+    /// Link to documentation about the problem, e.g. the Postgres docs for
+    /// the statement.
+    Url(String),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct RuleViolation {
     pub kind: RuleViolationKind,
+    pub severity: Severity,
+    /// The table lock the statement takes, when the rule knows it.
+    pub lock_level: Option<LockLevel>,
     pub span: Span,
+    /// The kind of the statement at `span`.
+    pub statement_kind: StatementKind,
     pub messages: Vec<ViolationMessage>,
+    /// The text of the statement, including the whitespace and comments
+    /// before it, when `CheckSqlConfig::include_snippets` is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
 }
 
 impl RuleViolation {
     #[must_use]
     pub fn new(
         kind: RuleViolationKind,
-        span: Span,
+        raw_stmt: &RawStmt,
         messages: Option<Vec<ViolationMessage>>,
     ) -> Self {
-        let messages = messages.unwrap_or_else(|| {
-            RULES
-                .iter()
-                .find(|r| r.name == kind)
-                .map_or_else(Vec::new, |x| x.messages.clone())
-        });
+        let rule = rule_by_kind(&kind);
+        let rule_messages = rule.map_or(&[][..], |x| x.messages.as_slice());
+        let messages = match messages {
+            // custom messages still get the rule's doc links.
+            Some(mut messages) => {
+                messages.extend(
+                    rule_messages
+                        .iter()
+                        .filter(|x| matches!(x, ViolationMessage::Url(_)))
+                        .cloned(),
+                );
+                messages
+            }
+            None => rule_messages.to_vec(),
+        };
+        let severity = rule.map_or(Severity::Warning, |x| x.severity);
+        let lock_level = rule.and_then(|x| x.lock_level);
         Self {
             kind,
-            span,
+            severity,
+            lock_level,
+            span: raw_stmt.into(),
+            statement_kind: (&raw_stmt.stmt).into(),
             messages,
+            snippet: None,
+        }
+    }
+
+    /// 1-indexed line and column of the violation in `sql`.
+    ///
+    /// The span from Postgres includes the whitespace and comments preceding
+    /// the statement, so we skip them to point at the statement itself.
+    /// Columns are counted in characters rather than bytes and `\r\n` is
+    /// treated as one line break.
+    #[must_use]
+    pub fn location(&self, sql: &str) -> (usize, usize) {
+        let start = statement_start(sql, self);
+        let before = &sql[..start];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+        let column = before[line_start..].chars().count() + 1;
+        (line, column)
+    }
+
+    /// Whether the violation should be treated as a failure, using the
+    /// rule's severity and `CheckSqlConfig::warnings_as_errors`. Info
+    /// violations are never errors.
+    #[must_use]
+    pub fn is_error(&self, config: &CheckSqlConfig) -> bool {
+        match self.severity {
+            Severity::Error => true,
+            Severity::Warning => config.warnings_as_errors,
+            Severity::Info => false,
+        }
+    }
+
+    /// Hash of the rule and the statement's SQL with comments removed,
+    /// whitespace collapsed and keywords lowercased, to identify the same
+    /// finding across runs and tools, e.g. in a `Baseline`. Unlike the span it
+    /// doesn't change when statements are added before it or when the
+    /// statement is reformatted.
+    #[must_use]
+    pub fn fingerprint(&self, sql: &str) -> String {
+        let normalized = normalize_statement(statement_text(sql, self));
+        format!(
+            "{:016x}",
+            fnv1a(&format!("{}:{}", self.kind.id(), normalized))
+        )
+    }
+}
+
+#[cfg(test)]
+mod test_violations {
+    use crate::{
+        check_sql_with_rule,
+        config::CheckSqlConfig,
+        violations::{LockLevel, RuleViolationKind, Severity, ViolationMessage},
+        RULES,
+    };
+
+    fn locations(sql: &str) -> Vec<(usize, usize)> {
+        check_sql_with_rule(sql, &RuleViolationKind::BanDropColumn, None, false)
+            .unwrap()
+            .iter()
+            .map(|v| v.location(sql))
+            .collect()
+    }
+
+    #[test]
+    fn test_location() {
+        let sql = r#"SELECT 1;
+  ALTER TABLE "foo" DROP COLUMN "bar";"#;
+        assert_eq!(locations(sql), vec![(2, 3)]);
+    }
+
+    #[test]
+    fn test_location_first_statement() {
+        let sql = r#"ALTER TABLE "foo" DROP COLUMN "bar";"#;
+        assert_eq!(locations(sql), vec![(1, 1)]);
+    }
+
+    /// Columns shouldn't drift when the line contains multibyte characters.
+    #[test]
+    fn test_location_multibyte() {
+        let sql = r#"SELECT 'é'; ALTER TABLE "café" DROP COLUMN "crème";"#;
+        assert_eq!(locations(sql), vec![(1, 13)]);
+    }
+
+    /// Comments before the statement are part of its span.
+    #[test]
+    fn test_location_skips_comments() {
+        let sql = r#"SELECT 1;
+-- drop bar
+/* it isn't used anymore */
+  ALTER TABLE "foo" DROP COLUMN "bar";"#;
+        assert_eq!(locations(sql), vec![(4, 3)]);
+    }
+
+    #[test]
+    fn test_location_crlf() {
+        let sql = "SELECT 1;\r\n\r\nALTER TABLE \"café\" DROP COLUMN \"crème\";\r\nSELECT 'é'; ALTER TABLE \"foo\" DROP COLUMN \"bar\";\r\n";
+        assert_eq!(locations(sql), vec![(3, 1), (4, 13)]);
+    }
+
+    #[test]
+    fn test_lock_level() {
+        let sql = r#"ALTER TABLE "foo" ALTER COLUMN "bar" TYPE bigint;"#;
+        let res =
+            check_sql_with_rule(sql, &RuleViolationKind::ChangingColumnType, None, false).unwrap();
+        assert_eq!(res[0].lock_level, Some(LockLevel::AccessExclusive));
+
+        let sql = r#"ALTER TABLE "foo" DROP COLUMN "bar";"#;
+        let res = check_sql_with_rule(sql, &RuleViolationKind::BanDropColumn, None, false).unwrap();
+        assert_eq!(res[0].lock_level, None);
+
+        assert!(LockLevel::ShareRowExclusive < LockLevel::AccessExclusive);
+        assert_eq!(
+            LockLevel::ShareRowExclusive.to_string(),
+            "SHARE ROW EXCLUSIVE"
+        );
+    }
+
+    #[test]
+    fn test_doc_url() {
+        let sql = r#"
+BEGIN;
+ALTER TABLE "app_email" ADD CONSTRAINT "fk_user" FOREIGN KEY (user_id) REFERENCES "app_user" (id) NOT VALID;
+ALTER TABLE "app_email" VALIDATE CONSTRAINT "fk_user";
+COMMIT;
+"#;
+        let res = check_sql_with_rule(
+            sql,
+            &RuleViolationKind::ConstraintMissingNotValid,
+            None,
+            false,
+        )
+        .unwrap();
+        // custom messages keep the rule's doc link.
+        assert_eq!(
+            serde_json::to_value(&res[0].messages).unwrap(),
+            serde_json::json!([
+                { "Note": "Using NOT VALID and VALIDATE CONSTRAINT in the same transaction will block all reads while the constraint is validated." },
+                { "Help": "Add constraint as NOT VALID in one transaction and VALIDATE CONSTRAINT in a separate transaction." },
+                { "Url": "https://www.postgresql.org/docs/current/sql-altertable.html" },
+            ])
+        );
+
+        for rule in RULES.iter().filter(|r| r.lock_level.is_some()) {
+            assert!(
+                rule.messages
+                    .iter()
+                    .any(|m| matches!(m, ViolationMessage::Url(_))),
+                "{}",
+                rule.name
+            );
         }
     }
+
+    #[test]
+    fn test_is_error() {
+        let sql = r#"ALTER TABLE "foo" ADD COLUMN "bar" integer NOT NULL;"#;
+        let res =
+            check_sql_with_rule(sql, &RuleViolationKind::AddingRequiredField, None, false).unwrap();
+        assert_eq!(res[0].severity, Severity::Warning);
+        let mut config = CheckSqlConfig::default();
+        assert!(!res[0].is_error(&config));
+        config.warnings_as_errors = true;
+        assert!(res[0].is_error(&config));
+
+        let sql = r#"ALTER TABLE "foo" DROP COLUMN "bar";"#;
+        let res = check_sql_with_rule(sql, &RuleViolationKind::BanDropColumn, None, false).unwrap();
+        assert_eq!(res[0].severity, Severity::Error);
+        assert!(res[0].is_error(&CheckSqlConfig::default()));
+
+        let sql = r#"
+CREATE INDEX CONCURRENTLY "foo_bar_idx" ON "foo" ("bar");
+CREATE INDEX CONCURRENTLY "foo_bar_idx2" ON "foo" ("bar");
+"#;
+        let res =
+            check_sql_with_rule(sql, &RuleViolationKind::DuplicateIndex, None, false).unwrap();
+        assert_eq!(res[0].severity, Severity::Info);
+        assert!(!res[0].is_error(&config));
+    }
+
+    fn fingerprints(sql: &str, kind: &RuleViolationKind) -> Vec<String> {
+        check_sql_with_rule(sql, kind, None, false)
+            .unwrap()
+            .iter()
+            .map(|v| v.fingerprint(sql))
+            .collect()
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let sql = r#"ALTER TABLE "foo" DROP COLUMN "bar";"#;
+        let fingerprint = fingerprints(sql, &RuleViolationKind::BanDropColumn);
+        assert_eq!(fingerprint.len(), 1);
+
+        let moved_sql = r#"
+-- drop the column
+SELECT 1;
+
+ALTER TABLE "foo"
+    DROP COLUMN   "bar";
+"#;
+        assert_eq!(
+            fingerprints(moved_sql, &RuleViolationKind::BanDropColumn),
+            fingerprint
+        );
+
+        let reformatted_sql = r#"alter table foo drop column bar;"#;
+        assert_eq!(
+            fingerprints(reformatted_sql, &RuleViolationKind::BanDropColumn),
+            fingerprint
+        );
+
+        let other_sql = r#"ALTER TABLE "foo" DROP COLUMN "baz";"#;
+        assert_ne!(
+            fingerprints(other_sql, &RuleViolationKind::BanDropColumn),
+            fingerprint
+        );
+    }
+
+    /// Different rules reported for the same statement are different findings.
+    #[test]
+    fn test_fingerprint_includes_rule() {
+        let sql = r#"ALTER TABLE "foo" DROP COLUMN "bar";"#;
+        let drop_column = fingerprints(sql, &RuleViolationKind::BanDropColumn);
+        let lock_timeout = fingerprints(sql, &RuleViolationKind::RequireLockTimeout);
+        assert_eq!(drop_column.len(), 1);
+        assert_eq!(lock_timeout.len(), 1);
+        assert_ne!(drop_column, lock_timeout);
+    }
 }
@@ -30,12 +30,34 @@ pub enum TransactionStmtKind {
 pub struct TransactionStmt {
     pub kind: TransactionStmtKind,
 }
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Span {
     pub start: i32,
     pub len: Option<i32>,
 }
 
+impl Span {
+    /// Byte offset just past the statement, `None` when it runs to the end of
+    /// the SQL.
+    #[must_use]
+    pub fn end(&self) -> Option<i32> {
+        self.len.map(|len| self.start + len)
+    }
+}
+
+/// Serialized as `{"start": .., "end": .., "len": ..}` byte offsets, with
+/// `end` and `len` `null` when the statement runs to the end of the SQL.
+impl Serialize for Span {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Span", 3)?;
+        state.serialize_field("start", &self.start)?;
+        state.serialize_field("end", &self.end())?;
+        state.serialize_field("len", &self.len)?;
+        state.end()
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct RawStmt {
     pub stmt: Stmt,
@@ -285,9 +307,21 @@ pub struct IndexStmt {
     /// just do nothing if index already exists?
     #[serde(default)]
     pub if_not_exists: bool,
+    /// additional columns to store in the index, from `INCLUDE (...)`
+    #[serde(rename = "indexIncludingParams", default)]
+    pub index_including_params: Vec<IndexParams>,
+    /// storage parameters, from `WITH (...)`
+    #[serde(default)]
+    pub options: Option<Value>,
+    /// is a unique index `NULLS NOT DISTINCT`?
+    #[serde(default)]
+    pub nulls_not_distinct: bool,
     /// tablespace, or NULL for default
     #[serde(rename = "tableSpace")]
     pub table_space: Option<String>,
+    /// qualification (partial-index predicate)
+    #[serde(rename = "whereClause")]
+    pub where_clause: Option<Value>,
 }
 
 /// When a command can act on several kinds of objects with only one
@@ -564,6 +598,8 @@ pub enum AlterTableType {
     AttachPartition,
     #[serde(rename = "AT_DetachPartition")]
     DetachPartition,
+    #[serde(rename = "AT_DetachPartitionFinalize")]
+    DetachPartitionFinalize,
     #[serde(rename = "AT_AddIdentity")]
     AddIdentity,
     #[serde(rename = "AT_SetIdentity")]
@@ -639,7 +675,6 @@ pub struct ColumnDef {
     // char		identity;		/* attidentity setting */
     // RangeVar   *identitySequence; /* to store identity sequence name for ALTER
     // 							   * TABLE ... ADD COLUMN */
-    // CollateClause *collClause;	/* untransformed COLLATE spec, if any */
     // Oid			collOid;		/* collation OID (InvalidOid if not set) */
     // List	   *fdwoptions;		/* per-column FDW options */
     pub colname: Option<String>,
@@ -647,6 +682,9 @@ pub struct ColumnDef {
     pub type_name: TypeName,
     #[serde(default)]
     pub constraints: Vec<ColumnDefConstraint>,
+    /// untransformed COLLATE spec, if any
+    #[serde(rename = "collClause", skip_serializing_if = "Option::is_none")]
+    pub coll_clause: Option<Value>,
     /// column has local (non-inherited) def'n
     #[serde(default)]
     pub is_local: bool,
@@ -856,10 +894,16 @@ pub struct DropStmt {
     pub missing_ok: bool,
     #[serde(rename = "removeType")]
     pub remove_type: ObjectType,
+    /// names of the objects to drop, e.g. a `List` of name parts for tables
+    /// and indexes
+    #[serde(default)]
+    pub objects: Vec<Value>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct StmtRoot {
+    /// missing when the query only contains whitespace or comments
+    #[serde(default)]
     pub stmts: Vec<RawStmt>,
 }
 
@@ -877,6 +921,7 @@ pub enum Stmt {
     InsertStmt(Value),
     UpdateStmt(Value),
     DeleteStmt(Value),
+    MergeStmt(Value),
     CreateSchemaStmt(Value),
     AlterDomainStmt(Value),
     GrantStmt(Value),
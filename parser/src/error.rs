@@ -7,7 +7,24 @@ pub enum PgQueryError {
     // The other `lineo` and `filename` fields are just references to the Postgres parser code.
     //
     // https://cs.github.com/pganalyze/libpg_query/blob/4b30b03cb3944f01d4807ee89532549ccf115a44/pg_query.h?q=PgQueryError#L6-L13
-    PgParseError(Option<String>),
+    PgParseError {
+        message: Option<String>,
+        /// Byte offset into the query where Postgres stopped parsing, if it
+        /// reported one.
+        offset: Option<usize>,
+    },
+}
+
+impl PgQueryError {
+    /// Byte offset into the query of a parse error, e.g. to point an editor
+    /// diagnostic at the failure rather than the whole file.
+    #[must_use]
+    pub fn offset(&self) -> Option<usize> {
+        match *self {
+            Self::PgParseError { offset, .. } => offset,
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for PgQueryError {
@@ -22,8 +39,8 @@ impl std::fmt::Display for PgQueryError {
                 "Squawk schema failed to parse Postgres response. This indicates a bug with Squawk. Please report this error to https://github.com/sbdchd/squawk. Schema error: {err}"
             ),
             Self::QueryToCString => write!(f, "Could not encode query into CString"),
-            Self::PgParseError(ref err) => {
-                if let Some(err) = err {
+            Self::PgParseError { ref message, .. } => {
+                if let Some(err) = message {
                     write!(f, "Postgres failed to parse query: {err}")
                 } else {
                     write!(f, "Postgres failed to parse query.")
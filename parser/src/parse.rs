@@ -1,10 +1,11 @@
-use crate::ast::{RawStmt, StmtRoot};
+use crate::ast::{RawStmt, Span, StmtRoot};
 use crate::error::PgQueryError;
 use libpg_query::{pg_query_free_parse_result, pg_query_parse};
 use serde::Deserialize;
 use serde_json::Value;
+use std::convert::TryFrom;
 use std::ffi::{CStr, CString};
-use std::os::raw::c_char;
+use std::os::raw::{c_char, c_int};
 
 fn parse_str_or_none(str_ptr: *mut c_char) -> Option<String> {
     if str_ptr.is_null() {
@@ -14,6 +15,18 @@ fn parse_str_or_none(str_ptr: *mut c_char) -> Option<String> {
     }
 }
 
+/// Postgres reports where parsing failed as a 1-indexed character position,
+/// or 0 when it doesn't know.
+fn cursor_offset(query: &str, cursorpos: c_int) -> Option<usize> {
+    let pos = usize::try_from(cursorpos).ok()?.checked_sub(1)?;
+    Some(
+        query
+            .char_indices()
+            .nth(pos)
+            .map_or(query.len(), |(idx, _)| idx),
+    )
+}
+
 fn parse_sql_query_base<'a, T>(query: &'a str) -> Result<T, PgQueryError>
 where
     T: Deserialize<'a>,
@@ -24,7 +37,10 @@ where
     if !pg_parse_result.error.is_null() {
         unsafe {
             let err = *pg_parse_result.error;
-            return Err(PgQueryError::PgParseError(parse_str_or_none(err.message)));
+            return Err(PgQueryError::PgParseError {
+                message: parse_str_or_none(err.message),
+                offset: cursor_offset(query, err.cursorpos),
+            });
         }
     }
 
@@ -53,6 +69,151 @@ pub fn parse_sql_query(query: &str) -> Result<Vec<RawStmt>, PgQueryError> {
     Ok(parsed.stmts)
 }
 
+/// A statement skipped by `parse_sql_query_with_recovery` because Postgres
+/// couldn't parse it.
+#[derive(Debug, PartialEq)]
+pub struct SkippedStmt {
+    pub span: Span,
+    pub error: PgQueryError,
+}
+
+/// Find the end of a dollar quote tag, e.g. `$$` or `$body$`, starting at
+/// `start`.
+fn dollar_quote_tag_end(bytes: &[u8], start: usize) -> Option<usize> {
+    // `$1` is a parameter and `foo$bar` is an identifier, not a dollar quote.
+    if start > 0 && (bytes[start - 1].is_ascii_alphanumeric() || bytes[start - 1] == b'_') {
+        return None;
+    }
+    let mut idx = start + 1;
+    while idx < bytes.len() && (bytes[idx].is_ascii_alphanumeric() || bytes[idx] == b'_') {
+        if idx == start + 1 && bytes[idx].is_ascii_digit() {
+            return None;
+        }
+        idx += 1;
+    }
+    if bytes.get(idx) == Some(&b'$') {
+        Some(idx + 1)
+    } else {
+        None
+    }
+}
+
+/// Split `query` into the byte ranges of its statements.
+///
+/// Statements end at semicolons outside of strings, quoted identifiers, and
+/// comments. Each range includes its semicolon so the spans match the ones
+/// Postgres gives when parsing the whole query. SQL function bodies using
+/// `BEGIN ATOMIC` aren't supported and will be split on their inner
+/// semicolons.
+fn split_statements(query: &str) -> Vec<(usize, usize)> {
+    let bytes = query.as_bytes();
+    let mut ranges = vec![];
+    let mut start = 0;
+    let mut idx = 0;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            quote @ (b'\'' | b'"') => {
+                let escapes =
+                    quote == b'\'' && idx > 0 && (bytes[idx - 1] == b'E' || bytes[idx - 1] == b'e');
+                idx += 1;
+                while idx < bytes.len() && bytes[idx] != quote {
+                    if escapes && bytes[idx] == b'\\' {
+                        idx += 1;
+                    }
+                    idx += 1;
+                }
+                // doubled quotes, e.g. 'it''s', are handled as two strings.
+                idx += 1;
+            }
+            b'-' if bytes.get(idx + 1) == Some(&b'-') => {
+                while idx < bytes.len() && bytes[idx] != b'\n' {
+                    idx += 1;
+                }
+            }
+            b'/' if bytes.get(idx + 1) == Some(&b'*') => {
+                // block comments can be nested.
+                let mut depth = 0;
+                while idx < bytes.len() {
+                    if bytes[idx] == b'/' && bytes.get(idx + 1) == Some(&b'*') {
+                        depth += 1;
+                        idx += 2;
+                    } else if bytes[idx] == b'*' && bytes.get(idx + 1) == Some(&b'/') {
+                        depth -= 1;
+                        idx += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    } else {
+                        idx += 1;
+                    }
+                }
+            }
+            b'$' => {
+                if let Some(tag_end) = dollar_quote_tag_end(bytes, idx) {
+                    let tag = &query[idx..tag_end];
+                    idx = query[tag_end..]
+                        .find(tag)
+                        .map_or(bytes.len(), |pos| tag_end + pos + tag.len());
+                } else {
+                    idx += 1;
+                }
+            }
+            b';' => {
+                ranges.push((start, idx + 1));
+                idx += 1;
+                start = idx;
+            }
+            _ => idx += 1,
+        }
+    }
+    if start < bytes.len() {
+        ranges.push((start, bytes.len()));
+    }
+    ranges
+}
+
+/// Parse `query`, skipping any statements Postgres can't parse instead of
+/// failing the entire query.
+pub fn parse_sql_query_with_recovery(query: &str) -> (Vec<RawStmt>, Vec<SkippedStmt>) {
+    // avoid splitting the query in the common case.
+    if let Ok(stmts) = parse_sql_query(query) {
+        return (stmts, vec![]);
+    }
+    let mut stmts = vec![];
+    let mut skipped = vec![];
+    for (start, end) in split_statements(query) {
+        let offset = i32::try_from(start).unwrap_or(i32::MAX);
+        match parse_sql_query(&query[start..end]) {
+            Ok(chunk_stmts) => {
+                stmts.extend(chunk_stmts.into_iter().map(|mut stmt| {
+                    stmt.stmt_location += offset;
+                    stmt
+                }));
+            }
+            Err(error) => {
+                // the offset is from the start of the statement.
+                let error = match error {
+                    PgQueryError::PgParseError { message, offset } => PgQueryError::PgParseError {
+                        message,
+                        offset: offset.map(|offset| offset + start),
+                    },
+                    error => error,
+                };
+                let len = if query[..end].ends_with(';') {
+                    i32::try_from(end - 1 - start).ok()
+                } else {
+                    None
+                };
+                skipped.push(SkippedStmt {
+                    span: Span { start: offset, len },
+                    error,
+                });
+            }
+        }
+    }
+    (stmts, skipped)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -66,6 +227,57 @@ mod tests {
         assert_debug_snapshot!(res);
     }
 
+    #[test]
+    fn test_split_statements() {
+        let sql = r#"SELECT ';'; SELECT "a;b";
+-- comment;
+SELECT $$ ; $$, $body$ ; $body$, E'\';' /* nested /* ; */ ; */;
+SELECT 1"#;
+        let ranges: Vec<&str> = split_statements(sql)
+            .into_iter()
+            .map(|(start, end)| &sql[start..end])
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![
+                "SELECT ';';",
+                r#" SELECT "a;b";"#,
+                "\n-- comment;\nSELECT $$ ; $$, $body$ ; $body$, E'\\';' /* nested /* ; */ ; */;",
+                "\nSELECT 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_sql_query_with_recovery() {
+        let sql = "SELECT 1;\nSELECT * FROM;\nALTER TABLE foo ADD COLUMN bar integer";
+        let (stmts, skipped) = parse_sql_query_with_recovery(sql);
+        let spans: Vec<Span> = stmts.iter().map(RawStmt::span).collect();
+        assert_eq!(
+            spans,
+            vec![
+                Span {
+                    start: 0,
+                    len: Some(8)
+                },
+                Span {
+                    start: 24,
+                    len: None
+                },
+            ]
+        );
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(
+            skipped[0].span,
+            Span {
+                start: 9,
+                len: Some(14)
+            }
+        );
+        // the offset of the `;`, from the start of the query.
+        assert_eq!(skipped[0].error.offset(), Some(23));
+    }
+
     #[test]
     fn test_span_with_indent() {
         // NOTE: the span information for these starts at 0 even though the SQL
@@ -50,6 +50,7 @@ pub use {{RuleNameSnake}}::*;`,
           pattern: /\/\/\sgenerator::new-rule-above/,
           template: `SquawkRule {
         name: RuleViolationKind::{{RuleNamePascal}},
+        severity: Severity::Warning,
         func: {{RuleNameSnake}},
         messages: vec![
             ViolationMessage::Note(